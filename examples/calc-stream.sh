@@ -0,0 +1,14 @@
+#!/bin/sh
+
+# streaming plugin answering every search as the user types, configured as
+#   plugins:
+#     calc:
+#       exec:   ["~/.config/rmenu/plugins/calc-stream.sh"]
+#       stream: true
+# each search arrives as one line on stdin and every answer ends w/ an empty line
+while read -r query; do
+  if [ -n "$query" ] && result=$(echo "$query" | bc -l 2>/dev/null) && [ -n "$result" ]; then
+    rmenu-build entry -n "$result" -c "$query" -a "`rmenu-build action -m echo "$result"`"
+  fi
+  echo
+done
@@ -52,11 +52,14 @@ rustPlatform.buildRustPackage rec {
     mv $out/plugins/rmenu-build $out/bin/rmenu-build
 
     # fix plugin names
-    # desktop  network  pactl-audio.sh  powermenu.sh  run  window
+    # clipboard  desktop  emoji  network  pactl-audio.sh  power  powermenu.sh  quick-adjust.sh  run  window
     mv $out/plugins/run $out/plugins/rmenu-run
     mv $out/plugins/desktop $out/plugins/rmenu-desktop
     mv $out/plugins/network $out/plugins/rmenu-network
     mv $out/plugins/window $out/plugins/rmenu-window
+    mv $out/plugins/emoji $out/plugins/rmenu-emoji
+    mv $out/plugins/clipboard $out/plugins/rmenu-clipboard
+    mv $out/plugins/power $out/plugins/rmenu-power
 
     # fix config and theme
     mkdir -p $out/share/rmenu
@@ -0,0 +1,44 @@
+#!/bin/sh
+#
+# Quick-adjust entries for volume and brightness. The actions are built w/
+# `rmenu-build action -k` so the menu stays open and the entries refresh
+# after every adjustment.
+
+STEP=${RMENU_ADJUST_STEP:-5}
+SINK="@DEFAULT_SINK@"
+
+#: desc => print current volume percentage of the default sink
+volume() {
+  pactl get-sink-volume "$SINK" | grep -o '[0-9]*%' | head -n1
+}
+
+#: desc => print current brightness percentage of the main backlight
+brightness() {
+  brightnessctl -m | cut -d ',' -f4
+}
+
+#: desc => generate adjustment entry
+#: usage => $icon $name $cmd
+adjust() {
+  rmenu-build entry -n "$2" -I "$1" -a "`rmenu-build action -k "$3"`"
+}
+
+#: desc => generate volume entries when pulseaudio is available
+volume_entries() {
+  command -v pactl >/dev/null || return
+  level=`volume`
+  adjust "🔊" "Volume Up ($level)"   "pactl set-sink-volume $SINK +$STEP%"
+  adjust "🔉" "Volume Down ($level)" "pactl set-sink-volume $SINK -$STEP%"
+  adjust "🔇" "Toggle Mute"          "pactl set-sink-mute $SINK toggle"
+}
+
+#: desc => generate brightness entries when brightnessctl is available
+brightness_entries() {
+  command -v brightnessctl >/dev/null || return
+  level=`brightness`
+  adjust "🔆" "Brightness Up ($level)"   "brightnessctl set +$STEP%"
+  adjust "🔅" "Brightness Down ($level)" "brightnessctl set $STEP%-"
+}
+
+volume_entries
+brightness_entries
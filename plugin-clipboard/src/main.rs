@@ -0,0 +1,221 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Parser, Subcommand};
+use rmenu_plugin::{self_exe, Action, Entry, EntryWriter, Method};
+
+static CLIPHIST: &'static str = "cliphist";
+static WL_COPY: &'static str = "wl-copy";
+static XDG_CACHE: &'static str = "XDG_CACHE_HOME";
+static HOME: &'static str = "HOME";
+
+/// Wrapper of the Preview Cliphist Lists for Binary Items
+static BINARY_PREFIX: &'static str = "[[ binary data ";
+static BINARY_SUFFIX: &'static str = " ]]";
+
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    /// List the Clipboard History (Newest First)
+    List,
+    /// Copy the History Item back into the Clipboard
+    Copy { id: String },
+    /// Delete the History Item
+    Delete { id: String },
+}
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+#[command(propagate_version = true)]
+pub struct Cli {
+    /// Skip Decoding Images into Icon Previews
+    #[arg(long)]
+    no_previews: bool,
+    #[clap(subcommand)]
+    command: Option<Commands>,
+}
+
+/// Image Details Listed in the Preview of Binary Items
+struct Image<'a> {
+    amount: &'a str,
+    unit: &'a str,
+    format: &'a str,
+    dimensions: &'a str,
+}
+
+/// Clipboard History Item Listed by Cliphist
+struct Item {
+    id: String,
+    preview: String,
+}
+
+impl Item {
+    /// Parse Binary Item Previews like `[[ binary data 24 KiB png 640x480 ]]`
+    fn image(&self) -> Option<Image> {
+        let info = self
+            .preview
+            .strip_prefix(BINARY_PREFIX)?
+            .strip_suffix(BINARY_SUFFIX)?;
+        let words: Vec<&str> = info.split_whitespace().collect();
+        match words[..] {
+            [amount, unit, format, dimensions] if dimensions.contains('x') => Some(Image {
+                amount,
+                unit,
+                format,
+                dimensions,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Run the Cliphist Command on the History Item w/ the Specified ID
+fn cliphist(command: &str, id: &str) -> Result<Vec<u8>> {
+    let mut child = Command::new(CLIPHIST)
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("Cliphist Failed to Execute")?;
+    // cliphist only reads the id preceding the tab of a listed line
+    let mut stdin = child.stdin.take().expect("Failed to Open Cliphist Stdin");
+    writeln!(stdin, "{id}\t")?;
+    drop(stdin);
+    let out = child.wait_with_output()?;
+    if !out.status.success() {
+        return Err(anyhow!("Invalid Cliphist Status: {:?}", out.status));
+    }
+    Ok(out.stdout)
+}
+
+/// Retrieve the Clipboard History Items
+fn history() -> Result<Vec<Item>> {
+    let out = Command::new(CLIPHIST)
+        .arg("list")
+        .output()
+        .context("Cliphist Failed to Execute")?;
+    if !out.status.success() {
+        return Err(anyhow!("Invalid Cliphist Status: {:?}", out.status));
+    }
+    let items = String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (id, preview) = line.split_once('\t')?;
+            Some(Item {
+                id: id.to_owned(),
+                preview: preview.to_owned(),
+            })
+        })
+        .collect();
+    Ok(items)
+}
+
+/// Copy the Decoded Item into the Clipboard using Wl-Copy
+fn copy(id: &str) -> Result<()> {
+    let data = cliphist("decode", id)?;
+    let mut child = Command::new(WL_COPY)
+        .stdin(Stdio::piped())
+        .spawn()
+        .context("Wl-Copy Failed to Execute")?;
+    let mut stdin = child.stdin.take().expect("Failed to Open Wl-Copy Stdin");
+    stdin.write_all(&data)?;
+    drop(stdin);
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow!("Wl-Copy Exited with Error: {status:?}"));
+    }
+    Ok(())
+}
+
+/// Retrieve the Directory Caching Decoded Image Previews
+fn preview_dir() -> PathBuf {
+    env::var(XDG_CACHE)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(env::var(HOME).unwrap_or_default()).join(".cache"))
+        .join("rmenu")
+        .join("clipboard")
+}
+
+/// Decode the Image Item into a Preview File (Unless Already Decoded)
+fn preview(dir: &Path, item: &Item, image: &Image) -> Option<PathBuf> {
+    let path = dir.join(format!("{}.{}", item.id, image.format));
+    if path.exists() {
+        return Some(path);
+    }
+    let data = cliphist("decode", &item.id).ok()?;
+    fs::write(&path, data).ok()?;
+    Some(path)
+}
+
+/// Generate Entries Copying or Deleting each History Item
+fn entries(items: Vec<Item>, previews: bool) -> Result<Vec<Entry>> {
+    let exe = self_exe();
+    let dir = preview_dir();
+    if previews {
+        fs::create_dir_all(&dir).context("Failed to Create Preview Directory")?;
+    }
+    let mut decoded = HashSet::new();
+    let mut entries = vec![];
+    for item in items {
+        let exec = format!("{exe} copy {}", item.id);
+        let mut entry = match item.image() {
+            Some(image) => {
+                let name = format!("Image {}", image.dimensions);
+                let comment = format!("{} {} {}", image.amount, image.unit, image.format);
+                let mut entry = Entry::new(&name, &exec, Some(&comment));
+                if previews {
+                    entry.icon = preview(&dir, &item, &image).map(|path| {
+                        let icon = path.to_string_lossy().to_string();
+                        decoded.insert(path);
+                        icon
+                    });
+                }
+                entry
+            }
+            None => Entry::new(&item.preview, &exec, None),
+        };
+        entry.actions.push(Action {
+            name: "delete".to_owned(),
+            exec: Method::Run(format!("{exe} delete {}", item.id)),
+            comment: Some("Delete the Item from the Clipboard History".to_owned()),
+            icon: None,
+            close: false,
+            env: Default::default(),
+            cwd: None,
+        });
+        entries.push(entry);
+    }
+    // drop previews of items no longer in the history
+    if let Some(files) = fs::read_dir(&dir).ok().filter(|_| previews) {
+        files
+            .filter_map(|f| f.ok())
+            .map(|f| f.path())
+            .filter(|path| !decoded.contains(path))
+            .for_each(|path| {
+                let _ = fs::remove_file(path);
+            });
+    }
+    Ok(entries)
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or(Commands::List);
+    match command {
+        Commands::Copy { id } => copy(&id)?,
+        Commands::Delete { id } => {
+            cliphist("delete", &id)?;
+        }
+        Commands::List => {
+            let entries = entries(history()?, !cli.no_previews)?;
+            let mut writer = EntryWriter::stdout();
+            writer.write_all(&entries)?;
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
@@ -1,20 +1,21 @@
+use std::collections::{BTreeMap, HashMap};
 use std::fs::read_to_string;
 use std::path::PathBuf;
 
 use freedesktop_desktop_entry::{DesktopEntry, Iter};
-use once_cell::sync::Lazy;
-use regex::Regex;
-use rmenu_plugin::{Action, Entry, Method};
-
-mod icons;
+use rmenu_plugin::{icons, Action, Entry, EntryWriter, Method};
 
 static XDG_DATA_ENV: &'static str = "XDG_DATA_DIRS";
 static XDG_CONFIG_ENV: &'static str = "XDG_CONFIG_HOME";
 static XDG_DATA_DEFAULT: &'static str = "/usr/share:/usr/local/share";
 static XDG_CONFIG_DEFAULT: &'static str = "~/.config";
 
-static EXEC_RGX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"%\w").expect("Failed to Build Exec Regex"));
+/// Entry Metadata RMenu Resolves Desktop Exec Values with
+static DESKTOP_FILE: &'static str = "desktop_file";
+static DESKTOP_ICON: &'static str = "desktop_icon";
+static DESKTOP_PATH: &'static str = "desktop_path";
+static DESKTOP_DBUS: &'static str = "desktop_dbus";
+static DESKTOP_ACTION: &'static str = "desktop_action.";
 
 /// Retrieve XDG-CONFIG-HOME Directory
 #[inline]
@@ -35,12 +36,6 @@ fn data_dirs(dir: &str) -> Vec<PathBuf> {
         .collect()
 }
 
-/// Modify Exec Statements to Remove %u/%f/etc...
-#[inline(always)]
-fn fix_exec(exec: &str) -> String {
-    EXEC_RGX.replace_all(exec, "").trim().to_string()
-}
-
 /// Parse XDG Desktop Entry into RMenu Entry
 fn parse_desktop(path: &PathBuf, locale: Option<&str>) -> Option<Entry> {
     let bytes = read_to_string(path).ok()?;
@@ -49,11 +44,27 @@ fn parse_desktop(path: &PathBuf, locale: Option<&str>) -> Option<Entry> {
     let icon = entry.icon().map(|i| i.to_string());
     let comment = entry.comment(locale).map(|s| s.to_string());
     let terminal = entry.terminal();
+    // field codes are left for rmenu to expand when the entry is executed
+    let mut meta = BTreeMap::new();
+    meta.insert(DESKTOP_FILE.to_owned(), path.to_string_lossy().to_string());
+    if let Some(icon) = icon.as_ref() {
+        meta.insert(DESKTOP_ICON.to_owned(), icon.to_owned());
+    }
+    if let Some(dir) = entry.desktop_entry("Path").filter(|p| !p.is_empty()) {
+        meta.insert(DESKTOP_PATH.to_owned(), dir.to_owned());
+    }
+    if entry.desktop_entry("DBusActivatable") == Some("true") {
+        meta.insert(DESKTOP_DBUS.to_owned(), "true".to_owned());
+    }
     let mut actions = match entry.exec() {
         Some(exec) => vec![Action {
             name: "main".to_string(),
-            exec: Method::new(fix_exec(exec), terminal),
+            exec: Method::new(exec.to_owned(), terminal),
             comment: None,
+            icon: None,
+            close: true,
+            env: Default::default(),
+            cwd: None,
         }],
         None => vec![],
     };
@@ -67,10 +78,15 @@ fn parse_desktop(path: &PathBuf, locale: Option<&str>) -> Option<Entry> {
             .filter_map(|a| {
                 let name = entry.action_name(a, locale)?;
                 let exec = entry.action_exec(a)?;
+                meta.insert(format!("{DESKTOP_ACTION}{name}"), a.to_owned());
                 Some(Action {
                     name: name.to_string(),
-                    exec: Method::new(fix_exec(exec), terminal),
+                    exec: Method::new(exec.to_owned(), terminal),
                     comment: None,
+                    icon: None,
+                    close: true,
+                    env: Default::default(),
+                    cwd: None,
                 })
             }),
     );
@@ -80,11 +96,15 @@ fn parse_desktop(path: &PathBuf, locale: Option<&str>) -> Option<Entry> {
         comment,
         icon,
         icon_alt: None,
+        default_action: None,
+        uri: None,
+        source: None,
+        meta,
     })
 }
 
 /// Assign XDG Icon based on Desktop-Entry
-fn assign_icon(icon: String, map: &icons::IconMap) -> Option<String> {
+fn assign_icon(icon: String, map: &HashMap<String, PathBuf>) -> Option<String> {
     if !icon.contains("/") {
         if let Some(icon) = map.get(&icon) {
             if let Some(path) = icon.to_str() {
@@ -97,12 +117,13 @@ fn assign_icon(icon: String, map: &icons::IconMap) -> Option<String> {
 
 fn main() {
     let locale = Some("en");
-    let sizes = vec![64, 32, 96, 22, 128];
 
     // collect icons
-    let cfg = config_dir();
-    let spec = icons::IconSpec::standard(&cfg, sizes);
-    let icons = icons::collect_icons(spec);
+    let theme = icons::detect_theme(&config_dir());
+    let mut bases = vec![PathBuf::from(shellexpand::tilde("~/.icons").to_string())];
+    bases.extend(data_dirs("icons"));
+    let chain = icons::themes(&bases, theme.as_deref());
+    let icons = icons::index(&chain, 64);
 
     // collect applications
     let app_paths = data_dirs("applications");
@@ -116,9 +137,9 @@ fn main() {
         .collect();
 
     desktops.sort_by_cached_key(|e| e.name.to_owned());
-    desktops
-        .into_iter()
-        .filter_map(|e| serde_json::to_string(&e).ok())
-        .map(|s| println!("{}", s))
-        .last();
+    let mut writer = EntryWriter::stdout();
+    writer
+        .write_all(&desktops)
+        .and_then(|_| writer.flush())
+        .expect("Failed to Write Entries");
 }
@@ -0,0 +1,103 @@
+use std::env;
+use std::path::PathBuf;
+
+use clap::Parser;
+use emojis::Emoji;
+use rmenu_plugin::{Action, Entry, EntryWriter, Method};
+
+static PATH: &'static str = "PATH";
+static WAYLAND: &'static str = "WAYLAND_DISPLAY";
+
+/// Clipboard Writers w/ the Command Copying a Quoted Glyph
+static WAYLAND_COPY: (&'static str, &'static str) = ("wl-copy", "wl-copy -- {}");
+static X11_COPY: &[(&'static str, &'static str)] = &[
+    (
+        "xclip",
+        "sh -c 'printf %s \"$0\" | xclip -selection clipboard' {}",
+    ),
+    (
+        "xsel",
+        "sh -c 'printf %s \"$0\" | xsel --clipboard --input' {}",
+    ),
+];
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+pub struct Cli {
+    /// Include every Skin-Tone Variant as a Separate Entry
+    #[arg(long)]
+    skin_tones: bool,
+}
+
+/// Check if the Program is Available in $PATH
+fn on_path(program: &str) -> bool {
+    env::var(PATH)
+        .unwrap_or_default()
+        .split(':')
+        .any(|dir| PathBuf::from(dir).join(program).is_file())
+}
+
+/// Detect the Clipboard Writer for the Running Display Server (Wayland First)
+fn copy_template() -> Option<&'static str> {
+    let wayland = env::var(WAYLAND).is_ok();
+    let mut writers: Vec<(&str, &str)> = X11_COPY.to_vec();
+    match wayland {
+        true => writers.insert(0, WAYLAND_COPY),
+        false => writers.push(WAYLAND_COPY),
+    }
+    writers
+        .into_iter()
+        .find(|(program, _)| on_path(program))
+        .map(|(_, template)| template)
+}
+
+/// Generate an Entry Copying the Emoji (or Printing it w/o a Clipboard Tool)
+fn emoji_entry(emoji: &Emoji, copy: Option<&str>) -> Entry {
+    let glyph = emoji.as_str();
+    let shortcodes: Vec<String> = emoji.shortcodes().map(|s| format!(":{s}:")).collect();
+    let comment = shortcodes.join(" ");
+    let mut entry = match copy {
+        Some(template) => {
+            // glyphs are quoted to keep sequences like `#️⃣` from parsing as comments
+            let exec = template.replace("{}", &format!("'{glyph}'"));
+            Entry::new(emoji.name(), &exec, Some(&comment))
+        }
+        None => {
+            let mut entry = Entry::echo(glyph, Some(&comment));
+            entry.name = emoji.name().to_owned();
+            entry
+        }
+    };
+    entry.icon_alt = Some(glyph.to_owned());
+    entry.actions.push(Action {
+        name: "type".to_owned(),
+        exec: Method::Type(glyph.to_owned()),
+        comment: Some("Type the Emoji into the Focused Window".to_owned()),
+        icon: None,
+        close: true,
+        env: Default::default(),
+        cwd: None,
+    });
+    entry
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let copy = copy_template();
+    let mut entries = vec![];
+    for emoji in emojis::iter() {
+        entries.push(emoji_entry(emoji, copy));
+        if !cli.skin_tones {
+            continue;
+        }
+        if let Some(tones) = emoji.skin_tones() {
+            // the first variant is the emoji w/o any skin-tone
+            entries.extend(tones.skip(1).map(|tone| emoji_entry(tone, copy)));
+        }
+    }
+    let mut writer = EntryWriter::stdout();
+    writer
+        .write_all(&entries)
+        .and_then(|_| writer.flush())
+        .expect("Failed to Write Entries");
+}
@@ -1,7 +1,7 @@
 use anyhow::{anyhow, Result};
 
 use clap::{Parser, Subcommand};
-use rmenu_plugin::Entry;
+use rmenu_plugin::{Entry, EntryWriter};
 
 mod gui;
 mod network;
@@ -44,14 +44,16 @@ async fn list_aps() -> Result<()> {
         manager.scan_wifi().await?;
     }
     // retrive access-points and print as entries
+    let mut writer = EntryWriter::stdout();
     for ap in manager.access_points() {
         let star = ap.is_active.then(|| " *").unwrap_or("");
         let bars = get_bars(ap.signal);
         let desc = format!("{bars} {}{star}", ap.ssid);
         let exec = format!("{exe} connect {:?}", ap.ssid);
         let entry = Entry::new(&desc, &exec, None);
-        println!("{}", serde_json::to_string(&entry).unwrap());
+        writer.write(&entry)?;
     }
+    writer.flush()?;
     Ok(())
 }
 
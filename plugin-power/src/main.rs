@@ -0,0 +1,160 @@
+use std::env;
+
+use clap::{Parser, Subcommand};
+use rmenu_plugin::{self_exe, Action, Entry, EntryWriter, Method};
+
+static SESSION_ID: &'static str = "XDG_SESSION_ID";
+static USER: &'static str = "USER";
+
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    /// List the Power and Session Actions
+    List,
+    /// List the Entries Confirming or Cancelling the Action
+    Confirm { id: String },
+}
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+#[command(propagate_version = true)]
+pub struct Cli {
+    /// Run Destructive Actions w/o Confirmation
+    #[arg(long)]
+    no_confirm: bool,
+    #[clap(subcommand)]
+    command: Option<Commands>,
+}
+
+/// Power or Session Action Handled by Systemd-Logind
+struct Power {
+    id: &'static str,
+    name: &'static str,
+    icon: &'static str,
+    glyph: &'static str,
+    exec: String,
+    /// Ask for Confirmation before Ending the Session
+    destructive: bool,
+}
+
+impl Power {
+    /// Generate Entry Running the Action Directly
+    fn entry(&self) -> Entry {
+        let mut entry = Entry::new(self.name, &self.exec, None);
+        entry.icon = Some(self.icon.to_owned());
+        entry.icon_alt = Some(self.glyph.to_owned());
+        entry
+    }
+}
+
+/// Command Ending the Current Logind Session
+fn logout() -> String {
+    match env::var(SESSION_ID) {
+        Ok(id) if !id.is_empty() => format!("loginctl terminate-session {id}"),
+        _ => format!(
+            "loginctl terminate-user {}",
+            env::var(USER).unwrap_or_default()
+        ),
+    }
+}
+
+/// Retrieve Supported Power and Session Actions
+fn actions() -> Vec<Power> {
+    vec![
+        Power {
+            id: "shutdown",
+            name: "Shutdown",
+            icon: "system-shutdown",
+            glyph: "⏻",
+            exec: "systemctl poweroff".to_owned(),
+            destructive: true,
+        },
+        Power {
+            id: "reboot",
+            name: "Reboot",
+            icon: "system-reboot",
+            glyph: "↻",
+            exec: "systemctl reboot".to_owned(),
+            destructive: true,
+        },
+        Power {
+            id: "suspend",
+            name: "Suspend",
+            icon: "system-suspend",
+            glyph: "⏾",
+            exec: "systemctl suspend".to_owned(),
+            destructive: false,
+        },
+        Power {
+            id: "hibernate",
+            name: "Hibernate",
+            icon: "system-suspend-hibernate",
+            glyph: "❄",
+            exec: "systemctl hibernate".to_owned(),
+            destructive: true,
+        },
+        Power {
+            id: "lock",
+            name: "Lock",
+            icon: "system-lock-screen",
+            glyph: "🔒",
+            exec: "loginctl lock-session".to_owned(),
+            destructive: false,
+        },
+        Power {
+            id: "logout",
+            name: "Log Out",
+            icon: "system-log-out",
+            glyph: "⇥",
+            exec: logout(),
+            destructive: true,
+        },
+    ]
+}
+
+/// Generate Entries w/ Destructive Actions Opening a Confirmation Menu
+fn entries(confirm: bool) -> Vec<Entry> {
+    let exe = self_exe();
+    actions()
+        .into_iter()
+        .map(|power| {
+            let mut entry = power.entry();
+            if confirm && power.destructive {
+                entry.actions = vec![Action {
+                    name: "main".to_owned(),
+                    exec: Method::Menu(format!("{exe} confirm {}", power.id)),
+                    comment: Some(format!("Confirm to {}", power.name)),
+                    icon: None,
+                    close: true,
+                    env: Default::default(),
+                    cwd: None,
+                }];
+            }
+            entry
+        })
+        .collect()
+}
+
+/// Generate the Confirmation Menu of the Specified Action
+fn confirm(id: &str) -> Vec<Entry> {
+    let Some(power) = actions().into_iter().find(|p| p.id == id) else {
+        return vec![];
+    };
+    let mut cancel = Entry::echo("Cancel", None);
+    cancel.actions = vec![Action::echo(&format!("{} Cancelled", power.name))];
+    cancel.icon = Some("process-stop".to_owned());
+    cancel.icon_alt = Some("✕".to_owned());
+    vec![cancel, power.entry()]
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let entries = match cli.command.unwrap_or(Commands::List) {
+        Commands::List => entries(!cli.no_confirm),
+        Commands::Confirm { id } => confirm(&id),
+    };
+    let mut writer = EntryWriter::stdout();
+    writer
+        .write_all(&entries)
+        .and_then(|_| writer.flush())
+        .expect("Failed to Write Entries");
+}
@@ -1,7 +1,7 @@
 use std::env;
 use std::os::unix::fs::PermissionsExt;
 
-use rmenu_plugin::Entry;
+use rmenu_plugin::{Entry, EntryWriter};
 use walkdir::{DirEntry, WalkDir};
 
 static PATH: &'static str = "PATH";
@@ -55,10 +55,9 @@ fn main() {
         .collect();
     // sort entries and render to json
     entries.sort_by_cached_key(|e| e.name.clone());
-    entries
-        .into_iter()
-        .map(|e| serde_json::to_string(&e))
-        .filter_map(|r| r.ok())
-        .map(|s| println!("{}", s))
-        .last();
+    let mut writer = EntryWriter::stdout();
+    writer
+        .write_all(&entries)
+        .and_then(|_| writer.flush())
+        .expect("Failed to Write Entries");
 }
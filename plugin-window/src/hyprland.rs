@@ -0,0 +1,79 @@
+//! Hyprland WindowManager Window Selector
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+use rmenu_plugin::Entry;
+use serde::Deserialize;
+
+use crate::{window_entry, Backend, WindowManager};
+
+#[derive(Debug, Deserialize)]
+pub struct HyprWorkspace {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HyprWindow {
+    pub address: String,
+    pub title: String,
+    pub class: String,
+    pub workspace: HyprWorkspace,
+    /// Zero for the Focused Window, Counting up the Longer Ago
+    pub focus_history_id: i64,
+}
+
+#[derive(Debug)]
+pub struct HyprlandManager {}
+
+/// Run Hyprctl and Return its Output
+fn hyprctl(args: &[&str]) -> Result<Vec<u8>> {
+    let out = Command::new("hyprctl")
+        .args(args)
+        .stdout(Stdio::piped())
+        .output()
+        .context("Hyprctl Failed to Execute")?;
+    if !out.status.success() {
+        return Err(anyhow!("Invalid Hyprctl Status: {:?}", out.status));
+    }
+    Ok(out.stdout)
+}
+
+pub fn get_windows() -> Result<Vec<HyprWindow>> {
+    let out = hyprctl(&["clients", "-j"])?;
+    let windows: Vec<HyprWindow> =
+        serde_json::from_slice(&out).context("Failed to Parse Hyprctl Output")?;
+    // most recently used first, w/ the focused window last
+    let mut windows: Vec<HyprWindow> = windows
+        .into_iter()
+        .filter(|w| !w.title.is_empty() || !w.class.is_empty())
+        .collect();
+    windows.sort_by_key(|w| (w.focus_history_id == 0, w.focus_history_id));
+    Ok(windows)
+}
+
+impl WindowManager for HyprlandManager {
+    /// Focus on Specified Window
+    fn focus(&self, id: &str) -> Result<()> {
+        let out = Command::new("hyprctl")
+            .args(["dispatch", "focuswindow", &format!("address:{id}")])
+            .output()
+            .with_context(|| format!("Failed Hyprctl To Focus Window: {id:?}"))?;
+        if !out.status.success() {
+            return Err(anyhow!("Hyprctl Exited with Error: {:?}", out.status));
+        }
+        Ok(())
+    }
+    /// Generate RMenu Entries
+    fn entries(&self) -> Result<Vec<Entry>> {
+        let windows = get_windows()?;
+        let entries = windows
+            .into_iter()
+            .map(|w| {
+                let comment = format!("{} ({})", w.class, w.workspace.name);
+                window_entry(Backend::Hyprland, &w.address, &w.title, Some(&comment))
+            })
+            .collect();
+        Ok(entries)
+    }
+}
@@ -1,11 +1,19 @@
+use std::env;
 use std::fmt::Debug;
+use std::path::PathBuf;
 
-use anyhow::Result;
-use clap::{Parser, Subcommand};
-use rmenu_plugin::Entry;
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use rmenu_plugin::{self_exe, Entry, EntryWriter};
 
+#[cfg(feature = "hyprland")]
+mod hyprland;
 #[cfg(feature = "sway")]
 mod sway;
+#[cfg(feature = "wlr")]
+mod wlr;
+#[cfg(feature = "x11")]
+mod x11;
 
 /// Trait To Implement for Window Focus
 pub trait WindowManager: Debug {
@@ -19,33 +27,113 @@ pub enum Commands {
     Focus { id: String },
 }
 
+/// Window Manager Backends in Order of Autodetection
+#[derive(Debug, Copy, Clone, PartialEq, ValueEnum)]
+pub enum Backend {
+    /// Sway IPC (swaymsg)
+    Sway,
+    /// Hyprland IPC (hyprctl)
+    Hyprland,
+    /// wlr-foreign-toplevel Compositors (lswt and wlrctl)
+    Wlr,
+    /// X11 Window Managers supporting EWMH (wmctrl)
+    X11,
+}
+
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
 pub struct Cli {
+    /// Window manager backend (detected from the session by default)
+    #[arg(short, long, value_enum)]
+    backend: Option<Backend>,
     #[clap(subcommand)]
     command: Option<Commands>,
 }
 
+/// Check if the Program is Available in $PATH
+pub fn on_path(program: &str) -> bool {
+    env::var("PATH")
+        .unwrap_or_default()
+        .split(':')
+        .any(|dir| PathBuf::from(dir).join(program).is_file())
+}
+
+/// Quote an Argument for the Shell-Style Split of Entry Commands
+fn quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// Generate the Command Focusing the Window through the same Backend
+pub fn focus_exec(backend: Backend, id: &str) -> String {
+    let exe = self_exe();
+    let name = backend
+        .to_possible_value()
+        .map(|v| v.get_name().to_owned())
+        .unwrap_or_default();
+    format!("{exe} --backend {name} focus {}", quote(id))
+}
+
+/// Generate the Entry Focusing the Window
+pub fn window_entry(backend: Backend, id: &str, title: &str, comment: Option<&str>) -> Entry {
+    let exec = focus_exec(backend, id);
+    Entry::new(title, &exec, comment.filter(|c| !c.is_empty()))
+}
+
+impl Backend {
+    /// Detect the Backend of the Running Session
+    fn detect() -> Option<Self> {
+        let set = |var: &str| env::var(var).map(|v| !v.is_empty()).unwrap_or(false);
+        if set("SWAYSOCK") {
+            return Some(Self::Sway);
+        }
+        if set("HYPRLAND_INSTANCE_SIGNATURE") {
+            return Some(Self::Hyprland);
+        }
+        if set("WAYLAND_DISPLAY") && on_path("lswt") && on_path("wlrctl") {
+            return Some(Self::Wlr);
+        }
+        if set("DISPLAY") && on_path("wmctrl") {
+            return Some(Self::X11);
+        }
+        None
+    }
+
+    /// Retrieve the Implementation of the Backend (if Compiled In)
+    fn manager(&self) -> Result<Box<dyn WindowManager>> {
+        match self {
+            #[cfg(feature = "sway")]
+            Self::Sway => Ok(Box::new(sway::SwayManager {})),
+            #[cfg(feature = "hyprland")]
+            Self::Hyprland => Ok(Box::new(hyprland::HyprlandManager {})),
+            #[cfg(feature = "wlr")]
+            Self::Wlr => Ok(Box::new(wlr::WlrManager {})),
+            #[cfg(feature = "x11")]
+            Self::X11 => Ok(Box::new(x11::X11Manager {})),
+            #[allow(unreachable_patterns)]
+            backend => Err(anyhow!("Backend {backend:?} was not Enabled at Build Time")),
+        }
+    }
+}
+
 /// Retrieve WindowManager Implementation
-#[allow(unreachable_code)]
-fn get_impl() -> impl WindowManager {
-    #[cfg(feature = "sway")]
-    return sway::SwayManager {};
-    // if no features are enabled for some reason?
-    panic!("No Implementations Available")
+fn get_impl(backend: Option<Backend>) -> Result<Box<dyn WindowManager>> {
+    backend
+        .or_else(Backend::detect)
+        .ok_or_else(|| anyhow!("No Supported Window Manager Detected"))?
+        .manager()
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    let windows = get_impl();
+    let windows = get_impl(cli.backend)?;
     let command = cli.command.unwrap_or(Commands::ListWindow);
     match command {
         Commands::Focus { id } => windows.focus(&id)?,
         Commands::ListWindow => {
-            for entry in windows.entries()? {
-                println!("{}", serde_json::to_string(&entry).unwrap());
-            }
+            let mut writer = EntryWriter::stdout();
+            writer.write_all(&windows.entries()?)?;
+            writer.flush()?;
         }
     }
     Ok(())
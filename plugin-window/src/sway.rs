@@ -6,7 +6,7 @@ use rmenu_plugin::Entry;
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::WindowManager;
+use crate::{window_entry, Backend, WindowManager};
 
 static SWAY_TYPE_KEY: &'static str = "type";
 static SWAY_NODES_KEY: &'static str = "nodes";
@@ -18,6 +18,7 @@ pub struct SwayWindow {
     pub name: String,
     pub pid: u64,
     pub focused: bool,
+    pub app_id: Option<String>,
 }
 
 #[derive(Debug)]
@@ -71,7 +72,7 @@ impl WindowManager for SwayManager {
         let out = Command::new("swaymsg")
             .arg(format!("[pid={}] focus", id))
             .output()
-            .context("Failed SwayMsg To Focus Window: {id:?}")?;
+            .with_context(|| format!("Failed SwayMsg To Focus Window: {id:?}"))?;
         if !out.status.success() {
             return Err(anyhow!("SwayMsg Exited with Error: {:?}", out.status));
         }
@@ -79,13 +80,12 @@ impl WindowManager for SwayManager {
     }
     /// Generate RMenu Entries
     fn entries(&self) -> Result<Vec<Entry>> {
-        let exe = std::env::current_exe()?.to_str().unwrap().to_string();
         let windows = get_windows()?;
         let entries = windows
             .into_iter()
             .map(|w| {
-                let exec = format!("{exe} focus {:?}", w.pid);
-                Entry::new(&w.name, &exec, None)
+                let id = w.pid.to_string();
+                window_entry(Backend::Sway, &id, &w.name, w.app_id.as_deref())
             })
             .collect();
         Ok(entries)
@@ -0,0 +1,89 @@
+//! wlr-foreign-toplevel Window Selector for Wlroots Compositors
+//!
+//! Windows are listed w/ `lswt` and focused w/ `wlrctl`, neither of which
+//! exposes a stable window handle, so windows are matched on their
+//! app-id and title instead.
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+use rmenu_plugin::Entry;
+use serde::Deserialize;
+
+use crate::{window_entry, Backend, WindowManager};
+
+/// Separator of the App-ID and Title inside the Focus ID
+static ID_SEPARATOR: char = '\t';
+
+#[derive(Debug, Deserialize)]
+pub struct Toplevel {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default, alias = "app-id")]
+    pub app_id: String,
+    #[serde(default)]
+    pub activated: bool,
+}
+
+/// Lswt Output w/ or w/o the Toplevel Wrapper Object
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Listing {
+    Wrapped { toplevels: Vec<Toplevel> },
+    Plain(Vec<Toplevel>),
+}
+
+#[derive(Debug)]
+pub struct WlrManager {}
+
+pub fn get_windows() -> Result<Vec<Toplevel>> {
+    let out = Command::new("lswt")
+        .arg("-j")
+        .stdout(Stdio::piped())
+        .output()
+        .context("Lswt Failed to Execute")?;
+    if !out.status.success() {
+        return Err(anyhow!("Invalid Lswt Status: {:?}", out.status));
+    }
+    let listing: Listing =
+        serde_json::from_slice(&out.stdout).context("Failed to Parse Lswt Output")?;
+    let mut windows = match listing {
+        Listing::Wrapped { toplevels } => toplevels,
+        Listing::Plain(toplevels) => toplevels,
+    };
+    windows.sort_by_key(|w| w.activated);
+    Ok(windows)
+}
+
+impl WindowManager for WlrManager {
+    /// Focus on Specified Window
+    fn focus(&self, id: &str) -> Result<()> {
+        let (app_id, title) = id
+            .split_once(ID_SEPARATOR)
+            .ok_or_else(|| anyhow!("Invalid Window ID: {id:?}"))?;
+        let mut command = Command::new("wlrctl");
+        command.args(["toplevel", "focus"]);
+        if !app_id.is_empty() {
+            command.arg(format!("app_id:{app_id}"));
+        }
+        let out = command
+            .arg(format!("title:{title}"))
+            .output()
+            .with_context(|| format!("Failed Wlrctl To Focus Window: {id:?}"))?;
+        if !out.status.success() {
+            return Err(anyhow!("Wlrctl Exited with Error: {:?}", out.status));
+        }
+        Ok(())
+    }
+    /// Generate RMenu Entries
+    fn entries(&self) -> Result<Vec<Entry>> {
+        let windows = get_windows()?;
+        let entries = windows
+            .into_iter()
+            .map(|w| {
+                let id = format!("{}{ID_SEPARATOR}{}", w.app_id, w.title);
+                window_entry(Backend::Wlr, &id, &w.title, Some(&w.app_id))
+            })
+            .collect();
+        Ok(entries)
+    }
+}
@@ -0,0 +1,77 @@
+//! X11 Window Selector for EWMH Compliant Window Managers
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+use rmenu_plugin::Entry;
+
+use crate::{window_entry, Backend, WindowManager};
+
+#[derive(Debug)]
+pub struct X11Window {
+    pub id: String,
+    pub class: String,
+    pub title: String,
+}
+
+#[derive(Debug)]
+pub struct X11Manager {}
+
+/// Parse a `wmctrl -lx` Line: `<id> <desktop> <class> <host> <title>`
+fn parse_window(line: &str) -> Option<X11Window> {
+    let mut rest = line;
+    let mut columns = vec![];
+    for _ in 0..4 {
+        let (column, tail) = rest.trim_start().split_once(char::is_whitespace)?;
+        columns.push(column);
+        rest = tail;
+    }
+    // sticky windows and panels are listed on desktop -1
+    if columns[1] == "-1" {
+        return None;
+    }
+    let class = columns[2].rsplit('.').next().unwrap_or(columns[2]);
+    Some(X11Window {
+        id: columns[0].to_owned(),
+        class: class.to_owned(),
+        title: rest.trim().to_owned(),
+    })
+}
+
+pub fn get_windows() -> Result<Vec<X11Window>> {
+    let out = Command::new("wmctrl")
+        .arg("-lx")
+        .stdout(Stdio::piped())
+        .output()
+        .context("Wmctrl Failed to Execute")?;
+    if !out.status.success() {
+        return Err(anyhow!("Invalid Wmctrl Status: {:?}", out.status));
+    }
+    let windows = String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(parse_window)
+        .collect();
+    Ok(windows)
+}
+
+impl WindowManager for X11Manager {
+    /// Focus on Specified Window
+    fn focus(&self, id: &str) -> Result<()> {
+        let out = Command::new("wmctrl")
+            .args(["-ia", id])
+            .output()
+            .with_context(|| format!("Failed Wmctrl To Focus Window: {id:?}"))?;
+        if !out.status.success() {
+            return Err(anyhow!("Wmctrl Exited with Error: {:?}", out.status));
+        }
+        Ok(())
+    }
+    /// Generate RMenu Entries
+    fn entries(&self) -> Result<Vec<Entry>> {
+        let windows = get_windows()?;
+        let entries = windows
+            .into_iter()
+            .map(|w| window_entry(Backend::X11, &w.id, &w.title, Some(&w.class)))
+            .collect();
+        Ok(entries)
+    }
+}
@@ -0,0 +1,54 @@
+//! Keystroke Search Cost: Normalizing per Keystroke vs Pre-Normalized Entries
+//!
+//! Run with `cargo bench -p rmenu-core`, typing the query one character at
+//! a time over a large listing like a case-insensitive search would.
+use std::time::{Duration, Instant};
+
+use rmenu_core::config::Config;
+use rmenu_core::search::{Normalized, Search};
+use rmenu_plugin::Entry;
+
+static ENTRIES: usize = 100_000;
+static QUERY: &str = "Firefox";
+
+/// Generate Entries resembling a Large Application Listing
+fn entries() -> Vec<Entry> {
+    (0..ENTRIES)
+        .map(|i| {
+            let name = format!("Application-{i} Ünicode Äpp");
+            let exec = format!("/usr/bin/application-{i} --new-window");
+            Entry::new(&name, &exec, Some("Launch an Application"))
+        })
+        .collect()
+}
+
+/// Report the Time Spent per Keystroke of a Single Run
+fn report(name: &str, elapsed: Duration) {
+    let keystroke = elapsed / QUERY.len() as u32;
+    eprintln!("{name:>14}: {elapsed:>10.2?} ({keystroke:>10.2?} per keystroke)");
+}
+
+fn main() {
+    let config = Config::default();
+    let entries = entries();
+    let candidates: Vec<usize> = (0..entries.len()).collect();
+
+    let start = Instant::now();
+    let normalized: Vec<Normalized> = entries.iter().map(Normalized::new).collect();
+    eprintln!("{:>14}: {:>10.2?}", "normalize once", start.elapsed());
+
+    let start = Instant::now();
+    for end in 1..=QUERY.len() {
+        let search = Search::new(&config, &QUERY[..end]);
+        let normalized: Vec<Normalized> = entries.iter().map(Normalized::new).collect();
+        search.matches(&entries, &normalized, &candidates);
+    }
+    report("per keystroke", start.elapsed());
+
+    let start = Instant::now();
+    for end in 1..=QUERY.len() {
+        let search = Search::new(&config, &QUERY[..end]);
+        search.matches(&entries, &normalized, &candidates);
+    }
+    report("pre-normalized", start.elapsed());
+}
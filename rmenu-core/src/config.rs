@@ -0,0 +1,1055 @@
+//! RMENU Configuration Implementations
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::str::FromStr;
+use std::time::Duration;
+
+use heck::AsPascalCase;
+use keyboard_types::{Code, Modifiers};
+use rmenu_plugin::{Entry, Method, Options};
+use serde::{de::Error, Deserialize, Serialize};
+
+// parse supported modifiers from string
+fn mod_from_str(s: &str) -> Option<Modifiers> {
+    match s.to_lowercase().as_str() {
+        "alt" => Some(Modifiers::ALT),
+        "ctrl" => Some(Modifiers::CONTROL),
+        "shift" => Some(Modifiers::SHIFT),
+        "super" => Some(Modifiers::SUPER),
+        _ => None,
+    }
+}
+
+/// Single GUI Keybind for Configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keybind {
+    pub mods: Modifiers,
+    pub key: Code,
+}
+
+impl Keybind {
+    fn new(key: Code) -> Self {
+        Self {
+            mods: Modifiers::empty(),
+            key,
+        }
+    }
+}
+
+impl std::fmt::Display for Keybind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mods = [
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::CONTROL, "Ctrl"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::SUPER, "Super"),
+        ];
+        for (keymod, name) in mods {
+            if self.mods.contains(keymod) {
+                write!(f, "{name}+")?;
+            }
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+impl FromStr for Keybind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // parse modifiers/keys from string
+        let mut mods = vec![];
+        let mut keys = vec![];
+        for item in s.split("+") {
+            let camel = format!("{}", AsPascalCase(item));
+            match Code::from_str(&camel) {
+                Ok(key) => keys.push(key),
+                Err(_) => match mod_from_str(item) {
+                    Some(keymod) => mods.push(keymod),
+                    None => return Err(format!("invalid key/modifier: {item}")),
+                },
+            }
+        }
+        // generate final keybind
+        let kmod = mods.into_iter().fold(Modifiers::empty(), |m1, m2| m1 | m2);
+        match keys.len() {
+            0 => Err(format!("no keys specified")),
+            1 => Ok(Keybind {
+                mods: kmod,
+                key: keys.pop().unwrap(),
+            }),
+            _ => Err(format!("too many keys: {keys:?}")),
+        }
+    }
+}
+
+impl Serialize for Keybind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Keybind {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s: &str = Deserialize::deserialize(deserializer)?;
+        Keybind::from_str(s).map_err(D::Error::custom)
+    }
+}
+
+/// How Keybinds are Matched against Keypresses
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyMatch {
+    /// Physical key position (e.g. `KeyZ` is `Y` on QWERTZ)
+    #[default]
+    Code,
+    /// Character produced by the active keyboard layout
+    Key,
+}
+
+impl FromStr for KeyMatch {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "code" | "scancode" => Ok(Self::Code),
+            "key" | "keysym" => Ok(Self::Key),
+            _ => Err("No Such Key-Match Mode".to_owned()),
+        }
+    }
+}
+
+/// Parse the Hint Modifier Rejecting Shift, which is Needed to Type Capitals
+fn hint_modifier<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s: String = Deserialize::deserialize(deserializer)?;
+    match mod_from_str(&s) == Some(Modifiers::SHIFT) {
+        true => Err(D::Error::custom("shift cannot be the hint modifier")),
+        false => Ok(s),
+    }
+}
+
+/// Global GUI Keybind Settings Options
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyConfig {
+    pub match_by: KeyMatch,
+    #[serde(deserialize_with = "hint_modifier")]
+    pub hint_modifier: String,
+    pub ignore_composing: bool,
+    pub exec: Vec<Keybind>,
+    pub exec_alt: Vec<Keybind>,
+    pub exit: Vec<Keybind>,
+    pub move_next: Vec<Keybind>,
+    pub move_prev: Vec<Keybind>,
+    pub open_menu: Vec<Keybind>,
+    pub close_menu: Vec<Keybind>,
+    pub jump_next: Vec<Keybind>,
+    pub jump_prev: Vec<Keybind>,
+    pub show_more: Vec<Keybind>,
+    pub paste: Vec<Keybind>,
+    #[serde(alias = "mark")]
+    pub toggle_select: Vec<Keybind>,
+    pub jump_letter: Vec<Keybind>,
+    pub hide_entry: Vec<Keybind>,
+    pub toggle_top: Vec<Keybind>,
+    pub toggle_sticky: Vec<Keybind>,
+}
+
+impl Default for KeyConfig {
+    fn default() -> Self {
+        return Self {
+            match_by: Default::default(),
+            hint_modifier: "alt".to_owned(),
+            ignore_composing: true,
+            exec: vec![Keybind::new(Code::Enter)],
+            exec_alt: vec![Keybind {
+                mods: Modifiers::SHIFT,
+                key: Code::Enter,
+            }],
+            exit: vec![Keybind::new(Code::Escape)],
+            move_next: vec![Keybind::new(Code::ArrowUp)],
+            move_prev: vec![Keybind::new(Code::ArrowDown)],
+            open_menu: vec![],
+            close_menu: vec![],
+            jump_next: vec![Keybind::new(Code::PageDown)],
+            jump_prev: vec![Keybind::new(Code::PageUp)],
+            show_more: vec![],
+            paste: vec![],
+            toggle_select: vec![Keybind {
+                mods: Modifiers::CONTROL,
+                key: Code::Space,
+            }],
+            jump_letter: vec![],
+            hide_entry: vec![],
+            toggle_top: vec![],
+            toggle_sticky: vec![],
+        };
+    }
+}
+
+impl KeyConfig {
+    /// Modifier Held to Preview Shortcuts and Quick-Exec Entries (if Any)
+    ///
+    /// Shift is never used, as holding it would swallow typed capitals.
+    pub fn hint_mods(&self) -> Option<Modifiers> {
+        mod_from_str(&self.hint_modifier).filter(|m| *m != Modifiers::SHIFT)
+    }
+
+    /// Shortcut Label to Quick-Exec the Result at the Given Index
+    pub fn quick_hint(&self, index: usize) -> Option<String> {
+        let mods = Keybind {
+            mods: self.hint_mods()?,
+            key: Code::Digit1,
+        };
+        let mods = mods.to_string();
+        let mods = mods.trim_end_matches("Digit1");
+        (index < 9).then(|| format!("{mods}{}", index + 1))
+    }
+
+    /// Shortcut Label to Execute the Entry Action at the Given Index
+    pub fn action_hint(&self, index: usize) -> Option<String> {
+        match index {
+            0 => self.exec.first(),
+            n => self.exec_alt.get(n - 1),
+        }
+        .map(|bind| bind.to_string())
+    }
+}
+
+/// Window Dimensions in Logical Pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Window Position in Logical Pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// GUI Desktop Window Configuration Settings
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowConfig {
+    pub title: String,
+    pub size: WindowSize,
+    pub position: WindowPosition,
+    #[serde(default = "_true")]
+    pub focus: bool,
+    pub decorate: bool,
+    pub transparent: bool,
+    #[serde(default = "_true")]
+    pub always_top: bool,
+    #[serde(default)]
+    pub sticky: bool,
+    pub fullscreen: Option<bool>,
+    pub dark_mode: Option<bool>,
+    #[serde(default = "_true")]
+    pub hide_until_ready: bool,
+    #[serde(default)]
+    pub remember_geometry: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            title: "RMenu - App Launcher".to_owned(),
+            size: WindowSize {
+                width: 700.0,
+                height: 400.0,
+            },
+            position: WindowPosition { x: 100.0, y: 100.0 },
+            focus: true,
+            decorate: false,
+            transparent: false,
+            always_top: true,
+            sticky: false,
+            fullscreen: None,
+            dark_mode: None,
+            hide_until_ready: true,
+            remember_geometry: false,
+        }
+    }
+}
+
+/// Cache Settings for Configured RMenu Plugins
+#[derive(Debug, Clone, PartialEq)]
+pub enum CacheSetting {
+    NoCache,
+    Never,
+    OnLogin,
+    AfterSeconds(usize),
+}
+
+impl FromStr for CacheSetting {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "never" => Ok(Self::Never),
+            "false" | "disable" | "disabled" => Ok(Self::NoCache),
+            "true" | "login" | "onlogin" => Ok(Self::OnLogin),
+            _ => {
+                let secs: usize = s
+                    .parse()
+                    .map_err(|_| format!("Invalid Cache Setting: {s:?}"))?;
+                Ok(Self::AfterSeconds(secs))
+            }
+        }
+    }
+}
+
+impl Serialize for CacheSetting {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::NoCache => serializer.serialize_str("false"),
+            Self::Never => serializer.serialize_str("never"),
+            Self::OnLogin => serializer.serialize_str("onlogin"),
+            Self::AfterSeconds(secs) => serializer.serialize_u64(*secs as u64),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CacheSetting {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s: &str = Deserialize::deserialize(deserializer)?;
+        CacheSetting::from_str(s).map_err(D::Error::custom)
+    }
+}
+
+impl Default for CacheSetting {
+    fn default() -> Self {
+        Self::NoCache
+    }
+}
+
+/// RMenu Data-Source Plugin Configuration
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginConfig {
+    #[serde(default)]
+    pub exec: Vec<String>,
+    #[serde(default)]
+    pub cache: CacheSetting,
+    #[serde(default)]
+    pub cache_max_bytes: Option<u64>,
+    #[serde(default)]
+    pub placeholder: Option<String>,
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub blacklist: Vec<String>,
+    #[serde(default)]
+    pub max_entries: Option<usize>,
+    #[serde(default)]
+    pub submit_query: bool,
+    #[serde(default)]
+    pub stream: bool,
+    /// Number Streamed Queries and Discard Answers to Stale ones
+    #[serde(default)]
+    pub request_ids: bool,
+    #[serde(default)]
+    pub service: bool,
+    #[serde(default)]
+    pub static_entries: Vec<StaticEntry>,
+    #[serde(default)]
+    pub http: Option<HttpSource>,
+    #[serde(default)]
+    pub options: Option<Options>,
+}
+
+#[inline]
+fn _http_timeout() -> u64 {
+    10
+}
+
+#[inline]
+fn _auth_header() -> String {
+    "Authorization".to_owned()
+}
+
+/// HTTP(S) Endpoint Returning Plugin Messages instead of a Command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpSource {
+    pub url: String,
+    #[serde(default = "_http_timeout")]
+    pub timeout: u64,
+    #[serde(default = "_auth_header")]
+    pub auth_header: String,
+    #[serde(default)]
+    pub auth_env: Option<String>,
+}
+
+/// Literal Entry Defined Directly within a Plugin Configuration
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaticEntry {
+    pub name: String,
+    pub exec: String,
+    #[serde(default)]
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub terminal: bool,
+}
+
+impl StaticEntry {
+    /// Convert the Definition into a Regular Menu Entry
+    pub fn entry(&self) -> Entry {
+        let mut entry = Entry::new(&self.name, &self.exec, self.comment.as_deref());
+        entry.actions[0].exec = Method::new(self.exec.clone(), self.terminal);
+        match self.icon.as_ref() {
+            Some(icon) if icon.contains('/') => entry.icon = Some(icon.clone()),
+            icon => entry.icon_alt = icon.cloned(),
+        }
+        entry
+    }
+}
+
+/// Behavior when Another RMenu Instance is Already Running
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstanceMode {
+    #[default]
+    Allow,
+    Focus,
+    Replace,
+    Error,
+}
+
+/// User-Interface Implementation used to Display the Menu
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontendKind {
+    #[default]
+    Webview,
+    Native,
+    Tui,
+}
+
+impl FromStr for FrontendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "webview" => Ok(Self::Webview),
+            "native" => Ok(Self::Native),
+            "tui" => Ok(Self::Tui),
+            _ => Err("No Such Frontend".to_owned()),
+        }
+    }
+}
+
+/// Execution Semantics when Multiple Entries are Selected
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MultiExec {
+    #[default]
+    Sequential,
+    Parallel,
+    EchoJoin,
+}
+
+impl FromStr for MultiExec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sequential" => Ok(Self::Sequential),
+            "parallel" => Ok(Self::Parallel),
+            "echo-join" => Ok(Self::EchoJoin),
+            _ => Err("No Such Multi-Exec Mode".to_owned()),
+        }
+    }
+}
+
+#[inline]
+fn _true() -> bool {
+    true
+}
+
+/// Secondary Keys Ordering Ranked Results w/ Equal Scores
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TieBreak {
+    /// Higher numeric `weight` in the entry metadata first
+    Weight,
+    /// More frequently and recently used entries first (w/ `use_history`)
+    Frecency,
+    /// Case-insensitive alphabetical order of the entry names
+    Name,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    pub query: Option<String>,
+    pub restrict: Option<String>,
+    pub restrict_input: bool,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub placeholder: Option<String>,
+    pub prompt: Option<String>,
+    pub prompt_icon: Option<String>,
+    #[serde(default = "_true")]
+    pub use_regex: bool,
+    #[serde(default = "_true")]
+    pub ignore_case: bool,
+    #[serde(alias = "mode")]
+    pub matcher: Option<String>,
+    pub use_acronym: bool,
+    pub acronym_weight: f64,
+    pub tie_break: Vec<TieBreak>,
+    pub password: bool,
+    pub echo_query: bool,
+    pub disable: bool,
+    #[serde(skip)]
+    pub scopes: Vec<Scope>,
+}
+
+/// Entries Loaded from a Single Plugin w/ an Optional Search Prefix
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scope {
+    pub name: String,
+    pub prefix: Option<String>,
+    pub entries: Range<usize>,
+}
+
+impl Scope {
+    /// Split a Plugin Prefix from the Search (if Any)
+    ///
+    /// Returns the remaining search and the range of entries it applies to.
+    pub fn split<'a>(scopes: &[Scope], search: &'a str) -> (&'a str, Option<Range<usize>>) {
+        scopes
+            .iter()
+            .find_map(|scope| {
+                let rest = search.strip_prefix(scope.prefix.as_deref()?)?;
+                Some((rest.trim_start(), Some(scope.entries.clone())))
+            })
+            .unwrap_or((search, None))
+    }
+
+    /// Retrieve the Name of the Plugin the Search Applies to (if Any)
+    ///
+    /// Prefixed searches apply to the matching plugin, and unprefixed
+    /// searches only to a plugin when it is the only one loaded.
+    pub fn plugin<'a>(scopes: &'a [Scope], search: &str) -> Option<&'a str> {
+        let prefixed = scopes.iter().find(|scope| {
+            let prefix = scope.prefix.as_deref();
+            prefix.map(|p| search.starts_with(p)).unwrap_or(false)
+        });
+        match (prefixed, scopes) {
+            (Some(scope), _) | (None, [scope]) => Some(scope.name.as_str()),
+            _ => None,
+        }
+    }
+}
+
+impl SearchConfig {
+    /// Split a Plugin Prefix from the Search (if Any)
+    #[inline]
+    pub fn scope<'a>(&self, search: &'a str) -> (&'a str, Option<Range<usize>>) {
+        Scope::split(&self.scopes, search)
+    }
+
+    /// Retrieve the Name of the Plugin the Search Applies to (if Any)
+    #[inline]
+    pub fn plugin(&self, search: &str) -> Option<&str> {
+        Scope::plugin(&self.scopes, search)
+    }
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            query: Default::default(),
+            restrict: Default::default(),
+            restrict_input: false,
+            min_length: Default::default(),
+            max_length: Default::default(),
+            placeholder: Default::default(),
+            prompt: Default::default(),
+            prompt_icon: Default::default(),
+            use_regex: true,
+            ignore_case: true,
+            matcher: None,
+            use_acronym: false,
+            acronym_weight: 0.5,
+            tie_break: vec![TieBreak::Weight, TieBreak::Frecency, TieBreak::Name],
+            password: false,
+            echo_query: false,
+            disable: false,
+            scopes: vec![],
+        }
+    }
+}
+
+/// Position of the Ellipsis when Shortening Long Text
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ellipsis {
+    /// Keep the start of the text (e.g. `Long Na…`)
+    #[default]
+    End,
+    /// Keep both ends of the text (e.g. `/home/…/file.txt`)
+    Middle,
+}
+
+impl Ellipsis {
+    /// Shorten Text to the Maximum Number of Characters w/ an Ellipsis
+    pub fn apply(&self, text: &str, max: usize) -> String {
+        let len = text.chars().count();
+        if len <= max || max == 0 {
+            return text.to_owned();
+        }
+        let keep = max - 1;
+        match self {
+            Self::End => text.chars().take(keep).chain(['…']).collect(),
+            Self::Middle => {
+                let head: String = text.chars().take(keep - keep / 2).collect();
+                let tail: String = text.chars().skip(len - keep / 2).collect();
+                format!("{head}…{tail}")
+            }
+        }
+    }
+}
+
+impl FromStr for Ellipsis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "end" => Ok(Self::End),
+            "middle" => Ok(Self::Middle),
+            _ => Err("No Such Ellipsis Mode".to_owned()),
+        }
+    }
+}
+
+/// Batching of Entries Streamed to the Menu while they are Read
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateConfig {
+    /// Milliseconds after which Collected Entries are Flushed
+    pub interval: u64,
+    /// Number of Collected Entries Flushed w/o Waiting for the Interval
+    pub batch_size: usize,
+    /// Milliseconds the Menu Waits for the First Answer of a Streaming Plugin
+    pub startup: u64,
+}
+
+impl UpdateConfig {
+    /// Retrieve the Flush Interval as a Duration
+    #[inline]
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval)
+    }
+
+    /// Retrieve the Startup Wait as a Duration
+    #[inline]
+    pub fn startup(&self) -> Duration {
+        Duration::from_millis(self.startup)
+    }
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            interval: 100,
+            batch_size: 1000,
+            startup: 500,
+        }
+    }
+}
+
+/// Entry Text Truncation and Layout Settings
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    pub ellipsis: Ellipsis,
+    pub name_length: Option<usize>,
+    pub comment_length: Option<usize>,
+    pub two_line: bool,
+}
+
+impl DisplayConfig {
+    /// Shorten Text w/o Markup to the Maximum Length (if Any)
+    fn shorten(&self, text: &str, max: Option<usize>) -> String {
+        match max {
+            // markup is left alone to avoid cutting through tags
+            Some(max) if !text.contains('<') => self.ellipsis.apply(text, max),
+            _ => text.to_owned(),
+        }
+    }
+
+    /// Shorten Entry Name to the Configured Length
+    #[inline]
+    pub fn name(&self, name: &str) -> String {
+        self.shorten(name, self.name_length)
+    }
+
+    /// Shorten Entry Comment to the Configured Length
+    #[inline]
+    pub fn comment(&self, comment: &str) -> String {
+        self.shorten(comment, self.comment_length)
+    }
+}
+
+/// Font and Look Settings Exposed to Themes as CSS Variables
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppearanceConfig {
+    pub font_family: Option<String>,
+    pub font_size: Option<f64>,
+    pub font_fallback: Vec<String>,
+    pub system_font: bool,
+}
+
+impl Default for AppearanceConfig {
+    fn default() -> Self {
+        Self {
+            font_family: None,
+            font_size: None,
+            font_fallback: vec![
+                "Noto Color Emoji".to_owned(),
+                "Twemoji".to_owned(),
+                "Noto Sans Symbols 2".to_owned(),
+                "Symbola".to_owned(),
+            ],
+            system_font: true,
+        }
+    }
+}
+
+/// Rendering Strategy Choosing how many Results are Materialized
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Performance {
+    /// Render several screens ahead for jank-free scrolling
+    #[default]
+    Smooth,
+    /// Render little more than the visible rows for the fastest updates
+    LowLatency,
+}
+
+impl Performance {
+    /// Initial Page Size before the Viewport is Known
+    pub fn page_size(&self) -> usize {
+        match self {
+            Self::Smooth => 50,
+            Self::LowLatency => 20,
+        }
+    }
+
+    /// Ratio of a Page Scrolled before the Next Page is Loaded
+    pub fn page_load(&self) -> f64 {
+        match self {
+            Self::Smooth => 0.5,
+            Self::LowLatency => 0.8,
+        }
+    }
+
+    /// Number of Screens Rendered on top of the Visible Rows
+    pub fn prefetch(&self) -> usize {
+        match self {
+            Self::Smooth => 3,
+            Self::LowLatency => 1,
+        }
+    }
+
+    /// Time Budget for Generating a Page of Results
+    pub fn budget(&self) -> Duration {
+        match self {
+            Self::Smooth => Duration::from_millis(16),
+            Self::LowLatency => Duration::from_millis(8),
+        }
+    }
+}
+
+impl FromStr for Performance {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "smooth" => Ok(Self::Smooth),
+            "low-latency" | "lowlatency" => Ok(Self::LowLatency),
+            _ => Err(format!("Invalid Performance: {s:?}")),
+        }
+    }
+}
+
+/// Strategy Deciding when the Next Page of Results is Loaded
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Pagination {
+    /// Load once the selection passed the configured `page_load` ratio
+    #[default]
+    Auto,
+    /// Load w/ the page controls or when selecting the last rendered result
+    Manual,
+    /// Like manual, also loading when scrolling near the end (webview only)
+    Scroll,
+}
+
+impl FromStr for Pagination {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "manual" => Ok(Self::Manual),
+            "scroll" => Ok(Self::Scroll),
+            _ => Err(format!("Invalid Pagination: {s:?}")),
+        }
+    }
+}
+
+/// Global RMenu Complete Configuration
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub performance: Performance,
+    pub page_size: Option<usize>,
+    pub page_load: Option<f64>,
+    pub pagination: Pagination,
+    pub jump_dist: usize,
+    pub wrap_navigation: bool,
+    pub scroll_margin: usize,
+    pub max_entries: Option<usize>,
+    #[serde(default = "_true")]
+    pub use_icons: bool,
+    pub icon_fallback: Option<String>,
+    pub icon_theme: Option<String>,
+    pub icon_size: u32,
+    #[serde(default = "_true")]
+    pub recolor_icons: bool,
+    #[serde(default = "_true")]
+    pub use_comments: bool,
+    #[serde(default = "_true")]
+    pub use_tooltips: bool,
+    pub search: SearchConfig,
+    pub display: DisplayConfig,
+    pub updates: UpdateConfig,
+    pub appearance: AppearanceConfig,
+    pub plugins: BTreeMap<String, PluginConfig>,
+    pub keybinds: KeyConfig,
+    pub window: WindowConfig,
+    pub css: Option<String>,
+    pub theme: Option<String>,
+    pub style: Option<String>,
+    pub terminal: Option<String>,
+    pub privileged: Option<String>,
+    pub instance: InstanceMode,
+    pub frontend: FrontendKind,
+    pub multi_select: bool,
+    pub multi_exec: MultiExec,
+    pub cache_max_bytes: u64,
+    pub persist_hidden: bool,
+    pub keep_open: bool,
+    pub use_history: bool,
+    pub prioritize_plugins: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            performance: Default::default(),
+            page_size: None,
+            page_load: None,
+            pagination: Default::default(),
+            jump_dist: 5,
+            wrap_navigation: false,
+            scroll_margin: 3,
+            max_entries: None,
+            use_icons: true,
+            icon_fallback: None,
+            icon_theme: None,
+            icon_size: 64,
+            recolor_icons: true,
+            use_comments: true,
+            use_tooltips: true,
+            search: Default::default(),
+            display: Default::default(),
+            updates: Default::default(),
+            appearance: Default::default(),
+            plugins: Default::default(),
+            keybinds: Default::default(),
+            window: Default::default(),
+            css: None,
+            theme: None,
+            style: None,
+            terminal: None,
+            privileged: None,
+            instance: Default::default(),
+            frontend: Default::default(),
+            multi_select: false,
+            multi_exec: Default::default(),
+            cache_max_bytes: 256 * 1024 * 1024,
+            persist_hidden: false,
+            keep_open: false,
+            use_history: false,
+            prioritize_plugins: false,
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! cfg_replace {
+    ($key:expr, $repl:expr) => {
+        if $repl.is_some() {
+            $key = $repl.clone();
+        }
+    };
+    ($key:expr, $repl:expr, true) => {
+        if let Some(value) = $repl.as_ref() {
+            $key = value.to_owned();
+        }
+    };
+}
+
+macro_rules! cfg_keybind {
+    ($key:expr, $repl:expr) => {
+        if let Some(bind_strings) = $repl.as_ref() {
+            let mut keybinds = vec![];
+            for bind_str in bind_strings.iter() {
+                let bind = Keybind::from_str(bind_str)?;
+                keybinds.push(bind);
+            }
+            $key = keybinds;
+        }
+    };
+}
+
+impl Config {
+    /// Update Configuration from Options Object
+    pub fn update(&mut self, options: &Options) -> Result<(), String> {
+        cfg_replace!(self.css, options.css);
+        cfg_replace!(self.theme, options.theme);
+        if let Some(style) = options.style.as_ref() {
+            self.style = Some(match self.style.take() {
+                Some(prev) => format!("{prev}\n{style}"),
+                None => style.to_owned(),
+            });
+        }
+        cfg_replace!(self.page_size, options.page_size);
+        cfg_replace!(self.page_load, options.page_load);
+        cfg_replace!(self.jump_dist, options.jump_dist, true);
+        cfg_replace!(self.max_entries, options.max_entries);
+        cfg_replace!(self.icon_fallback, options.icon_fallback);
+        cfg_replace!(self.appearance.font_family, options.font_family);
+        cfg_replace!(self.appearance.font_size, options.font_size);
+        cfg_replace!(self.keep_open, options.keep_open, true);
+        cfg_replace!(self.multi_select, options.multi_select, true);
+        if let Some(mode) = options.multi_exec.as_ref() {
+            self.multi_exec = MultiExec::from_str(mode)?;
+        }
+        // search settings
+        cfg_replace!(self.search.placeholder, options.placeholder);
+        cfg_replace!(self.search.prompt, options.prompt);
+        cfg_replace!(self.search.prompt_icon, options.prompt_icon);
+        cfg_replace!(self.search.restrict, options.search_restrict);
+        cfg_replace!(
+            self.search.restrict_input,
+            options.search_restrict_input,
+            true
+        );
+        cfg_replace!(self.search.min_length, options.search_min_length);
+        cfg_replace!(self.search.max_length, options.search_max_length);
+        cfg_replace!(self.search.matcher, options.search_matcher);
+        cfg_replace!(self.search.password, options.search_password, true);
+        cfg_replace!(self.search.disable, options.disable_search, true);
+        // keybind settings
+        cfg_keybind!(self.keybinds.exec, options.key_exec);
+        cfg_keybind!(self.keybinds.exec_alt, options.key_exec_alt);
+        cfg_keybind!(self.keybinds.exit, options.key_exit);
+        cfg_keybind!(self.keybinds.move_next, options.key_move_next);
+        cfg_keybind!(self.keybinds.move_prev, options.key_move_prev);
+        cfg_keybind!(self.keybinds.open_menu, options.key_open_menu);
+        cfg_keybind!(self.keybinds.close_menu, options.key_close_menu);
+        cfg_keybind!(self.keybinds.jump_next, options.key_jump_next);
+        cfg_keybind!(self.keybinds.jump_prev, options.key_jump_prev);
+        cfg_keybind!(self.keybinds.show_more, options.key_show_more);
+        cfg_keybind!(self.keybinds.paste, options.key_paste);
+        cfg_keybind!(self.keybinds.toggle_select, options.key_toggle_select);
+        cfg_keybind!(self.keybinds.jump_letter, options.key_jump_letter);
+        cfg_keybind!(self.keybinds.hide_entry, options.key_hide_entry);
+        cfg_keybind!(self.keybinds.toggle_top, options.key_toggle_top);
+        cfg_keybind!(self.keybinds.toggle_sticky, options.key_toggle_sticky);
+        // window settings
+        cfg_replace!(self.window.title, options.title, true);
+        cfg_replace!(self.window.decorate, options.decorate, true);
+        cfg_replace!(self.window.fullscreen, options.fullscreen);
+        cfg_replace!(self.window.transparent, options.transparent, true);
+        cfg_replace!(self.window.size.width, options.window_width, true);
+        cfg_replace!(self.window.size.height, options.window_height, true);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ellipsis() {
+        let path = "/home/user/documents/file.txt";
+        assert_eq!(Ellipsis::End.apply(path, 12), "/home/user/…");
+        assert_eq!(Ellipsis::Middle.apply(path, 12), "/home/…e.txt");
+        assert_eq!(Ellipsis::Middle.apply("short", 12), "short");
+        let display = DisplayConfig {
+            name_length: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(display.name("<b>bold</b>"), "<b>bold</b>");
+        assert_eq!(display.name("Firefox"), "Fir…");
+    }
+
+    #[test]
+    fn test_hint_modifier() {
+        use serde::de::value::{Error, StrDeserializer};
+        let parse = |s| hint_modifier(StrDeserializer::<Error>::new(s));
+        assert_eq!(parse("ctrl").unwrap(), "ctrl");
+        assert!(parse("Shift").is_err());
+        let keybinds = KeyConfig {
+            hint_modifier: "shift".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(keybinds.hint_mods(), None);
+    }
+
+    #[test]
+    fn test_multi_select_options() {
+        let options = Options {
+            multi_select: Some(true),
+            multi_exec: Some("echo-join".to_owned()),
+            key_toggle_select: Some(vec!["Ctrl+KeyM".to_owned()]),
+            ..Default::default()
+        };
+        let mut config = Config::default();
+        config.update(&options).unwrap();
+        assert!(config.multi_select);
+        assert_eq!(config.multi_exec, MultiExec::EchoJoin);
+        let mark = Keybind::from_str("Ctrl+KeyM").unwrap();
+        assert_eq!(config.keybinds.toggle_select, vec![mark]);
+        let invalid = Options {
+            multi_exec: Some("bogus".to_owned()),
+            ..Default::default()
+        };
+        assert!(config.update(&invalid).is_err());
+    }
+}
@@ -0,0 +1,68 @@
+//! RMenu Internal Event Bus
+//!
+//! Modules, in-process plugins and frontends subscribe to state changes
+//! here instead of polling or reaching into each other's state.
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+use rmenu_plugin::Action;
+
+/// Subscriber Callback Invoked for every Emitted Event
+pub type Subscriber = Arc<dyn Fn(&Event) + Send + Sync>;
+
+static SUBSCRIBERS: Lazy<Mutex<Vec<Subscriber>>> = Lazy::new(|| Mutex::new(vec![]));
+
+/// Internal State Change Notification
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// Search Query was Accepted and Updated
+    QueryChanged(String),
+    /// Entries were (Re)Loaded w/ the Given Number of Entries
+    EntriesUpdated(usize),
+    /// Selected Position/SubPosition Changed
+    SelectionMoved { pos: usize, subpos: usize },
+    /// Action is being Executed
+    ActionExecuted(Action),
+}
+
+/// Subscribe to all Future Events
+pub fn subscribe<F>(subscriber: F)
+where
+    F: Fn(&Event) + Send + Sync + 'static,
+{
+    SUBSCRIBERS
+        .lock()
+        .expect("Failed to Access Global Mutex")
+        .push(Arc::new(subscriber));
+}
+
+/// Notify all Subscribers of the Specified Event
+///
+/// Subscribers are called outside the lock so they may emit or
+/// subscribe themselves.
+pub fn emit(event: Event) {
+    let subscribers: Vec<Subscriber> = SUBSCRIBERS
+        .lock()
+        .expect("Failed to Access Global Mutex")
+        .clone();
+    for subscriber in subscribers {
+        subscriber(&event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscribe() {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let copy = Arc::clone(&seen);
+        subscribe(move |e| copy.lock().unwrap().push(e.clone()));
+        emit(Event::EntriesUpdated(3));
+        emit(Event::QueryChanged("test".to_owned()));
+        let seen = seen.lock().unwrap();
+        assert!(seen.contains(&Event::EntriesUpdated(3)));
+        assert!(seen.contains(&Event::QueryChanged("test".to_owned())));
+    }
+}
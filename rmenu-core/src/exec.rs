@@ -0,0 +1,432 @@
+//! Command Resolution for Entry Actions
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use once_cell::sync::Lazy;
+use rmenu_plugin::{Action, Entry, Method};
+use shell_words::{join, quote, split};
+use strfmt::strfmt;
+use which::which;
+
+use crate::config::Config;
+
+static SANDBOXED: Lazy<bool> = Lazy::new(|| Path::new("/.flatpak-info").exists());
+
+/// Prefix of Nested Menus Showing the Entries of a Configured Plugin
+pub static PLUGIN_MENU: &'static str = "plugin:";
+
+/// Check if RMenu is Running inside a Flatpak Sandbox
+#[inline]
+pub fn sandboxed() -> bool {
+    *SANDBOXED
+}
+
+/// Check if the Tool is Installed where Commands will Run
+///
+/// Inside a sandbox the host is searched, since that is where
+/// commands are spawned.
+fn exists(tool: &str) -> bool {
+    if !sandboxed() {
+        return which(tool).is_ok();
+    }
+    Command::new("flatpak-spawn")
+        .args(["--host", "sh", "-c", "command -v \"$0\"", tool])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// Route the Command to the Host when Running inside a Sandbox
+pub fn host_args(args: Vec<String>) -> Vec<String> {
+    match sandboxed() {
+        true => ["flatpak-spawn", "--host"]
+            .into_iter()
+            .map(|s| s.to_owned())
+            .chain(args)
+            .collect(),
+        false => args,
+    }
+}
+
+/// Find Best Terminal To Execute
+fn find_terminal() -> String {
+    vec![
+        ("alacritty", "-e {cmd}"),
+        ("kitty", "{cmd}"),
+        ("gnome-terminal", "-x {cmd}"),
+        ("foot", "-e {cmd}"),
+        ("xterm", "-C {cmd}"),
+    ]
+    .into_iter()
+    .find(|(t, _)| exists(t))
+    .map(|(t, v)| match sandboxed() {
+        true => format!("{t} {v}"),
+        false => {
+            let path = which(t).expect("Failed to Find Terminal Path");
+            format!("{} {v}", path.to_string_lossy())
+        }
+    })
+    .expect("Failed to Find Terminal Executable!")
+}
+
+/// Find Tool to Run Commands w/ Elevated Privileges and an Auth Prompt
+///
+/// `sudo -A` is only used when an askpass helper is configured, since
+/// there is no terminal for sudo to prompt in.
+fn find_privileged() -> Option<String> {
+    if exists("pkexec") {
+        return Some("pkexec {cmd}".to_owned());
+    }
+    let askpass = std::env::var_os("SUDO_ASKPASS").is_some();
+    (askpass && exists("sudo")).then(|| "sudo -A {cmd}".to_owned())
+}
+
+/// Substitute the Command into a `{cmd}` Template and Split Arguments
+fn format_args(template: &str, exec: &str) -> Option<Vec<String>> {
+    let mut args = HashMap::new();
+    args.insert("cmd".to_string(), exec.to_owned());
+    match strfmt(template, &args) {
+        Ok(command) => parse_args(&command),
+        Err(err) => {
+            log::error!("{template:?} invalid command template {err}");
+            None
+        }
+    }
+}
+
+/// Build Command Typing the Text into the Focused Window (Wayland First)
+///
+/// The short initial sleep lets focus return to the previous window
+/// once the menu has closed.
+fn find_typer(text: &str) -> Option<Vec<String>> {
+    let wayland = vec!["wtype", "-s", "100", "--", text];
+    let x11 = vec![
+        "xdotool",
+        "sleep",
+        "0.1",
+        "type",
+        "--clearmodifiers",
+        "--",
+        text,
+    ];
+    let typers = match std::env::var("WAYLAND_DISPLAY").is_ok() {
+        true => [wayland, x11],
+        false => [x11, wayland],
+    };
+    typers
+        .into_iter()
+        .find(|args| exists(args[0]))
+        .map(|args| args.into_iter().map(|s| s.to_owned()).collect())
+}
+
+/// Split the Command into its Arguments (`None` when Quoting is Broken)
+#[inline]
+fn parse_args(exec: &str) -> Option<Vec<String>> {
+    match split(exec) {
+        Ok(args) => Some(args),
+        Err(err) => {
+            log::error!("{:?} invalid command {err}", exec);
+            None
+        }
+    }
+}
+
+/// Resolve the Command Arguments for the Specified Method
+///
+/// Returns `None` for methods that do not spawn a command, for commands
+/// that cannot be split into arguments, or when no tool is available to
+/// type text or elevate privileges.
+pub fn command_args(method: &Method, config: &Config) -> Option<Vec<String>> {
+    let args = match method {
+        Method::Run(exec) | Method::Pipe(exec) | Method::PipeInput(exec, _) => parse_args(&exec),
+        Method::Terminal(exec) => {
+            let terminal = config.terminal.clone().unwrap_or_else(find_terminal);
+            format_args(&terminal, exec)
+        }
+        Method::RunPrivileged(exec) => {
+            let Some(template) = config.privileged.clone().or_else(find_privileged) else {
+                log::error!("no privilege tool available (install polkit or set SUDO_ASKPASS)");
+                return None;
+            };
+            format_args(&template, exec)
+        }
+        Method::Type(text) => {
+            let args = find_typer(text);
+            if args.is_none() {
+                log::error!("no typing tool available (install wtype or xdotool)");
+            }
+            args
+        }
+        Method::Echo(_) | Method::Menu(_) | Method::RefreshSource(_) | Method::DisableSource(_) => {
+            None
+        }
+    };
+    args.map(host_args)
+}
+
+/// Retrieve the Value of an Entry Field Placeholder (e.g. `meta.path`)
+fn placeholder(field: &str, entry: &Entry, query: &str) -> Option<String> {
+    if let Some(key) = field.strip_prefix("meta.") {
+        return entry.meta.get(key).cloned();
+    }
+    match field {
+        "name" => Some(entry.name.clone()),
+        "comment" => Some(entry.comment.clone().unwrap_or_default()),
+        "uri" => Some(entry.uri.clone().unwrap_or_default()),
+        "source" => Some(entry.source.clone().unwrap_or_default()),
+        "query" => Some(query.to_owned()),
+        _ => None,
+    }
+}
+
+/// Substitute Entry Fields into the Placeholders of a String
+///
+/// Unknown placeholders are left untouched, so literal braces (e.g. in
+/// `awk '{print}'`) keep working. Values are shell-quoted when `quoted`.
+fn fill(template: &str, entry: &Entry, query: &str, quoted: bool) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let value = tail
+            .find('}')
+            .and_then(|end| Some((end, placeholder(&tail[1..end], entry, query)?)));
+        match value {
+            Some((end, value)) => {
+                match quoted {
+                    true => out.push_str(&quote(&value)),
+                    false => out.push_str(&value),
+                }
+                rest = &tail[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Resolve `{name}`, `{comment}`, `{uri}`, `{source}`, `{query}` and
+/// `{meta.<key>}` Placeholders of the Method for the Executed Entry
+///
+/// Command strings receive shell-quoted values while echoed or typed
+/// text receives them verbatim.
+pub fn fill_placeholders(method: &Method, entry: &Entry, query: &str) -> Method {
+    match method {
+        Method::Run(exec) => Method::Run(fill(exec, entry, query, true)),
+        Method::Terminal(exec) => Method::Terminal(fill(exec, entry, query, true)),
+        Method::RunPrivileged(exec) => Method::RunPrivileged(fill(exec, entry, query, true)),
+        Method::Menu(exec) => Method::Menu(fill(exec, entry, query, true)),
+        Method::Pipe(exec) => Method::Pipe(fill(exec, entry, query, true)),
+        Method::Echo(echo) => Method::Echo(fill(echo, entry, query, false)),
+        Method::Type(text) => Method::Type(fill(text, entry, query, false)),
+        method => method.clone(),
+    }
+}
+
+/// Resolve the Command Printing the Entries of a Nested Menu (if Any)
+///
+/// References to configured plugins (`plugin:<name>`) are kept as-is for
+/// the frontend to load the plugin itself.
+pub fn menu_args(method: &Method) -> Option<Vec<String>> {
+    match method {
+        Method::Menu(exec) if exec.starts_with(PLUGIN_MENU) => Some(vec![exec.trim().to_owned()]),
+        Method::Menu(exec) => parse_args(exec).map(host_args),
+        _ => None,
+    }
+}
+
+/// Entry Metadata Describing the Desktop File an Entry was Read from
+pub static DESKTOP_FILE: &'static str = "desktop_file";
+pub static DESKTOP_ICON: &'static str = "desktop_icon";
+pub static DESKTOP_PATH: &'static str = "desktop_path";
+pub static DESKTOP_DBUS: &'static str = "desktop_dbus";
+/// Prefix of the Metadata Mapping Action Names to Desktop Action IDs
+pub static DESKTOP_ACTION: &'static str = "desktop_action.";
+
+/// Platform Data Passed to every `org.freedesktop.Application` Method
+static DBUS_PLATFORM: &'static str = "@a{sv} {}";
+
+/// Retrieve the Local Files and URIs the Entry is Opened with
+fn desktop_targets(entry: &Entry) -> (Vec<String>, Vec<String>) {
+    let Some(uri) = entry.uri.as_ref().filter(|u| !u.is_empty()) else {
+        return (vec![], vec![]);
+    };
+    let file = match uri.strip_prefix("file://") {
+        Some(path) => Some(path.to_owned()),
+        None if uri.starts_with('/') => Some(uri.to_owned()),
+        None => None,
+    };
+    (file.into_iter().collect(), vec![uri.to_owned()])
+}
+
+/// Expand the Field Codes of a Desktop File Exec Value into Arguments
+///
+/// File and URI lists (`%F`, `%U`) and the icon (`%i`) only expand as
+/// standalone arguments. Deprecated and unknown codes are removed, as
+/// are the arguments left empty by removing them.
+pub fn expand_field_codes(exec: &str, entry: &Entry) -> Option<Vec<String>> {
+    let (files, uris) = desktop_targets(entry);
+    let first = |targets: &[String]| targets.first().cloned().unwrap_or_default();
+    let mut args = vec![];
+    for arg in split(exec).ok()? {
+        match arg.as_str() {
+            "%F" => args.extend(files.iter().cloned()),
+            "%U" => args.extend(uris.iter().cloned()),
+            "%i" => {
+                if let Some(icon) = entry.meta.get(DESKTOP_ICON).filter(|i| !i.is_empty()) {
+                    args.extend(["--icon".to_owned(), icon.to_owned()]);
+                }
+            }
+            _ => {
+                let mut out = String::with_capacity(arg.len());
+                let mut chars = arg.chars();
+                while let Some(c) = chars.next() {
+                    if c != '%' {
+                        out.push(c);
+                        continue;
+                    }
+                    match chars.next() {
+                        Some('%') => out.push('%'),
+                        Some('f') => out.push_str(&first(&files)),
+                        Some('u') => out.push_str(&first(&uris)),
+                        Some('c') => out.push_str(&entry.name),
+                        Some('k') => out.push_str(
+                            entry
+                                .meta
+                                .get(DESKTOP_FILE)
+                                .map(|s| s.as_str())
+                                .unwrap_or_default(),
+                        ),
+                        _ => {}
+                    }
+                }
+                if !out.is_empty() || arg.is_empty() {
+                    args.push(out);
+                }
+            }
+        }
+    }
+    (!args.is_empty()).then_some(args)
+}
+
+/// Quote a String as a GVariant Text Literal
+fn gvariant_str(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Build the Command Activating a DBusActivatable Application
+///
+/// Applications are addressed by their desktop file id, which has to be
+/// a valid bus name, and fall back to their Exec value otherwise.
+fn dbus_activate(action: &Action, entry: &Entry) -> Option<Vec<String>> {
+    if entry.meta.get(DESKTOP_DBUS).map(|s| s.as_str()) != Some("true") {
+        return None;
+    }
+    let file = entry.meta.get(DESKTOP_FILE)?;
+    let id = Path::new(file).file_stem()?.to_str()?;
+    if !id.contains('.') || !exists("gdbus") {
+        return None;
+    }
+    let object = format!("/{}", id.replace('.', "/").replace('-', "_"));
+    let mut args: Vec<String> = ["gdbus", "call", "--session", "--dest", id]
+        .into_iter()
+        .chain(["--object-path", object.as_str(), "--method"])
+        .map(|s| s.to_owned())
+        .collect();
+    let (_, uris) = desktop_targets(entry);
+    let target = entry.meta.get(&format!("{DESKTOP_ACTION}{}", action.name));
+    match target {
+        Some(name) => args.extend([
+            "org.freedesktop.Application.ActivateAction".to_owned(),
+            gvariant_str(name),
+            "@av []".to_owned(),
+        ]),
+        None if !uris.is_empty() => {
+            let uris: Vec<String> = uris.iter().map(|u| gvariant_str(u)).collect();
+            args.push("org.freedesktop.Application.Open".to_owned());
+            args.push(format!("[{}]", uris.join(", ")));
+        }
+        None => args.push("org.freedesktop.Application.Activate".to_owned()),
+    }
+    args.push(DBUS_PLATFORM.to_owned());
+    Some(args)
+}
+
+/// Resolve the Action of an Entry Read from a Desktop File
+///
+/// Returns `None` for entries w/o a `desktop_file`. DBusActivatable
+/// applications are activated over the session bus, while others have
+/// their Exec field codes expanded and run from the `Path` directory.
+pub fn desktop_action(action: &Action, entry: &Entry) -> Option<Action> {
+    entry.meta.get(DESKTOP_FILE)?;
+    let (exec, terminal) = match &action.exec {
+        Method::Run(exec) => (exec, false),
+        Method::Terminal(exec) => (exec, true),
+        _ => return None,
+    };
+    let mut resolved = action.clone();
+    if let Some(args) = dbus_activate(action, entry) {
+        resolved.exec = Method::Run(join(args));
+        return Some(resolved);
+    }
+    resolved.exec = Method::new(join(expand_field_codes(exec, entry)?), terminal);
+    if let Some(dir) = entry.meta.get(DESKTOP_PATH).filter(|d| !d.is_empty()) {
+        resolved.cwd = Some(PathBuf::from(dir));
+    }
+    Some(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_field_codes() {
+        let mut entry = Entry::new("Editor", "edit --name=%c %i %F %k 100%%", None);
+        entry
+            .meta
+            .insert(DESKTOP_FILE.to_owned(), "/apps/edit.desktop".to_owned());
+        entry
+            .meta
+            .insert(DESKTOP_ICON.to_owned(), "accessories".to_owned());
+        let args = expand_field_codes("edit --name=%c %i %F %k 100%% %x", &entry).unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "edit",
+                "--name=Editor",
+                "--icon",
+                "accessories",
+                "/apps/edit.desktop",
+                "100%"
+            ]
+        );
+        entry.uri = Some("file:///tmp/a file.txt".to_owned());
+        let args = expand_field_codes("edit %f \"%u\" %U", &entry).unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "edit",
+                "/tmp/a file.txt",
+                "file:///tmp/a file.txt",
+                "file:///tmp/a file.txt"
+            ]
+        );
+    }
+    #[test]
+    fn test_invalid_command() {
+        let config = Config::default();
+        let broken = "echo 'unterminated";
+        assert!(command_args(&Method::Run(broken.to_owned()), &config).is_none());
+        assert!(menu_args(&Method::Menu(broken.to_owned())).is_none());
+        assert!(format_args("{cmd} {missing}", "ls").is_none());
+    }
+}
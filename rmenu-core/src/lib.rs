@@ -0,0 +1,9 @@
+//! RMenu UI-Agnostic Core
+//!
+//! Configuration, search, selection state, keybind dispatch and command
+//! resolution shared by every rmenu frontend.
+pub mod config;
+pub mod events;
+pub mod exec;
+pub mod search;
+pub mod state;
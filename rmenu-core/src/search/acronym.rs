@@ -0,0 +1,83 @@
+//! Word-Boundary Anchored Acronym Scoring
+use std::collections::HashMap;
+
+/// Split Text into Lists of Characters for each Word
+fn words(text: &str) -> Vec<Vec<char>> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.chars().collect())
+        .collect()
+}
+
+/// Memoized Acronym Search over the Words of a Single Text
+struct Acronym<'a> {
+    words: &'a [Vec<char>],
+    query: &'a [char],
+    memo: HashMap<(usize, usize, usize), Option<usize>>,
+}
+
+impl<'a> Acronym<'a> {
+    /// Find Maximum Number of Word-Starts Matching the Remaining Query
+    ///
+    /// Each query character must either begin a later word or directly
+    /// continue the prefix of the current word. Results are memoized by
+    /// word, offset and query position, so repeated initials cannot
+    /// backtrack exponentially.
+    fn hits(&mut self, word: usize, offset: usize, pos: usize) -> Option<usize> {
+        let Some(c) = self.query.get(pos).copied() else {
+            return Some(0);
+        };
+        if let Some(hits) = self.memo.get(&(word, offset, pos)) {
+            return *hits;
+        }
+        // continue prefix of current word
+        let words = self.words;
+        let cont = match offset > 0 && words.get(word).and_then(|w| w.get(offset)) == Some(&c) {
+            true => self.hits(word, offset + 1, pos + 1),
+            false => None,
+        };
+        // start a new word
+        let start = match offset {
+            0 => word,
+            _ => word + 1,
+        };
+        let next = (start..words.len())
+            .filter(|i| words[*i].first() == Some(&c))
+            .filter_map(|i| self.hits(i, 1, pos + 1).map(|h| h + 1))
+            .max();
+        let hits = cont.max(next);
+        self.memo.insert((word, offset, pos), hits);
+        hits
+    }
+}
+
+/// Score Word-Boundary Anchored Subsequence Match (e.g. `gim` for
+/// `GNU Image Manipulation Program`) as Ratio of Word-Start Matches
+pub fn acronym_score(text: &str, search: &str) -> Option<f64> {
+    let query: Vec<char> = search.chars().collect();
+    if query.is_empty() {
+        return None;
+    }
+    let words = words(text);
+    let mut acronym = Acronym {
+        words: &words,
+        query: &query,
+        memo: HashMap::new(),
+    };
+    let hits = acronym.hits(0, 0, 0)?;
+    Some(hits as f64 / query.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acronym_repeated_initials() {
+        let text = vec!["aa"; 60].join(" ");
+        let query = format!("{}b", "a".repeat(30));
+        assert_eq!(acronym_score(&text, &query), None);
+        assert_eq!(acronym_score(&text, "aaa"), Some(1.0));
+        assert_eq!(acronym_score("gnu image", "gnim"), Some(0.5));
+    }
+}
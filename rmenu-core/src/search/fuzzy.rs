@@ -0,0 +1,69 @@
+//! Subsequence Fuzzy Matcher
+use super::{normalize, Matcher};
+use crate::config::SearchConfig;
+
+/// Bonus for a Matched Character Directly Following the Previous Match
+const CONSECUTIVE_BONUS: f64 = 1.0;
+/// Bonus for a Matched Character at the Start of a Word
+const BOUNDARY_BONUS: f64 = 1.0;
+
+/// Score Query as In-Order Subsequence of Text
+///
+/// Scores are normalized to `0.0..=1.0`, where contiguous matches
+/// starting on word boundaries score highest.
+pub fn fuzzy_score(text: &str, query: &str) -> Option<f64> {
+    let query: Vec<char> = query.chars().collect();
+    if query.is_empty() {
+        return Some(1.0);
+    }
+    let mut qi = 0;
+    let mut total = 0.0;
+    let mut last: Option<usize> = None;
+    let mut prev: Option<char> = None;
+    for (i, c) in text.chars().enumerate() {
+        if qi < query.len() && c == query[qi] {
+            let boundary = match prev {
+                None => true,
+                Some(p) => !p.is_alphanumeric() || (p.is_lowercase() && c.is_uppercase()),
+            };
+            total += 1.0;
+            if boundary {
+                total += BOUNDARY_BONUS;
+            }
+            if last.map(|l| l + 1 == i).unwrap_or(false) {
+                total += CONSECUTIVE_BONUS;
+            }
+            last = Some(i);
+            qi += 1;
+        }
+        prev = Some(c);
+    }
+    let max = query.len() as f64 * (1.0 + BOUNDARY_BONUS + CONSECUTIVE_BONUS);
+    (qi == query.len()).then_some(total / max)
+}
+
+/// Match Text Containing the Search as a Subsequence
+struct Fuzzy {
+    search: String,
+}
+
+impl Matcher for Fuzzy {
+    fn score(&self, text: &str) -> Option<f64> {
+        fuzzy_score(text, &self.search)
+    }
+    fn ranked(&self) -> bool {
+        true
+    }
+    fn incremental(&self) -> bool {
+        true
+    }
+}
+
+/// Build new Fuzzy Matcher
+pub fn new(cfg: &SearchConfig, search: &str) -> Box<dyn Matcher> {
+    let search = match cfg.ignore_case {
+        true => normalize(search),
+        false => search.to_owned(),
+    };
+    Box::new(Fuzzy { search })
+}
@@ -0,0 +1,126 @@
+//! Matcher Supporting the fzf Extended-Search Syntax
+//!
+//! Space separated terms must all match, `|` separates alternatives:
+//!
+//! | Term     | Meaning                        |
+//! | -------- | ------------------------------ |
+//! | `abc`    | fuzzy match                    |
+//! | `'abc`   | exact substring match          |
+//! | `^abc`   | prefix match                   |
+//! | `abc$`   | suffix match                   |
+//! | `^abc$`  | equal match                    |
+//! | `!abc`   | does not contain `abc`         |
+//! | `!^abc`  | does not start with `abc`      |
+//! | `!abc$`  | does not end with `abc`        |
+use super::fuzzy::fuzzy_score;
+use super::{normalize, Matcher};
+use crate::config::SearchConfig;
+
+/// Comparison used by a Search Term
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Fuzzy,
+    Exact,
+    Prefix,
+    Suffix,
+    Equal,
+}
+
+/// Single Search Term from the Extended-Search Syntax
+#[derive(Debug)]
+struct Term {
+    kind: Kind,
+    text: String,
+    inverse: bool,
+}
+
+impl Term {
+    fn parse(token: &str) -> Self {
+        let (inverse, token) = match token.strip_prefix('!') {
+            Some(t) => (true, t),
+            None => (false, token),
+        };
+        let (kind, text) = if let Some(t) = token.strip_prefix('\'') {
+            (Kind::Exact, t)
+        } else if let Some(t) = token.strip_prefix('^') {
+            match t.strip_suffix('$') {
+                Some(t) => (Kind::Equal, t),
+                None => (Kind::Prefix, t),
+            }
+        } else if let Some(t) = token.strip_suffix('$') {
+            (Kind::Suffix, t)
+        } else if inverse {
+            (Kind::Exact, token)
+        } else {
+            (Kind::Fuzzy, token)
+        };
+        Self {
+            kind,
+            text: text.to_owned(),
+            inverse,
+        }
+    }
+
+    /// Score Text (which is already Normalized if Required)
+    fn score(&self, text: &str) -> Option<f64> {
+        let term = self.text.as_str();
+        let score = match self.kind {
+            Kind::Fuzzy => fuzzy_score(text, term),
+            Kind::Exact => text.contains(term).then_some(1.0),
+            Kind::Prefix => text.starts_with(term).then_some(1.0),
+            Kind::Suffix => text.ends_with(term).then_some(1.0),
+            Kind::Equal => (text == term).then_some(1.0),
+        };
+        match self.inverse {
+            true => score.is_none().then_some(1.0),
+            false => score,
+        }
+    }
+}
+
+/// Match Text against every Group of Alternative Terms
+struct Fzf {
+    groups: Vec<Vec<Term>>,
+}
+
+impl Matcher for Fzf {
+    fn score(&self, text: &str) -> Option<f64> {
+        let mut total = 0.0;
+        for group in self.groups.iter() {
+            total += group
+                .iter()
+                .filter_map(|t| t.score(text))
+                .reduce(f64::max)?;
+        }
+        match self.groups.is_empty() {
+            true => Some(1.0),
+            false => Some(total / self.groups.len() as f64),
+        }
+    }
+    fn ranked(&self) -> bool {
+        true
+    }
+}
+
+/// Build new fzf-Syntax Matcher
+pub fn new(cfg: &SearchConfig, search: &str) -> Box<dyn Matcher> {
+    let search = match cfg.ignore_case {
+        true => normalize(search),
+        false => search.to_owned(),
+    };
+    let mut groups: Vec<Vec<Term>> = vec![];
+    let mut alternative = false;
+    for token in search.split_whitespace() {
+        if token == "|" {
+            alternative = true;
+            continue;
+        }
+        let term = Term::parse(token);
+        match (alternative, groups.last_mut()) {
+            (true, Some(group)) => group.push(term),
+            _ => groups.push(vec![term]),
+        }
+        alternative = false;
+    }
+    Box::new(Fzf { groups })
+}
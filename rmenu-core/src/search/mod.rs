@@ -0,0 +1,256 @@
+//! RMENU Entry Search Function Implementaton
+//!
+//! Search algorithms implement [`Matcher`] and are selected by name
+//! from the matcher registry. Additional matchers (e.g. behind cargo
+//! features) only need to be added with [`register`].
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use rmenu_plugin::Entry;
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::config::{Config, SearchConfig, TieBreak};
+
+mod acronym;
+mod fuzzy;
+mod fzf;
+mod passthrough;
+mod pattern;
+mod substring;
+
+pub use acronym::acronym_score;
+
+/// Normalize Text (NFKD and Lowercase) for Case-Insensitive Matching
+pub fn normalize(text: &str) -> String {
+    text.nfkd().collect::<String>().to_lowercase()
+}
+
+/// Pre-Normalized Copies of Entry Text
+///
+/// Computed once when entries are loaded (and stored in the plugin
+/// cache) so case-insensitive searches never re-normalize entries.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Normalized {
+    pub name: String,
+    pub comment: Option<String>,
+    /// Usage Score from the History (Assigned after Loading)
+    #[serde(skip)]
+    pub frecency: f64,
+}
+
+impl Normalized {
+    /// Normalize Name and Comment of the Specified Entry
+    pub fn new(entry: &Entry) -> Self {
+        Self {
+            name: normalize(&entry.name),
+            comment: entry.comment.as_deref().map(normalize),
+            frecency: 0.0,
+        }
+    }
+}
+
+/// Retrieve the Numeric Weight a Plugin Assigned to the Entry (if Any)
+#[inline]
+fn weight(entry: &Entry) -> f64 {
+    entry
+        .meta
+        .get("weight")
+        .and_then(|w| w.parse().ok())
+        .unwrap_or(0.0)
+}
+
+/// Search Algorithm used to Match and Score Entry Text
+///
+/// When `ignore_case` is enabled matchers are given [`normalize`]d text
+/// and are expected to normalize the search-string the same way.
+pub trait Matcher {
+    /// Score Text against the Search (`None` if it does not Match)
+    fn score(&self, text: &str) -> Option<f64>;
+    /// Check if Scores should be used to Re-Order Results
+    fn ranked(&self) -> bool {
+        false
+    }
+    /// Check if Appending to the Search can only Narrow the Matches
+    ///
+    /// Incremental matchers allow refiltering only the previous
+    /// results when the user types an additional character.
+    fn incremental(&self) -> bool {
+        false
+    }
+}
+
+/// Constructor for a Matcher from Search Settings and Search-String
+pub type MatcherFactory = fn(&SearchConfig, &str) -> Box<dyn Matcher>;
+
+static REGISTRY: Lazy<Mutex<BTreeMap<String, MatcherFactory>>> =
+    Lazy::new(|| Mutex::new(builtin()));
+
+/// Generate Registry of Built-In Matchers
+fn builtin() -> BTreeMap<String, MatcherFactory> {
+    let mut map: BTreeMap<String, MatcherFactory> = BTreeMap::new();
+    map.insert("regex".to_owned(), pattern::new);
+    map.insert("substring".to_owned(), substring::new);
+    map.insert("fuzzy".to_owned(), fuzzy::new);
+    map.insert("fzf".to_owned(), fzf::new);
+    map.insert("extended".to_owned(), fzf::new);
+    map.insert("passthrough".to_owned(), passthrough::new);
+    map
+}
+
+/// Register Matcher under the Specified Name (Replacing Existing)
+#[allow(dead_code)]
+pub fn register(name: &str, factory: MatcherFactory) {
+    REGISTRY
+        .lock()
+        .expect("Failed to Access Global Mutex")
+        .insert(name.to_owned(), factory);
+}
+
+/// Retrieve Names of all Registered Matchers
+pub fn matchers() -> Vec<String> {
+    REGISTRY
+        .lock()
+        .expect("Failed to Access Global Mutex")
+        .keys()
+        .cloned()
+        .collect()
+}
+
+/// Determine Matcher Name from Search Settings
+fn matcher_name(cfg: &SearchConfig) -> &str {
+    match cfg.matcher.as_deref() {
+        Some(name) => name,
+        None if cfg.use_regex => "regex",
+        None => "substring",
+    }
+}
+
+/// Build Configured Matcher (Falling Back to Substring if Unknown)
+fn new_matcher(cfg: &SearchConfig, search: &str) -> Box<dyn Matcher> {
+    let name = matcher_name(cfg);
+    let factory = REGISTRY
+        .lock()
+        .expect("Failed to Access Global Mutex")
+        .get(name)
+        .copied();
+    match factory {
+        Some(factory) => factory(cfg, search),
+        None => {
+            log::warn!("unknown matcher {name:?}, falling back to substring");
+            substring::new(cfg, search)
+        }
+    }
+}
+
+/// Select the Highest of two Optional Scores
+#[inline]
+fn best(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(f64::max(a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Compiled Search used to Filter and Rank Entries
+pub struct Search {
+    matcher: Box<dyn Matcher>,
+    query: String,
+    ignore_case: bool,
+    acronym: Option<f64>,
+    tie_break: Vec<TieBreak>,
+}
+
+impl Search {
+    /// Compile new Search based on Configurtaion Settings and Search-String
+    pub fn new(cfg: &Config, search: &str) -> Self {
+        Self {
+            matcher: new_matcher(&cfg.search, search),
+            query: match cfg.search.ignore_case {
+                true => normalize(search),
+                false => search.to_owned(),
+            },
+            ignore_case: cfg.search.ignore_case,
+            acronym: cfg.search.use_acronym.then_some(cfg.search.acronym_weight),
+            tie_break: cfg.search.tie_break.clone(),
+        }
+    }
+
+    /// Check if Results should be Re-Ordered by Score
+    pub fn ranked(&self) -> bool {
+        self.acronym.is_some() || self.matcher.ranked()
+    }
+
+    /// Score Entry Name and Comment (`None` if Entry does not Match)
+    pub fn score(&self, entry: &Entry, normalized: &Normalized) -> Option<f64> {
+        let (name, comment) = match self.ignore_case {
+            true => (normalized.name.as_str(), normalized.comment.as_deref()),
+            false => (entry.name.as_str(), entry.comment.as_deref()),
+        };
+        let comment = comment.and_then(|c| self.matcher.score(c));
+        let direct = best(self.matcher.score(name), comment);
+        let Some(weight) = self.acronym else {
+            return direct;
+        };
+        let acronym = acronym_score(name, &self.query);
+        best(direct, acronym.map(|s| s * weight))
+    }
+
+    /// Check if Previous Matches can be Reused when the Search Grows
+    pub fn incremental(&self) -> bool {
+        self.matcher.incremental()
+    }
+
+    /// Score Candidate Entries (by Index) Keeping only Matches
+    pub fn matches(
+        &self,
+        entries: &[Entry],
+        normalized: &[Normalized],
+        candidates: &[usize],
+    ) -> Vec<(usize, f64)> {
+        candidates
+            .iter()
+            .filter_map(|i| self.score(&entries[*i], &normalized[*i]).map(|s| (*i, s)))
+            .collect()
+    }
+
+    /// Compare Equally Scored Entries (by Index) along the Tie-Breaker Chain
+    fn tie_break(
+        &self,
+        entries: &[Entry],
+        normalized: &[Normalized],
+        a: usize,
+        b: usize,
+    ) -> Ordering {
+        let (na, nb) = (&normalized[a], &normalized[b]);
+        self.tie_break.iter().fold(Ordering::Equal, |order, key| {
+            order.then_with(|| match key {
+                TieBreak::Weight => weight(&entries[b]).total_cmp(&weight(&entries[a])),
+                TieBreak::Frecency => nb.frecency.total_cmp(&na.frecency),
+                TieBreak::Name => na.name.cmp(&nb.name),
+            })
+        })
+    }
+
+    /// Resolve Matches into Entry Indices Sorted by Score (if Ranked)
+    ///
+    /// Equal scores are ordered by the tie-breaker chain, and the sort is
+    /// stable so remaining ties (and empty searches) keep the entry order.
+    pub fn rank(
+        &self,
+        entries: &[Entry],
+        normalized: &[Normalized],
+        matches: &[(usize, f64)],
+    ) -> Vec<usize> {
+        let mut matches = matches.to_vec();
+        if self.ranked() && !self.query.is_empty() {
+            matches.sort_by(|a, b| {
+                let tie = || self.tie_break(entries, normalized, a.0, b.0);
+                b.1.total_cmp(&a.1).then_with(tie)
+            });
+        }
+        matches.into_iter().map(|(i, _)| i).collect()
+    }
+}
@@ -0,0 +1,29 @@
+//! Regular-Expression Matcher
+use regex::{Regex, RegexBuilder};
+use unicode_normalization::UnicodeNormalization;
+
+use super::Matcher;
+use crate::config::SearchConfig;
+
+/// Match Text against Regex (Invalid Expressions Match Nothing)
+struct Pattern(Option<Regex>);
+
+impl Matcher for Pattern {
+    fn score(&self, text: &str) -> Option<f64> {
+        self.0.as_ref()?.is_match(text).then_some(1.0)
+    }
+}
+
+/// Build new Regex Matcher
+pub fn new(cfg: &SearchConfig, search: &str) -> Box<dyn Matcher> {
+    // decompose (but never lowercase) to match normalized text
+    let search = match cfg.ignore_case {
+        true => search.nfkd().collect(),
+        false => search.to_owned(),
+    };
+    let regex = RegexBuilder::new(&search)
+        .case_insensitive(cfg.ignore_case)
+        .build()
+        .ok();
+    Box::new(Pattern(regex))
+}
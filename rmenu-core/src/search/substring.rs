@@ -0,0 +1,26 @@
+//! Plain Substring Matcher
+use super::{normalize, Matcher};
+use crate::config::SearchConfig;
+
+/// Match Text Containing the Search-String
+struct Substring {
+    search: String,
+}
+
+impl Matcher for Substring {
+    fn score(&self, text: &str) -> Option<f64> {
+        text.contains(&self.search).then_some(1.0)
+    }
+    fn incremental(&self) -> bool {
+        true
+    }
+}
+
+/// Build new Substring Matcher
+pub fn new(cfg: &SearchConfig, search: &str) -> Box<dyn Matcher> {
+    let search = match cfg.ignore_case {
+        true => normalize(search),
+        false => search.to_owned(),
+    };
+    Box::new(Substring { search })
+}
@@ -0,0 +1,1390 @@
+//! RMenu UI-Agnostic Search, Selection and Keybind State
+use std::str::FromStr;
+use std::time::Duration;
+
+use keyboard_types::{Code, Key, Modifiers};
+use regex::Regex;
+use regex_automata::hybrid::dfa::DFA;
+use regex_automata::{Anchored, Input};
+use rmenu_plugin::{Action, Entry};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::config::{Config, KeyConfig, Keybind, Pagination, Scope};
+use crate::events::{emit, Event};
+use crate::search::{Normalized, Search};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum KeyEvent {
+    Exec,
+    ExecAlt(usize),
+    Exit,
+    MovePrev,
+    MoveNext,
+    OpenMenu,
+    CloseMenu,
+    JumpNext,
+    JumpPrev,
+    ShowMore,
+    Paste,
+    ToggleSelect,
+    QuickExec(usize),
+    Back,
+    JumpLetter,
+    JumpTo(char),
+    HideEntry,
+    ToggleTop,
+    ToggleSticky,
+    NextPage,
+    PrevPage,
+}
+
+/// check if the current inputs match any of the given keybindings
+#[inline]
+fn matches(bind: &Vec<Keybind>, mods: &Modifiers, key: &Code) -> bool {
+    bind.iter().any(|b| mods.contains(b.mods) && &b.key == key)
+}
+
+/// Translate the Character Produced by a Keypress into its QWERTY Code
+///
+/// Used to match keybinds by layout rather than physical position, where
+/// characters without a matching code (e.g. `!`) return None.
+pub fn char_code(key: &Key) -> Option<Code> {
+    let Key::Character(s) = key else {
+        return None;
+    };
+    let mut chars = s.chars();
+    let c = chars.next()?;
+    if chars.next().is_some() {
+        return None;
+    }
+    let name = match c {
+        ' ' => "Space".to_owned(),
+        c if c.is_ascii_alphabetic() => format!("Key{}", c.to_ascii_uppercase()),
+        c if c.is_ascii_digit() => format!("Digit{c}"),
+        _ => return None,
+    };
+    Code::from_str(&name).ok()
+}
+
+/// Retrieve KeyEvent Assigned to the Given Inputs (if Any)
+pub fn key_event(keybinds: &KeyConfig, mods: &Modifiers, key: &Code) -> Option<KeyEvent> {
+    // alternate exec binds are checked first as they often extend exec
+    let alt = keybinds
+        .exec_alt
+        .iter()
+        .position(|b| mods.contains(b.mods) && &b.key == key);
+    if let Some(index) = alt {
+        Some(KeyEvent::ExecAlt(index + 1))
+    } else if matches(&keybinds.exec, mods, key) {
+        Some(KeyEvent::Exec)
+    } else if matches(&keybinds.exit, mods, key) {
+        Some(KeyEvent::Exit)
+    } else if matches(&keybinds.move_next, mods, key) {
+        Some(KeyEvent::MoveNext)
+    } else if matches(&keybinds.move_prev, mods, key) {
+        Some(KeyEvent::MovePrev)
+    } else if matches(&keybinds.open_menu, mods, key) {
+        Some(KeyEvent::OpenMenu)
+    } else if matches(&keybinds.close_menu, mods, key) {
+        Some(KeyEvent::CloseMenu)
+    } else if matches(&keybinds.jump_next, mods, key) {
+        Some(KeyEvent::JumpNext)
+    } else if matches(&keybinds.jump_prev, mods, key) {
+        Some(KeyEvent::JumpPrev)
+    } else if matches(&keybinds.show_more, mods, key) {
+        Some(KeyEvent::ShowMore)
+    } else if matches(&keybinds.paste, mods, key) {
+        Some(KeyEvent::Paste)
+    } else if matches(&keybinds.toggle_select, mods, key) {
+        Some(KeyEvent::ToggleSelect)
+    } else if matches(&keybinds.jump_letter, mods, key) {
+        Some(KeyEvent::JumpLetter)
+    } else if matches(&keybinds.hide_entry, mods, key) {
+        Some(KeyEvent::HideEntry)
+    } else if matches(&keybinds.toggle_top, mods, key) {
+        Some(KeyEvent::ToggleTop)
+    } else if matches(&keybinds.toggle_sticky, mods, key) {
+        Some(KeyEvent::ToggleSticky)
+    } else {
+        quick_index(keybinds, mods, key).map(KeyEvent::QuickExec)
+    }
+}
+
+/// Retrieve Result Index of a Hint-Modifier + Digit Keypress (e.g. `Alt+3`)
+fn quick_index(keybinds: &KeyConfig, mods: &Modifiers, key: &Code) -> Option<usize> {
+    if !mods.contains(keybinds.hint_mods()?) {
+        return None;
+    }
+    let digits = [
+        Code::Digit1,
+        Code::Digit2,
+        Code::Digit3,
+        Code::Digit4,
+        Code::Digit5,
+        Code::Digit6,
+        Code::Digit7,
+        Code::Digit8,
+        Code::Digit9,
+    ];
+    digits.iter().position(|d| d == key)
+}
+
+/// Retrieve the Letter or Digit Typed by a Keypress (e.g. `KeyA` -> `a`)
+fn code_char(key: &Code) -> Option<char> {
+    let name = key.to_string();
+    let rest = name
+        .strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))?;
+    let mut chars = rest.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c.to_ascii_lowercase()),
+        _ => None,
+    }
+}
+
+/// Title, Search and Position of a Parent Menu while a Nested Menu is Open
+#[derive(Debug)]
+struct Crumb {
+    title: String,
+    search: String,
+    page: usize,
+    pos: usize,
+    subpos: usize,
+}
+
+/// Bytes Reserved for Editing the Search beyond its Current Length
+pub static EDIT_RESERVE: usize = 1024;
+
+pub struct InnerState {
+    pos: usize,
+    subpos: usize,
+    page: usize,
+    page_size: usize,
+    search: String,
+    event: Option<KeyEvent>,
+    search_regex: Option<Regex>,
+    search_dfa: Option<DFA>,
+    /// Number of Times the Entry Limits were Raised
+    shown: usize,
+    hidden: usize,
+    /// Raised Entry Limits the Hidden Count was Last Computed for
+    counted: Option<usize>,
+    matched: Option<(String, Vec<usize>)>,
+    marked: Vec<(Entry, Action)>,
+    hinting: bool,
+    /// First Result Row Visible in the Frontend
+    top: usize,
+    jumping: bool,
+    navigated: bool,
+    disabled: Vec<String>,
+    dismissed: Vec<(String, Option<String>)>,
+    crumbs: Vec<Crumb>,
+    /// Scopes of Entries Reloaded after the Menu Opened
+    scopes: Option<Vec<Scope>>,
+    /// Search is a Masked Password that must never be Published
+    password: bool,
+}
+
+impl InnerState {
+    /// Spawn new Inner State based on Configuration
+    pub fn new(config: &Config) -> Self {
+        let search_regex = config.search.restrict.clone().and_then(|mut r| {
+            if !r.starts_with('^') {
+                r = format!("^{r}")
+            };
+            if !r.ends_with('$') {
+                r = format!("{r}$")
+            };
+            match Regex::new(&r) {
+                Ok(regex) => Some(regex),
+                Err(err) => {
+                    log::error!("Invalid Regex Expression: {:?}", err);
+                    None
+                }
+            }
+        });
+        Self {
+            pos: 0,
+            subpos: 0,
+            page: 0,
+            page_size: config
+                .page_size
+                .unwrap_or_else(|| config.performance.page_size()),
+            search: config.search.query.clone().unwrap_or_default(),
+            event: None,
+            shown: 0,
+            hidden: 0,
+            counted: None,
+            matched: None,
+            marked: vec![],
+            hinting: false,
+            top: 0,
+            jumping: false,
+            navigated: false,
+            disabled: vec![],
+            dismissed: vec![],
+            crumbs: vec![],
+            scopes: None,
+            password: config.search.password,
+            search_dfa: search_regex
+                .as_ref()
+                .and_then(|r| DFA::new(r.as_str()).ok()),
+            search_regex,
+        }
+    }
+
+    /// Retrieve Current Position State
+    #[inline]
+    pub fn position(&self) -> (usize, usize) {
+        (self.pos, self.subpos)
+    }
+
+    /// Retrieve Current Search String
+    #[inline]
+    pub fn search(&self) -> &str {
+        &self.search
+    }
+
+    /// Retrieve the Search Bound to `{query}` Placeholders of Actions
+    ///
+    /// Password searches are never bound, so they cannot leak into the
+    /// arguments of spawned commands.
+    #[inline]
+    pub fn query(&self) -> &str {
+        match self.password {
+            true => "",
+            false => &self.search,
+        }
+    }
+
+    /// Retrieve Number of Matching Results Hidden by the Entry Limit
+    #[inline]
+    pub fn hidden(&self) -> usize {
+        self.hidden
+    }
+
+    /// Retrieve Number of Entries Matching the Last Generated Results
+    #[inline]
+    pub fn matches(&self) -> usize {
+        self.matched
+            .as_ref()
+            .map(|(_, m)| m.len())
+            .unwrap_or_default()
+    }
+
+    /// Raise every Entry Limit by its Configured Amount
+    pub fn show_more(&mut self) {
+        self.shown += 1;
+    }
+
+    /// Retrieve the Entry Limit of the Plugin (or of Entries w/o a Plugin)
+    ///
+    /// Plugins w/o their own `max_entries` fall back to the global limit.
+    fn entry_limit(&self, config: &Config, scope: Option<&Scope>) -> Option<usize> {
+        let limit = scope
+            .and_then(|s| config.plugins.get(&s.name))
+            .and_then(|p| p.max_entries)
+            .or(config.max_entries)?;
+        Some(limit * (self.shown + 1))
+    }
+
+    /// Generate Results for the Current Search and Page
+    pub fn results<'a>(
+        &mut self,
+        config: &Config,
+        entries: &'a [Entry],
+        normalized: &[Normalized],
+    ) -> Vec<&'a Entry> {
+        let ratio = config
+            .page_load
+            .unwrap_or_else(|| config.performance.page_load());
+        let page_size = self.page_size(config);
+        // determine current page based on position and configuration
+        let next = config.pagination == Pagination::Auto
+            && (self.pos % page_size) as f64 / page_size as f64 > ratio;
+        let pos_page = (self.pos + 1) / page_size + 1 + next as usize;
+        let new_page = std::cmp::max(pos_page, self.page);
+        let index = page_size * new_page;
+        // update page counter if higher than before
+        self.page = new_page;
+        // render nothing until the minimum search length is met
+        if self.missing(config) > 0 {
+            self.hidden = 0;
+            return vec![];
+        }
+        // masked searches never reach the matcher so no copies are derived
+        if config.search.password {
+            self.hidden = 0;
+            return entries.iter().take(index).collect();
+        }
+        // render results and stop at page-limit
+        let (term, scope) = match self.crumbs.is_empty() {
+            true => Scope::split(self.scopes(config), &self.search),
+            false => (self.search.as_str(), None),
+        };
+        let search = Search::new(config, term);
+        // hidden entries are only counted again once the matches change
+        let unchanged = matches!(&self.matched, Some((prev, _)) if *prev == self.search);
+        let candidates = match (self.matched.take(), scope) {
+            (_, Some(scope)) => scope.filter(|i| *i < entries.len()).collect(),
+            (Some((prev, matched)), _)
+                if search.incremental() && self.search.starts_with(&prev) =>
+            {
+                matched
+            }
+            _ => (0..entries.len()).collect(),
+        };
+        let candidates = match self.disabled.is_empty() && self.dismissed.is_empty() {
+            true => candidates,
+            false => candidates
+                .into_iter()
+                .filter(|i| !self.is_disabled(&entries[*i]))
+                .collect(),
+        };
+        let scored = search.matches(entries, normalized, &candidates);
+        let matched = scored.iter().map(|(i, _)| *i).collect();
+        self.matched = Some((self.search.clone(), matched));
+        let ranked = search.rank(entries, normalized, &scored);
+        let scopes = self.scopes(config);
+        let mut limits: Vec<Option<usize>> = scopes
+            .iter()
+            .map(|s| self.entry_limit(config, Some(s)))
+            .collect();
+        limits.push(self.entry_limit(config, None));
+        if limits.iter().all(Option::is_none) {
+            self.hidden = 0;
+            return ranked
+                .into_iter()
+                .take(index)
+                .map(|i| &entries[i])
+                .collect();
+        }
+        // cap the matches of every plugin at its entry limit
+        let counted = unchanged && self.counted == Some(self.shown);
+        let mut taken = vec![0; scopes.len() + 1];
+        let mut hidden = 0;
+        let mut results = vec![];
+        for i in ranked {
+            let source = scopes
+                .iter()
+                .position(|s| s.entries.contains(&i))
+                .unwrap_or(scopes.len());
+            if limits[source].map(|l| taken[source] >= l).unwrap_or(false) {
+                hidden += 1;
+                continue;
+            }
+            taken[source] += 1;
+            if results.len() < index {
+                results.push(&entries[i]);
+            } else if counted {
+                break;
+            }
+        }
+        if !counted {
+            self.hidden = hidden;
+            self.counted = Some(self.shown);
+        }
+        results
+    }
+
+    /// Retrieve the Effective Number of Results per Page
+    #[inline]
+    fn page_size(&self, config: &Config) -> usize {
+        config.page_size.unwrap_or(self.page_size).max(1)
+    }
+
+    /// Retrieve the Page of the Selection and the Number of Pages
+    pub fn pages(&self, config: &Config, results: &[&Entry]) -> (usize, usize) {
+        let size = self.page_size(config);
+        let total = self.total(results);
+        (self.pos / size + 1, (total + size - 1) / size)
+    }
+
+    /// Render the Next Page of Results w/o Moving the Selection
+    pub fn load_page(&mut self, config: &Config, results: &[&Entry]) {
+        if self.page * self.page_size(config) < self.total(results) {
+            self.page += 1;
+        }
+    }
+
+    /// Adapt the Page Size to the Viewport and the Measured Render Time
+    ///
+    /// Pages cover the visible rows plus the prefetched screens of the
+    /// configured performance mode, shrinking when generating `rendered`
+    /// results took longer than its budget. Pinned page sizes never change.
+    pub fn tune(&mut self, config: &Config, rows: usize, rendered: usize, elapsed: Duration) {
+        if config.page_size.is_some() {
+            return;
+        }
+        let perf = &config.performance;
+        let rows = rows.max(1);
+        let wanted = rows * (1 + perf.prefetch());
+        let per_row = elapsed.as_secs_f64() / rendered.max(1) as f64;
+        let affordable = match per_row > 0.0 {
+            true => (perf.budget().as_secs_f64() / per_row) as usize,
+            false => usize::MAX,
+        };
+        let size = wanted.min(affordable).max(rows + 1);
+        // ignore small changes to avoid resizing on every render
+        if size.abs_diff(self.page_size) * 4 > self.page_size {
+            log::debug!("page size tuned {} -> {size}", self.page_size);
+            self.page_size = size;
+        }
+    }
+
+    /// Update Search and Reset Position (if Search is Valid)
+    pub fn set_search(&mut self, config: &Config, search: String) -> bool {
+        // confirm search meets required criteria
+        if let Some(max) = config.search.max_length.as_ref() {
+            if search.chars().count() > *max {
+                return false;
+            }
+        }
+        // restricted input only rejects what could never match while typing
+        let allowed = match config.search.restrict_input {
+            true => self.is_partial(&search),
+            false => self.is_allowed(&search),
+        };
+        if !allowed {
+            return false;
+        }
+        // update search w/ new content
+        let mut previous = std::mem::replace(&mut self.search, search);
+        previous.zeroize();
+        self.navigated = false;
+        self.query_changed();
+        self.move_to(0, 0);
+        true
+    }
+
+    /// Notify Subscribers of the Current Search
+    ///
+    /// Password searches are published as empty so neither their content
+    /// nor their length reaches the event log or instance socket.
+    fn query_changed(&self) {
+        match self.password {
+            true => emit(Event::QueryChanged(String::new())),
+            false => emit(Event::QueryChanged(self.search.clone())),
+        }
+    }
+
+    /// Retrieve the Masked (or dmenu-Style Echoed) Search to Print when
+    /// Nothing else would be Executed
+    pub fn secret(&self, config: &Config, results: &[&Entry]) -> Option<Zeroizing<String>> {
+        let empty = results.is_empty() && self.marked.is_empty();
+        let echo = config.search.password || (config.search.echo_query && !self.search.is_empty());
+        (echo && empty).then(|| Zeroizing::new(self.search.clone()))
+    }
+
+    /// Copy the Search into a Buffer to Edit w/o Leaving Copies Behind
+    ///
+    /// Room for [`EDIT_RESERVE`] more bytes is reserved up front, so
+    /// typing never reallocates (and abandons) the buffer.
+    pub fn edit_buffer(&self) -> Zeroizing<String> {
+        let mut buffer = String::with_capacity(self.search.len() + EDIT_RESERVE);
+        buffer.push_str(&self.search);
+        Zeroizing::new(buffer)
+    }
+
+    /// Overwrite the Search and every Copy Kept for Matching or Nested Menus
+    pub fn wipe(&mut self) {
+        self.search.zeroize();
+        if let Some((prev, _)) = self.matched.as_mut() {
+            prev.zeroize();
+        }
+        for crumb in self.crumbs.iter_mut() {
+            crumb.search.zeroize();
+        }
+    }
+
+    /// Forget Previous Matches and Selection after Entries were Replaced
+    pub fn refresh(&mut self) {
+        self.crumbs.clear();
+        self.reset();
+    }
+
+    /// Forget Previous Matches after Entries were Updated in Place
+    ///
+    /// The search and selection are kept, so actions executed w/o closing
+    /// the menu can be repeated right away.
+    pub fn update(&mut self) {
+        self.matched = None;
+    }
+
+    /// Retrieve the Scopes of the Entries Currently Loaded
+    #[inline]
+    fn scopes<'a>(&'a self, config: &'a Config) -> &'a [Scope] {
+        self.scopes.as_deref().unwrap_or(&config.search.scopes)
+    }
+
+    /// Replace the Configured Scopes w/ the ones of Reloaded Entries
+    pub fn set_scopes(&mut self, scopes: Vec<Scope>) {
+        self.scopes = Some(scopes);
+        self.matched = None;
+    }
+
+    /// Retrieve the Name of the Plugin the Current Search Applies to (if Any)
+    ///
+    /// Masked searches never select a plugin by their prefix.
+    pub fn plugin<'a>(&'a self, config: &'a Config) -> Option<&'a str> {
+        let search = match config.search.password {
+            true => "",
+            false => self.search.as_str(),
+        };
+        Scope::plugin(self.scopes(config), search)
+    }
+
+    /// Hide Entries of the Named Source for the Rest of the Session
+    pub fn disable_source(&mut self, source: &str) {
+        log::info!("disabled source {source:?}");
+        self.disabled.push(source.to_owned());
+        self.reset();
+    }
+
+    /// Hide the Highlighted Entry for the Rest of the Session
+    ///
+    /// Returns the hidden entry, so it can also be blacklisted for later.
+    pub fn hide_entry<'a>(&mut self, results: &[&'a Entry]) -> Option<&'a Entry> {
+        let entry = *results.get(self.pos)?;
+        log::info!("hiding entry {:?}", entry.name);
+        self.dismissed
+            .push((entry.name.clone(), entry.source.clone()));
+        self.matched = None;
+        self.pos = self.pos.min(results.len().saturating_sub(2));
+        self.subpos = 0;
+        Some(entry)
+    }
+
+    /// Check if the Entry was Hidden or Belongs to a Disabled Source
+    fn is_disabled(&self, entry: &Entry) -> bool {
+        let source = entry.source.as_ref();
+        if source.map(|s| self.disabled.contains(s)).unwrap_or(false) {
+            return true;
+        }
+        self.dismissed
+            .iter()
+            .any(|(name, source)| name == &entry.name && source == &entry.source)
+    }
+
+    /// Forget Matches and Selection w/o Leaving Nested Menus
+    fn reset(&mut self) {
+        self.matched = None;
+        self.navigated = false;
+        self.page = 0;
+        self.top = 0;
+        self.move_to(0, 0);
+    }
+
+    /// Retrieve Title and Command of the Nested Menu the Current Action Opens
+    pub fn submenu(&self, results: &[&Entry]) -> Option<(String, Vec<String>)> {
+        if !self.marked.is_empty() {
+            return None;
+        }
+        let entry = results.get(self.pos)?;
+        let exec = crate::exec::fill_placeholders(&self.action(results)?.exec, entry, &self.search);
+        let args = crate::exec::menu_args(&exec)?;
+        Some((entry.name.clone(), args))
+    }
+
+    /// Retrieve the Plugin and Query to Submit instead of Executing
+    ///
+    /// Queries of `submit_query` plugins are submitted until a result
+    /// was explicitly selected after the search last changed.
+    pub fn submit(&self, config: &Config, results: &[&Entry]) -> Option<(String, String)> {
+        if !self.crumbs.is_empty() || !self.marked.is_empty() {
+            return None;
+        }
+        if self.navigated && !results.is_empty() {
+            return None;
+        }
+        let plugin = self.plugin(config)?;
+        if !config.plugins.get(plugin)?.submit_query {
+            return None;
+        }
+        let (term, _) = Scope::split(self.scopes(config), &self.search);
+        let term = term.trim();
+        (!term.is_empty()).then(|| (plugin.to_owned(), term.to_owned()))
+    }
+
+    /// Remember the Current Menu and Start Fresh in a Nested Menu
+    pub fn enter(&mut self, title: String) {
+        self.crumbs.push(Crumb {
+            title,
+            search: std::mem::take(&mut self.search),
+            page: self.page,
+            pos: self.pos,
+            subpos: self.subpos,
+        });
+        emit(Event::QueryChanged(String::new()));
+        self.reset();
+    }
+
+    /// Return to the Parent Menu w/ its Search and Position (if Nested)
+    pub fn back(&mut self) -> bool {
+        let Some(crumb) = self.crumbs.pop() else {
+            return false;
+        };
+        self.search = crumb.search;
+        self.query_changed();
+        self.reset();
+        self.page = crumb.page;
+        self.move_to(crumb.pos, crumb.subpos);
+        true
+    }
+
+    /// Retrieve Titles of the Nested Menus Entered so Far
+    pub fn breadcrumbs(&self) -> Vec<&str> {
+        self.crumbs.iter().map(|c| c.title.as_str()).collect()
+    }
+
+    /// Check if the Keypress Returns to the Parent Menu (Backspace on Empty Search)
+    #[inline]
+    pub fn is_back(&self, key: &Code) -> bool {
+        *key == Code::Backspace && self.search.is_empty() && !self.crumbs.is_empty()
+    }
+
+    /// Retrieve Number of Characters Missing to Meet the Minimum Length
+    pub fn missing(&self, config: &Config) -> usize {
+        config
+            .search
+            .min_length
+            .map(|min| min.saturating_sub(self.search.chars().count()))
+            .unwrap_or(0)
+    }
+
+    /// Retrieve Pending KeyEvent
+    #[inline]
+    pub fn event(&self) -> Option<KeyEvent> {
+        self.event.clone()
+    }
+
+    /// Set Pending KeyEvent for Later Evaluation
+    #[inline]
+    pub fn set_event(&mut self, event: Option<KeyEvent>) {
+        self.event = event;
+    }
+
+    /// Update Current Position and Notify Subscribers of Changes
+    fn move_to(&mut self, pos: usize, subpos: usize) {
+        let moved = (self.pos, self.subpos) != (pos, subpos);
+        self.pos = pos;
+        self.subpos = subpos;
+        if moved {
+            emit(Event::SelectionMoved { pos, subpos });
+        }
+    }
+
+    /// Update Current Position
+    #[inline]
+    pub fn set_position(&mut self, pos: usize, subpos: usize) {
+        self.navigated = true;
+        self.move_to(pos, subpos);
+    }
+
+    /// Check if the Search Matches the Configured Restriction
+    pub fn is_allowed(&self, search: &str) -> bool {
+        self.search_regex
+            .as_ref()
+            .map(|r| r.is_match(search))
+            .unwrap_or(true)
+    }
+
+    /// Check if the Search can still be Completed into a Match of the Restriction
+    ///
+    /// Anchored patterns like `^\d{3}$` reject every prefix of a valid
+    /// input, so typing is only blocked once no completion could match.
+    pub fn is_partial(&self, search: &str) -> bool {
+        let Some(dfa) = self.search_dfa.as_ref() else {
+            return true;
+        };
+        let mut cache = dfa.create_cache();
+        let input = Input::new(search).anchored(Anchored::Yes);
+        let Ok(mut state) = dfa.start_state_forward(&mut cache, &input) else {
+            return true;
+        };
+        for byte in search.bytes() {
+            match dfa.next_state(&mut cache, state, byte) {
+                Ok(next) if next.is_dead() => return false,
+                Ok(next) if next.is_quit() => return true,
+                Ok(next) => state = next,
+                Err(_) => return true,
+            }
+        }
+        true
+    }
+
+    /// Check if the Search may be Submitted
+    ///
+    /// The full pattern is always enforced on submit, since partial input
+    /// and initial queries were never matched against it.
+    pub fn is_submittable(&self) -> bool {
+        self.is_allowed(&self.search)
+    }
+
+    /// Move Position To SubMenu if it Exists
+    pub fn open_menu(&mut self, results: &[&Entry]) {
+        if let Some(result) = results.get(self.pos) {
+            if result.actions.len() > 1 {
+                self.subpos += 1;
+            }
+        }
+    }
+
+    // Reset and Close SubMenu Position
+    #[inline]
+    pub fn close_menu(&mut self) {
+        self.subpos = 0;
+    }
+
+    /// Retrieve Action Selected by the Current Position
+    pub fn action<'a>(&self, results: &[&'a Entry]) -> Option<&'a Action> {
+        log::debug!("execute {} {}", self.pos, self.subpos);
+        let result = results.get(self.pos)?;
+        log::debug!("result: {result:?}");
+        let index = match self.subpos {
+            0 => result.default_index(),
+            subpos => subpos,
+        };
+        let action = result.actions.get(index)?;
+        log::debug!("action: {action:?}");
+        Some(action)
+    }
+
+    /// Retrieve Entry and Action Selected by the Current Position
+    pub fn selected<'a>(&self, results: &[&'a Entry]) -> Option<(&'a Entry, &'a Action)> {
+        Some((results.get(self.pos)?, self.action(results)?))
+    }
+
+    /// Check if an Action of the Entry is Marked for Execution
+    pub fn is_marked(&self, entry: &Entry) -> bool {
+        self.marked.iter().any(|(e, _)| e == entry)
+    }
+
+    /// Mark or Unmark the Currently Selected Action for Execution
+    ///
+    /// Actions are marked along w/ their entry, so identical actions of
+    /// other entries are not marked as well.
+    pub fn toggle_select(&mut self, results: &[&Entry]) {
+        let Some((entry, action)) = self.selected(results) else {
+            return;
+        };
+        match self
+            .marked
+            .iter()
+            .position(|(e, a)| e == entry && a == action)
+        {
+            Some(index) => {
+                self.marked.remove(index);
+            }
+            None => self.marked.push((entry.clone(), action.clone())),
+        }
+    }
+
+    /// Retrieve Marked Actions or the Currently Selected Action if None
+    ///
+    /// Every action is returned w/ the entry it was selected from.
+    pub fn actions(&self, results: &[&Entry]) -> Vec<(Entry, Action)> {
+        match self.marked.is_empty() {
+            true => self
+                .selected(results)
+                .map(|(e, a)| (e.clone(), a.clone()))
+                .into_iter()
+                .collect(),
+            false => self.marked.clone(),
+        }
+    }
+
+    /// Retrieve Specified Action of the Currently Selected Entry
+    pub fn alt_action<'a>(
+        &self,
+        results: &[&'a Entry],
+        index: usize,
+    ) -> Option<(&'a Entry, &'a Action)> {
+        let entry = *results.get(self.pos)?;
+        Some((entry, entry.actions.get(index)?))
+    }
+
+    /// Select the Result in the Given Visible Row and Retrieve its Entry and Action
+    ///
+    /// Rows count from the first visible result, so quick-exec keys keep
+    /// matching their badges after scrolling.
+    pub fn quick_action<'a>(
+        &mut self,
+        results: &[&'a Entry],
+        index: usize,
+    ) -> Option<(&'a Entry, &'a Action)> {
+        let index = self.top + index;
+        if index >= results.len() {
+            return None;
+        }
+        self.move_to(index, 0);
+        self.selected(results)
+    }
+
+    /// Retrieve the First Result Row Visible in the Frontend
+    #[inline]
+    pub fn top(&self) -> usize {
+        self.top
+    }
+
+    /// Record the First Result Row Visible in the Frontend
+    #[inline]
+    pub fn set_top(&mut self, top: usize) {
+        self.top = top;
+    }
+
+    /// Retrieve the Quick-Exec Slot of the Result at the Given Index (if Any)
+    pub fn quick_slot(&self, index: usize) -> Option<usize> {
+        index.checked_sub(self.top).filter(|slot| *slot < 9)
+    }
+
+    /// Check if Shortcut Hints are Shown (Hint Modifier Held)
+    #[inline]
+    pub fn hinting(&self) -> bool {
+        self.hinting
+    }
+
+    /// Show or Hide Shortcut Hints
+    #[inline]
+    pub fn set_hinting(&mut self, hinting: bool) {
+        self.hinting = hinting;
+    }
+
+    /// Apply Navigation Related KeyEvents to the Current Position
+    pub fn navigate(&mut self, event: &KeyEvent, config: &Config, results: &[&Entry]) {
+        let before = (self.pos, self.subpos);
+        self.jumping = *event == KeyEvent::JumpLetter;
+        match event {
+            KeyEvent::MovePrev if config.wrap_navigation && before == (0, 0) => {
+                self.pos = self.total(results).saturating_sub(1);
+            }
+            KeyEvent::MoveNext if config.wrap_navigation && self.at_end(results) => {
+                self.pos = 0;
+                self.subpos = 0;
+            }
+            KeyEvent::OpenMenu => self.open_menu(results),
+            KeyEvent::CloseMenu => self.close_menu(),
+            KeyEvent::MovePrev => self.move_prev(),
+            KeyEvent::MoveNext => self.move_next(results),
+            KeyEvent::JumpPrev => self.jump_up(config.jump_dist),
+            KeyEvent::JumpNext => self.jump_down(config.jump_dist, results),
+            KeyEvent::ShowMore => self.show_more(),
+            KeyEvent::ToggleSelect if config.multi_select => {
+                self.toggle_select(results);
+                self.move_next(results);
+            }
+            KeyEvent::ToggleSelect => {}
+            KeyEvent::JumpTo(letter) => self.jump_to(*letter, results),
+            KeyEvent::JumpLetter => {}
+            KeyEvent::HideEntry => {
+                self.hide_entry(results);
+            }
+            KeyEvent::NextPage => {
+                let size = self.page_size(config);
+                let pos = (self.pos / size + 1) * size;
+                if pos < self.total(results) {
+                    self.pos = pos;
+                    self.subpos = 0;
+                }
+            }
+            KeyEvent::PrevPage => {
+                let size = self.page_size(config);
+                self.pos = (self.pos / size).saturating_sub(1) * size;
+                self.subpos = 0;
+            }
+            KeyEvent::Exec
+            | KeyEvent::ExecAlt(_)
+            | KeyEvent::QuickExec(_)
+            | KeyEvent::Back
+            | KeyEvent::Exit
+            | KeyEvent::Paste
+            | KeyEvent::ToggleTop
+            | KeyEvent::ToggleSticky => {}
+        }
+        if before != (self.pos, self.subpos) {
+            let (pos, subpos) = (self.pos, self.subpos);
+            self.navigated = true;
+            emit(Event::SelectionMoved { pos, subpos });
+        }
+    }
+
+    /// Check if the Next Letter Pressed Jumps to a Matching Entry
+    #[inline]
+    pub fn jumping(&self) -> bool {
+        self.jumping
+    }
+
+    /// Translate a Letter Keypress into a Jump or Quick-Exec
+    ///
+    /// Letters jump while waiting for one, and when the search is disabled
+    /// unbound letters jump while digits execute the matching result.
+    /// Other keys return None and are handled as usual.
+    pub fn letter_event(&self, config: &Config, mods: &Modifiers, key: &Code) -> Option<KeyEvent> {
+        if self.jumping {
+            return code_char(key).map(KeyEvent::JumpTo);
+        }
+        if !config.search.disable || key_event(&config.keybinds, mods, key).is_some() {
+            return None;
+        }
+        match code_char(key)? {
+            '0' => None,
+            c @ '1'..='9' => Some(KeyEvent::QuickExec(c as usize - '1' as usize)),
+            c => Some(KeyEvent::JumpTo(c)),
+        }
+    }
+
+    /// Move to the Next Entry whose Name Starts w/ the Letter
+    ///
+    /// Repeating the letter cycles through every matching entry.
+    pub fn jump_to(&mut self, letter: char, results: &[&Entry]) {
+        let starts = |e: &&Entry| {
+            let first = e.name.trim_start().chars().next();
+            first
+                .map(|c| c.to_ascii_lowercase() == letter)
+                .unwrap_or(false)
+        };
+        let after = results.iter().skip(self.pos + 1).position(starts);
+        let found = match after {
+            Some(index) => Some(self.pos + 1 + index),
+            None => results.iter().position(starts),
+        };
+        if let Some(pos) = found {
+            self.pos = pos;
+            self.subpos = 0;
+        }
+    }
+
+    /// Retrieve Number of Selectable Results including those on Later Pages
+    fn total(&self, results: &[&Entry]) -> usize {
+        if results.is_empty() {
+            return 0;
+        }
+        let matched = match self.matched.as_ref() {
+            Some((_, matched)) => matched.len(),
+            None => results.len(),
+        };
+        matched.saturating_sub(self.hidden)
+    }
+
+    /// Check if Moving Down would Leave the Last Result
+    fn at_end(&self, results: &[&Entry]) -> bool {
+        let in_menu = results
+            .get(self.pos)
+            .map(|r| self.subpos > 0 && self.subpos < r.actions.len() - 1)
+            .unwrap_or(false);
+        !in_menu && self.pos + 1 >= self.total(results)
+    }
+
+    /// Move X Primary Results Upwards
+    pub fn move_up(&mut self, x: usize) {
+        self.subpos = 0;
+        self.pos = std::cmp::max(self.pos, x) - x;
+    }
+
+    /// Move X Primary Results Downwards
+    pub fn move_down(&mut self, x: usize, max: usize) {
+        self.subpos = 0;
+        self.pos = std::cmp::min(self.pos + x, max - 1)
+    }
+
+    /// Jump a spefified number of results upwards
+    #[inline]
+    pub fn jump_up(&mut self, jump: usize) {
+        self.move_up(jump)
+    }
+
+    /// Jump a specified number of results downwards
+    pub fn jump_down(&mut self, jump: usize, results: &[&Entry]) {
+        let max = std::cmp::max(results.len(), 1);
+        self.move_down(jump, max);
+    }
+
+    /// Move Up Once With Context of SubMenu
+    pub fn move_prev(&mut self) {
+        if self.subpos > 0 {
+            self.subpos -= 1;
+            return;
+        }
+        self.move_up(1);
+    }
+
+    /// Move Down Once With Context of SubMenu
+    pub fn move_next(&mut self, results: &[&Entry]) {
+        if let Some(result) = results.get(self.pos) {
+            if self.subpos > 0 && self.subpos < result.actions.len() - 1 {
+                self.subpos += 1;
+                return;
+            }
+        }
+        self.jump_down(1, results)
+    }
+}
+
+impl Drop for InnerState {
+    fn drop(&mut self) {
+        self.wipe();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{PluginConfig, Scope};
+
+    fn entries() -> Vec<Entry> {
+        ["Firefox", "Files", "Vim"]
+            .into_iter()
+            .map(|name| Entry::echo(name, None))
+            .collect()
+    }
+
+    #[test]
+    fn test_search_and_navigate() {
+        let mut config = Config::default();
+        config.search.use_regex = false;
+        let entries = entries();
+        let normalized: Vec<Normalized> = entries.iter().map(Normalized::new).collect();
+        let mut state = InnerState::new(&config);
+        assert!(state.set_search(&config, "fi".to_owned()));
+        let results = state.results(&config, &entries, &normalized);
+        let names: Vec<&str> = results.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Firefox", "Files"]);
+        state.navigate(&KeyEvent::MoveNext, &config, &results);
+        state.navigate(&KeyEvent::MoveNext, &config, &results);
+        assert_eq!(state.position(), (1, 0));
+        let action = state.action(&results).expect("missing action");
+        assert_eq!(action.exec, rmenu_plugin::Method::Echo("Files".to_owned()));
+    }
+
+    #[test]
+    fn test_wrap_navigation() {
+        let mut config = Config::default();
+        config.wrap_navigation = true;
+        let entries = entries();
+        let normalized: Vec<Normalized> = entries.iter().map(Normalized::new).collect();
+        let mut state = InnerState::new(&config);
+        let results = state.results(&config, &entries, &normalized);
+        state.navigate(&KeyEvent::MovePrev, &config, &results);
+        assert_eq!(state.position(), (2, 0));
+        state.navigate(&KeyEvent::MoveNext, &config, &results);
+        assert_eq!(state.position(), (0, 0));
+        config.wrap_navigation = false;
+        state.navigate(&KeyEvent::MovePrev, &config, &results);
+        assert_eq!(state.position(), (0, 0));
+    }
+
+    #[test]
+    fn test_jump_letter() {
+        let config = Config::default();
+        let entries = entries();
+        let normalized: Vec<Normalized> = entries.iter().map(Normalized::new).collect();
+        let mut state = InnerState::new(&config);
+        let results = state.results(&config, &entries, &normalized);
+        let mods = Modifiers::empty();
+        assert_eq!(state.letter_event(&config, &mods, &Code::KeyV), None);
+        state.navigate(&KeyEvent::JumpLetter, &config, &results);
+        let event = state.letter_event(&config, &mods, &Code::KeyV);
+        let event = event.expect("missing jump");
+        assert_eq!(event, KeyEvent::JumpTo('v'));
+        state.navigate(&event, &config, &results);
+        assert_eq!(state.position(), (2, 0));
+        assert!(!state.jumping());
+        state.navigate(&KeyEvent::JumpLetter, &config, &results);
+        state.navigate(&KeyEvent::JumpTo('f'), &config, &results);
+        assert_eq!(state.position(), (0, 0));
+        state.navigate(&KeyEvent::JumpTo('f'), &config, &results);
+        assert_eq!(state.position(), (1, 0));
+    }
+
+    #[test]
+    fn test_disable_search() {
+        let mut config = Config::default();
+        config.search.disable = true;
+        config.keybinds.paste = vec![Keybind {
+            mods: Modifiers::CONTROL,
+            key: Code::KeyV,
+        }];
+        let state = InnerState::new(&config);
+        let mods = Modifiers::empty();
+        let event = state.letter_event(&config, &mods, &Code::Digit2);
+        assert_eq!(event, Some(KeyEvent::QuickExec(1)));
+        let event = state.letter_event(&config, &mods, &Code::KeyV);
+        assert_eq!(event, Some(KeyEvent::JumpTo('v')));
+        let ctrl = Modifiers::CONTROL;
+        assert_eq!(state.letter_event(&config, &ctrl, &Code::KeyV), None);
+        assert_eq!(state.letter_event(&config, &mods, &Code::Enter), None);
+    }
+
+    #[test]
+    fn test_hide_entry() {
+        let config = Config::default();
+        let entries = entries();
+        let normalized: Vec<Normalized> = entries.iter().map(Normalized::new).collect();
+        let mut state = InnerState::new(&config);
+        let results = state.results(&config, &entries, &normalized);
+        state.navigate(&KeyEvent::MoveNext, &config, &results);
+        let hidden = state.hide_entry(&results).expect("missing entry");
+        assert_eq!(hidden.name, "Files");
+        let results = state.results(&config, &entries, &normalized);
+        let names: Vec<&str> = results.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Firefox", "Vim"]);
+        assert_eq!(state.position(), (1, 0));
+    }
+
+    #[test]
+    fn test_mark_shared_actions() {
+        let mut config = Config::default();
+        config.search.use_regex = false;
+        let entries = vec![
+            Entry::new("Notes", "gvim {meta.path}", None),
+            Entry::new("Todo", "gvim {meta.path}", None),
+        ];
+        let normalized: Vec<Normalized> = entries.iter().map(Normalized::new).collect();
+        let mut state = InnerState::new(&config);
+        let results = state.results(&config, &entries, &normalized);
+        state.move_to(1, 0);
+        let selected = state.actions(&results);
+        assert_eq!(selected[0].0.name, "Todo");
+        state.toggle_select(&results);
+        assert!(!state.is_marked(&entries[0]));
+        assert!(state.is_marked(&entries[1]));
+        state.move_to(0, 0);
+        let marked = state.actions(&results);
+        assert_eq!(marked.len(), 1);
+        assert_eq!(marked[0].0.name, "Todo");
+    }
+
+    #[test]
+    fn test_quick_exec() {
+        let config = Config::default();
+        let entries = entries();
+        let normalized: Vec<Normalized> = entries.iter().map(Normalized::new).collect();
+        let mut state = InnerState::new(&config);
+        let results = state.results(&config, &entries, &normalized);
+        let event = key_event(&config.keybinds, &Modifiers::ALT, &Code::Digit3);
+        assert_eq!(event, Some(KeyEvent::QuickExec(2)));
+        let (_, action) = state.quick_action(&results, 2).expect("missing action");
+        assert_eq!(action.exec, rmenu_plugin::Method::Echo("Vim".to_owned()));
+        assert_eq!(state.position(), (2, 0));
+        assert_eq!(state.quick_action(&results, 5), None);
+        // quick-exec slots follow the first visible row
+        state.set_top(1);
+        assert_eq!(state.quick_slot(0), None);
+        assert_eq!(state.quick_slot(2), Some(1));
+        let (entry, _) = state.quick_action(&results, 1).expect("missing action");
+        assert_eq!(entry.name, "Vim");
+        state.set_top(0);
+        assert_eq!(config.keybinds.quick_hint(2).as_deref(), Some("Alt+3"));
+        assert_eq!(
+            config.keybinds.action_hint(1).as_deref(),
+            Some("Shift+Enter")
+        );
+    }
+
+    #[test]
+    fn test_nested_menus() {
+        let config = Config::default();
+        let mut state = InnerState::new(&config);
+        state.set_search(&config, "vi".to_owned());
+        state.set_position(2, 0);
+        state.enter("Vim".to_owned());
+        assert_eq!(state.search(), "");
+        assert_eq!(state.position(), (0, 0));
+        assert_eq!(state.breadcrumbs(), vec!["Vim"]);
+        assert!(state.is_back(&Code::Backspace));
+        assert!(state.back());
+        assert_eq!(state.search(), "vi");
+        assert_eq!(state.position(), (2, 0));
+        assert!(!state.back());
+        assert!(!state.is_back(&Code::Backspace));
+    }
+
+    #[test]
+    fn test_submit_query() {
+        let mut config = Config::default();
+        let dict = PluginConfig {
+            submit_query: true,
+            ..Default::default()
+        };
+        config.plugins.insert("dict".to_owned(), dict);
+        config.search.scopes.push(Scope {
+            name: "dict".to_owned(),
+            prefix: None,
+            entries: 0..3,
+        });
+        let entries = entries();
+        let refs: Vec<&Entry> = entries.iter().collect();
+        let mut state = InnerState::new(&config);
+        assert_eq!(state.submit(&config, &refs), None);
+        state.set_search(&config, " fi ".to_owned());
+        let submitted = Some(("dict".to_owned(), "fi".to_owned()));
+        assert_eq!(state.submit(&config, &refs), submitted);
+        state.navigate(&KeyEvent::MoveNext, &config, &refs);
+        assert_eq!(state.submit(&config, &refs), None);
+        assert_eq!(state.submit(&config, &[]), submitted);
+        state.set_search(&config, "fir".to_owned());
+        assert!(state.submit(&config, &refs).is_some());
+    }
+
+    #[test]
+    fn test_reloaded_scopes() {
+        let mut config = Config::default();
+        config.search.use_regex = false;
+        config.search.scopes.push(Scope {
+            name: "web".to_owned(),
+            prefix: Some("w:".to_owned()),
+            entries: 0..1,
+        });
+        let entries = entries();
+        let normalized: Vec<Normalized> = entries.iter().map(Normalized::new).collect();
+        let mut state = InnerState::new(&config);
+        state.set_search(&config, "w:".to_owned());
+        assert_eq!(state.results(&config, &entries, &normalized).len(), 1);
+        // the plugin owns two entries after it was reloaded
+        state.set_scopes(vec![Scope {
+            name: "web".to_owned(),
+            prefix: Some("w:".to_owned()),
+            entries: 0..2,
+        }]);
+        let results = state.results(&config, &entries, &normalized);
+        let names: Vec<&str> = results.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Firefox", "Files"]);
+        assert_eq!(state.plugin(&config), Some("web"));
+    }
+
+    #[test]
+    fn test_password_search() {
+        let mut config = Config::default();
+        config.search.password = true;
+        let entries = entries();
+        let normalized: Vec<Normalized> = entries.iter().map(Normalized::new).collect();
+        let mut state = InnerState::new(&config);
+        assert!(state.set_search(&config, "hunter2".to_owned()));
+        let results = state.results(&config, &entries, &normalized);
+        assert_eq!(results.len(), 3);
+        assert_eq!(state.secret(&config, &results), None);
+        let secret = state.secret(&config, &[]).expect("missing secret");
+        assert_eq!(secret.as_str(), "hunter2");
+        let buffer = state.edit_buffer();
+        assert_eq!(buffer.as_str(), "hunter2");
+        assert!(buffer.capacity() >= buffer.len() + EDIT_RESERVE);
+        state.wipe();
+        assert_eq!(state.search(), "");
+    }
+
+    #[test]
+    fn test_restrict_input() {
+        let mut config = Config::default();
+        config.search.restrict = Some(r"\d{3}".to_owned());
+        config.search.restrict_input = true;
+        let mut state = InnerState::new(&config);
+        assert!(state.is_partial(""));
+        assert!(state.set_search(&config, "1".to_owned()));
+        assert!(state.set_search(&config, "12".to_owned()));
+        assert!(!state.is_submittable());
+        assert!(!state.set_search(&config, "12a".to_owned()));
+        assert!(!state.set_search(&config, "1234".to_owned()));
+        assert!(state.set_search(&config, "123".to_owned()));
+        assert!(state.is_submittable());
+    }
+
+    #[test]
+    fn test_restrict_submit() {
+        let mut config = Config::default();
+        config.search.restrict = Some(r"\d{3}".to_owned());
+        config.search.query = Some("12".to_owned());
+        let mut state = InnerState::new(&config);
+        assert!(!state.is_submittable());
+        assert!(!state.set_search(&config, "1".to_owned()));
+        assert_eq!(state.search(), "12");
+        assert!(state.set_search(&config, "123".to_owned()));
+        assert!(state.is_submittable());
+    }
+
+    #[test]
+    fn test_password_events() {
+        use std::sync::{Arc, Mutex};
+        let mut config = Config::default();
+        config.search.password = true;
+        let seen = Arc::new(Mutex::new(vec![]));
+        let copy = Arc::clone(&seen);
+        crate::events::subscribe(move |e| {
+            if let Event::QueryChanged(query) = e {
+                copy.lock().unwrap().push(query.clone());
+            }
+        });
+        let mut state = InnerState::new(&config);
+        assert!(state.set_search(&config, "s3cr3t-pass".to_owned()));
+        state.enter("Nested".to_owned());
+        assert!(state.back());
+        assert_eq!(state.search(), "s3cr3t-pass");
+        // other tests emit concurrently so only check for traces of the secret
+        let seen = seen.lock().unwrap();
+        assert!(seen
+            .iter()
+            .all(|q| !q.contains("s3cr3t") && !q.starts_with('*')));
+    }
+
+    #[test]
+    fn test_echo_query() {
+        let mut config = Config::default();
+        config.search.echo_query = true;
+        let mut state = InnerState::new(&config);
+        assert_eq!(state.secret(&config, &[]), None);
+        assert!(state.set_search(&config, "custom".to_owned()));
+        let secret = state.secret(&config, &[]).expect("missing query");
+        assert_eq!(secret.as_str(), "custom");
+    }
+
+    #[test]
+    fn test_char_code() {
+        let key = |s: &str| Key::Character(s.to_owned());
+        assert_eq!(char_code(&key("z")), Some(Code::KeyZ));
+        assert_eq!(char_code(&key("Y")), Some(Code::KeyY));
+        assert_eq!(char_code(&key("3")), Some(Code::Digit3));
+        assert_eq!(char_code(&key("!")), None);
+        assert_eq!(char_code(&Key::Enter), None);
+    }
+
+    #[test]
+    fn test_manual_pagination() {
+        let mut config = Config::default();
+        config.page_size = Some(3);
+        config.pagination = Pagination::Manual;
+        let entries: Vec<Entry> = (0..10)
+            .map(|i| Entry::echo(&format!("entry {i}"), None))
+            .collect();
+        let normalized: Vec<Normalized> = entries.iter().map(Normalized::new).collect();
+        let mut state = InnerState::new(&config);
+        let results = state.results(&config, &entries, &normalized);
+        assert_eq!(results.len(), 3);
+        assert_eq!(state.pages(&config, &results), (1, 4));
+        state.navigate(&KeyEvent::NextPage, &config, &results);
+        assert_eq!(state.position(), (3, 0));
+        let results = state.results(&config, &entries, &normalized);
+        assert_eq!(results.len(), 6);
+        state.navigate(&KeyEvent::PrevPage, &config, &results);
+        assert_eq!(state.position(), (0, 0));
+        state.load_page(&config, &results);
+        let results = state.results(&config, &entries, &normalized);
+        assert_eq!(results.len(), 9);
+        assert_eq!(state.position(), (0, 0));
+    }
+
+    #[test]
+    fn test_tune_page_size() {
+        let mut config = Config::default();
+        let mut state = InnerState::new(&config);
+        state.tune(&config, 5, 50, Duration::from_millis(1));
+        assert_eq!(state.page_size, 20);
+        // slow renders shrink pages down to a single screen
+        state.tune(&config, 5, 20, Duration::from_millis(100));
+        assert_eq!(state.page_size, 6);
+        config.performance = crate::config::Performance::LowLatency;
+        state.tune(&config, 10, 11, Duration::ZERO);
+        assert_eq!(state.page_size, 20);
+        config.page_size = Some(5);
+        state.tune(&config, 100, 20, Duration::ZERO);
+        assert_eq!(state.page_size, 20);
+    }
+}
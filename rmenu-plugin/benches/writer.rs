@@ -0,0 +1,45 @@
+//! Entry Serialization Throughput: `println!` per Entry vs `EntryWriter`
+//!
+//! Run with `cargo bench -p rmenu-plugin > /dev/null` to keep the
+//! serialized entries out of the terminal, results are printed to stderr.
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use rmenu_plugin::{Entry, EntryWriter};
+
+static ENTRIES: usize = 100_000;
+
+/// Generate Entries resembling a Large Application Listing
+fn entries() -> Vec<Entry> {
+    (0..ENTRIES)
+        .map(|i| {
+            let name = format!("application-{i}");
+            let exec = format!("/usr/bin/{name} --new-window");
+            Entry::new(&name, &exec, Some("Launch an Application"))
+        })
+        .collect()
+}
+
+/// Report Throughput of a Single Run
+fn report(name: &str, elapsed: Duration) {
+    let rate = ENTRIES as f64 / elapsed.as_secs_f64();
+    eprintln!("{name:>12}: {elapsed:>10.2?} ({rate:>12.0} entries/s)");
+}
+
+fn main() -> io::Result<()> {
+    let entries = entries();
+
+    let start = Instant::now();
+    for entry in entries.iter() {
+        println!("{}", serde_json::to_string(entry)?);
+    }
+    io::stdout().flush()?;
+    report("println", start.elapsed());
+
+    let start = Instant::now();
+    let mut writer = EntryWriter::stdout();
+    writer.write_all(&entries)?;
+    writer.flush()?;
+    report("EntryWriter", start.elapsed());
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, path::PathBuf, str::FromStr};
 
 use rmenu_plugin::*;
 
@@ -9,6 +9,21 @@ fn parse_action(action: &str) -> Result<Action, serde_json::Error> {
     serde_json::from_str(action)
 }
 
+/// Parse Metadata Field from `key=value`
+fn parse_meta(field: &str) -> Result<(String, String), String> {
+    field
+        .split_once('=')
+        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        .ok_or_else(|| format!("Invalid Metadata Field: {field:?}"))
+}
+
+/// Parse Environment Variable from `key=value`
+fn parse_env(var: &str) -> Result<(String, String), String> {
+    var.split_once('=')
+        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        .ok_or_else(|| format!("Invalid Environment Variable: {var:?}"))
+}
+
 //TODO: add options struct object that allows for further
 // dynamic customization of the cli settings.
 // last instance overwrites previous entries
@@ -27,6 +42,10 @@ enum ActionMode {
     Run,
     Terminal,
     Echo,
+    Type,
+    Menu,
+    Privileged,
+    Pipe,
 }
 
 impl Display for ActionMode {
@@ -35,6 +54,10 @@ impl Display for ActionMode {
             Self::Run => write!(f, "run"),
             Self::Terminal => write!(f, "terminal"),
             Self::Echo => write!(f, "echo"),
+            Self::Type => write!(f, "type"),
+            Self::Menu => write!(f, "menu"),
+            Self::Privileged => write!(f, "privileged"),
+            Self::Pipe => write!(f, "pipe"),
         }
     }
 }
@@ -47,6 +70,10 @@ impl FromStr for ActionMode {
             "run" => Ok(Self::Run),
             "terminal" => Ok(Self::Terminal),
             "echo" => Ok(Self::Echo),
+            "type" => Ok(Self::Type),
+            "menu" => Ok(Self::Menu),
+            "privileged" => Ok(Self::Privileged),
+            "pipe" => Ok(Self::Pipe),
             _ => Err(format!("Invalid Method: {s:?}")),
         }
     }
@@ -61,12 +88,24 @@ struct ActionArgs {
     /// Set Comment of Action
     #[arg(short, long)]
     comment: Option<String>,
+    /// Icon Image Path
+    #[arg(short, long)]
+    icon: Option<String>,
     /// Arguments to run As Action Command
     #[clap(required = true, value_delimiter = ' ')]
     args: Vec<String>,
     /// Action Mode
     #[arg(short, long, default_value_t=ActionMode::Run)]
     mode: ActionMode,
+    /// Keep the Menu Open after Execution
+    #[arg(short, long)]
+    keep_open: bool,
+    /// Environment Variable (`key=value`) Set for the Command
+    #[arg(short, long, value_parser=parse_env)]
+    env: Vec<(String, String)>,
+    /// Working Directory of the Command
+    #[arg(short = 'd', long)]
+    cwd: Option<PathBuf>,
 }
 
 impl Into<Action> for ActionArgs {
@@ -75,10 +114,18 @@ impl Into<Action> for ActionArgs {
         Action {
             name: self.name,
             comment: self.comment,
+            icon: self.icon,
+            close: !self.keep_open,
+            env: self.env.into_iter().collect(),
+            cwd: self.cwd,
             exec: match self.mode {
                 ActionMode::Run => Method::Run(exec),
                 ActionMode::Terminal => Method::Terminal(exec),
                 ActionMode::Echo => Method::Echo(exec),
+                ActionMode::Type => Method::Type(exec),
+                ActionMode::Menu => Method::Menu(exec),
+                ActionMode::Privileged => Method::RunPrivileged(exec),
+                ActionMode::Pipe => Method::Pipe(exec),
             },
         }
     }
@@ -103,6 +150,15 @@ struct EntryArgs {
     /// Alternative Image Text/HTML
     #[arg(short = 'I', long)]
     icon_alt: Option<String>,
+    /// Action Index or Name Run by Default
+    #[arg(short = 'D', long)]
+    default_action: Option<ActionRef>,
+    /// File Path or URI Provided when Dragging the Entry
+    #[arg(short = 'u', long)]
+    uri: Option<String>,
+    /// Metadata Field (`key=value`) Available to Action Placeholders
+    #[arg(short = 'x', long, value_parser=parse_meta)]
+    meta: Vec<(String, String)>,
 }
 
 impl Into<Entry> for EntryArgs {
@@ -113,6 +169,10 @@ impl Into<Entry> for EntryArgs {
             actions: self.actions,
             icon: self.icon,
             icon_alt: self.icon_alt,
+            default_action: self.default_action,
+            uri: self.uri,
+            source: None,
+            meta: self.meta.into_iter().collect(),
         }
     }
 }
@@ -123,29 +183,77 @@ struct OptionArgs {
     /// Override Applicaiton Theme
     #[arg(short = 'C', long)]
     pub css: Option<String>,
+    /// Override Base Theme by Name or Path
+    #[arg(long)]
+    pub theme: Option<String>,
+    /// Append CSS Snippet while the Plugin is Active
+    #[arg(long)]
+    pub style: Option<String>,
     #[arg(short = 's', long)]
     pub page_size: Option<usize>,
     #[arg(short = 'l', long)]
     pub page_load: Option<f64>,
     #[arg(short = 'd', long)]
     pub jump_dist: Option<usize>,
+    /// Override Maximum Number of Rendered Results per Plugin
+    #[arg(long)]
+    pub max_entries: Option<usize>,
+    /// Glyph Shown in Place of Missing Icons
+    #[arg(long)]
+    pub icon_fallback: Option<String>,
+    /// Override Font Family of the Menu
+    #[arg(long)]
+    pub font_family: Option<String>,
+    /// Override Font Size of the Menu (in Points)
+    #[arg(long)]
+    pub font_size: Option<f64>,
+    /// Keep the Menu Open after Executing any Action
+    #[arg(long)]
+    pub keep_open: Option<bool>,
+    /// Allow Marking Multiple Entries to Execute Together
+    #[arg(long)]
+    pub multi_select: Option<bool>,
+    /// Override how Marked Entries are Executed (sequential, parallel, echo-join)
+    #[arg(long)]
+    pub multi_exec: Option<String>,
     // search settings
     /// Override Default Placeholder
     #[arg(short = 'P', long)]
     pub placeholder: Option<String>,
+    /// Override Prompt Label before the Search
+    #[arg(long)]
+    pub prompt: Option<String>,
+    /// Override Prompt Icon (Path or Glyph) before the Search
+    #[arg(long)]
+    pub prompt_icon: Option<String>,
     /// Override Search Restriction
     #[arg(short = 'r', long)]
     pub search_restrict: Option<String>,
+    /// Block Typed Characters that Violate the Search Restriction
+    #[arg(long)]
+    pub search_restrict_input: Option<bool>,
     /// Override Minimum Search Length
     #[arg(short = 'm', long)]
     pub search_min_length: Option<usize>,
     /// Override Maximum Search Length
     #[arg(short = 'M', long)]
     pub search_max_length: Option<usize>,
+    /// Override Search Matcher (regex, substring, fuzzy, fzf)
+    #[arg(long)]
+    pub search_matcher: Option<String>,
+    /// Mask the Search and Echo it on Exec w/o Matching Entries
+    #[arg(long)]
+    pub search_password: Option<bool>,
+    /// Hide the Search and Route all Keys to Navigation
+    #[arg(long)]
+    pub disable_search: Option<bool>,
     // key settings
     /// Override Execution Keybinds
     #[arg(short = 'e', long)]
     pub key_exec: Option<Vec<String>>,
+    /// Override Alternate-Action Execution Keybinds
+    #[arg(long)]
+    pub key_exec_alt: Option<Vec<String>>,
     /// Override Program-Exit Keybinds
     #[arg(short = 'E', long)]
     pub key_exit: Option<Vec<String>>,
@@ -167,6 +275,27 @@ struct OptionArgs {
     /// Override Jump-Previous Keybinds
     #[arg(short = 'J', long)]
     pub key_jump_prev: Option<Vec<String>>,
+    /// Override Show-More Keybinds
+    #[arg(long)]
+    pub key_show_more: Option<Vec<String>>,
+    /// Override Clipboard-Paste Keybinds
+    #[arg(long)]
+    pub key_paste: Option<Vec<String>>,
+    /// Override Toggle-Select Keybinds
+    #[arg(long, alias = "key-mark")]
+    pub key_toggle_select: Option<Vec<String>>,
+    /// Override Jump-to-Letter Keybinds
+    #[arg(long)]
+    pub key_jump_letter: Option<Vec<String>>,
+    /// Override Hide-Entry Keybinds
+    #[arg(long)]
+    pub key_hide_entry: Option<Vec<String>>,
+    /// Override Toggle-Always-On-Top Keybinds
+    #[arg(long)]
+    pub key_toggle_top: Option<Vec<String>>,
+    /// Override Toggle-Sticky Keybinds
+    #[arg(long)]
+    pub key_toggle_sticky: Option<Vec<String>>,
     // window settings
     /// Override Window Title
     #[arg(short, long)]
@@ -192,14 +321,30 @@ impl Into<Options> for OptionArgs {
     fn into(self) -> Options {
         Options {
             css: self.css,
+            theme: self.theme,
+            style: self.style,
             page_size: self.page_size,
             page_load: self.page_load,
             jump_dist: self.jump_dist,
+            max_entries: self.max_entries,
+            icon_fallback: self.icon_fallback,
+            font_family: self.font_family,
+            font_size: self.font_size,
+            keep_open: self.keep_open,
+            multi_select: self.multi_select,
+            multi_exec: self.multi_exec,
             placeholder: self.placeholder,
+            prompt: self.prompt,
+            prompt_icon: self.prompt_icon,
             search_restrict: self.search_restrict,
+            search_restrict_input: self.search_restrict_input,
             search_min_length: self.search_min_length,
             search_max_length: self.search_max_length,
+            search_matcher: self.search_matcher,
+            search_password: self.search_password,
+            disable_search: self.disable_search,
             key_exec: self.key_exec,
+            key_exec_alt: self.key_exec_alt,
             key_exit: self.key_exit,
             key_move_next: self.key_move_next,
             key_move_prev: self.key_move_prev,
@@ -207,6 +352,13 @@ impl Into<Options> for OptionArgs {
             key_close_menu: self.key_close_menu,
             key_jump_next: self.key_jump_next,
             key_jump_prev: self.key_jump_prev,
+            key_show_more: self.key_show_more,
+            key_paste: self.key_paste,
+            key_toggle_select: self.key_toggle_select,
+            key_jump_letter: self.key_jump_letter,
+            key_hide_entry: self.key_hide_entry,
+            key_toggle_top: self.key_toggle_top,
+            key_toggle_sticky: self.key_toggle_sticky,
             title: self.title,
             decorate: self.deocorate,
             fullscreen: self.fullscreen,
@@ -0,0 +1,306 @@
+//! Freedesktop Icon Theme Lookup Resolving Icon Names into Files
+//!
+//! Icon names (e.g. `firefox`) are searched in a theme, the themes it
+//! inherits and `hicolor` closest to the preferred size, falling back to
+//! the unthemed icons in `/usr/share/pixmaps`. Shared by rmenu and the
+//! plugins resolving icons for their own entries.
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Icon File Extensions in Order of Preference
+static EXTENSIONS: &[&str] = &["png", "svg", "xpm"];
+static FALLBACK_THEME: &str = "hicolor";
+static PIXMAPS: &str = "/usr/share/pixmaps";
+/// Settings Files Naming the Icon Theme w/ their Group and Key
+static THEME_SETTINGS: &[(&str, &str, &str)] = &[
+    ("gtk-4.0/settings.ini", "Settings", "gtk-icon-theme-name"),
+    ("gtk-3.0/settings.ini", "Settings", "gtk-icon-theme-name"),
+    ("kdeglobals", "Icons", "Theme"),
+];
+
+/// Size Specification of a Single Theme Directory
+#[derive(Debug)]
+struct ThemeDir {
+    size: u32,
+    scale: u32,
+    kind: String,
+    min: u32,
+    max: u32,
+    threshold: u32,
+}
+
+/// Distance of the Value to the Inclusive Range (Zero when Within)
+#[inline]
+fn outside(value: u32, low: u32, high: u32) -> u32 {
+    match value {
+        v if v < low => low - v,
+        v if v > high => v - high,
+        _ => 0,
+    }
+}
+
+impl ThemeDir {
+    fn parse(group: &HashMap<String, String>) -> Option<Self> {
+        let num = |key: &str| group.get(key).and_then(|v| v.parse::<u32>().ok());
+        let size = num("Size")?;
+        Some(Self {
+            size,
+            scale: num("Scale").unwrap_or(1),
+            kind: group
+                .get("Type")
+                .cloned()
+                .unwrap_or_else(|| "Threshold".to_owned()),
+            min: num("MinSize").unwrap_or(size),
+            max: num("MaxSize").unwrap_or(size),
+            threshold: num("Threshold").unwrap_or(2),
+        })
+    }
+
+    /// Distance of the Icons to the Preferred Size in Pixels (Zero when Matching)
+    fn distance(&self, pixels: u32) -> u32 {
+        let scale = self.scale;
+        match self.kind.as_str() {
+            "Fixed" => (self.size * scale).abs_diff(pixels),
+            "Scalable" => outside(pixels, self.min * scale, self.max * scale),
+            _ => outside(
+                pixels,
+                self.size.saturating_sub(self.threshold) * scale,
+                (self.size + self.threshold) * scale,
+            ),
+        }
+    }
+}
+
+/// Parse the Groups and Keys of an INI Styled Index File
+fn sections(content: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut current = None;
+    for line in content.lines().map(|l| l.trim()) {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = Some(name.to_owned());
+            continue;
+        }
+        if let (Some(section), Some((key, value))) = (current.as_ref(), line.split_once('=')) {
+            sections
+                .entry(section.to_owned())
+                .or_default()
+                .insert(key.trim().to_owned(), value.trim().to_owned());
+        }
+    }
+    sections
+}
+
+/// Icon Theme w/ its Directories in Order of Appearance
+#[derive(Debug)]
+pub struct Theme {
+    name: String,
+    roots: Vec<PathBuf>,
+    dirs: Vec<(String, ThemeDir)>,
+    inherits: Vec<String>,
+}
+
+impl Theme {
+    /// Load the Named Theme from the First Base Directory Describing it
+    ///
+    /// Theme directories found in several base directories are merged.
+    fn load(bases: &[PathBuf], name: &str) -> Option<Self> {
+        let roots: Vec<PathBuf> = bases
+            .iter()
+            .map(|b| b.join(name))
+            .filter(|p| p.is_dir())
+            .collect();
+        let content = roots
+            .iter()
+            .find_map(|r| fs::read_to_string(r.join("index.theme")).ok())?;
+        let sections = sections(&content);
+        let main = sections.get("Icon Theme")?;
+        let list = |key: &str| -> Vec<String> {
+            main.get(key)
+                .map(|v| v.split(',').map(|s| s.trim().to_owned()).collect())
+                .unwrap_or_default()
+        };
+        let mut names = list("Directories");
+        for dir in list("ScaledDirectories") {
+            if !names.contains(&dir) {
+                names.push(dir);
+            }
+        }
+        let dirs = names
+            .into_iter()
+            .filter_map(|dir| {
+                let spec = ThemeDir::parse(sections.get(&dir)?)?;
+                Some((dir, spec))
+            })
+            .collect();
+        Some(Self {
+            name: name.to_owned(),
+            roots,
+            dirs,
+            inherits: list("Inherits"),
+        })
+    }
+
+    /// Retrieve every Directory the Theme Keeps Icons in
+    fn paths(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.roots
+            .iter()
+            .flat_map(|root| self.dirs.iter().map(move |(dir, _)| root.join(dir)))
+    }
+
+    /// Collect the Icon Closest to the Preferred Size for every Name
+    fn index(&self, pixels: u32, found: &mut HashMap<String, PathBuf>) {
+        let mut best: HashMap<String, ((u32, usize), PathBuf)> = HashMap::new();
+        for (dir, spec) in self.dirs.iter() {
+            let distance = spec.distance(pixels);
+            for root in self.roots.iter() {
+                let Ok(files) = fs::read_dir(root.join(dir)) else {
+                    continue;
+                };
+                for path in files.filter_map(|f| f.ok()).map(|f| f.path()) {
+                    let Some((name, rank)) = icon_name(&path) else {
+                        continue;
+                    };
+                    let score = (distance, rank);
+                    if best.get(&name).map(|(s, _)| *s <= score).unwrap_or(false) {
+                        continue;
+                    }
+                    best.insert(name, (score, path));
+                }
+            }
+        }
+        for (name, (_, path)) in best {
+            found.entry(name).or_insert(path);
+        }
+    }
+}
+
+/// Split an Icon Filename into its Name and Extension Preference
+fn icon_name(path: &Path) -> Option<(String, usize)> {
+    let ext = path.extension()?.to_str()?;
+    let rank = EXTENSIONS.iter().position(|e| *e == ext)?;
+    let name = path.file_stem()?.to_str()?;
+    Some((name.to_owned(), rank))
+}
+
+/// Check if the Icon is Named rather than Linked (or a Glyph)
+#[inline]
+pub fn is_name(icon: &str) -> bool {
+    !icon.is_empty()
+        && icon
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.+".contains(c))
+}
+
+/// Determine the Icon Theme Configured for GTK (or KDE) Applications
+pub fn detect_theme(config_home: &Path) -> Option<String> {
+    THEME_SETTINGS.iter().find_map(|(file, group, key)| {
+        let content = fs::read_to_string(config_home.join(file)).ok()?;
+        let settings = sections(&content);
+        let theme = settings.get(*group)?.get(*key)?;
+        Some(theme.trim_matches('"').to_owned())
+    })
+}
+
+/// Collect the Theme, the Themes it Inherits (Depth-First) and Hicolor
+pub fn themes(bases: &[PathBuf], theme: Option<&str>) -> Vec<Theme> {
+    fn inherit(bases: &[PathBuf], name: &str, chain: &mut Vec<Theme>) {
+        if name == FALLBACK_THEME || chain.iter().any(|t| t.name == name) {
+            return;
+        }
+        let Some(theme) = Theme::load(bases, name) else {
+            return;
+        };
+        let parents = theme.inherits.clone();
+        chain.push(theme);
+        for parent in parents {
+            inherit(bases, &parent, chain);
+        }
+    }
+    let mut chain = vec![];
+    if let Some(theme) = theme {
+        inherit(bases, theme, &mut chain);
+    }
+    chain.extend(Theme::load(bases, FALLBACK_THEME));
+    chain
+}
+
+/// Map every Icon Name to its Preferred File across the Themes
+///
+/// Earlier themes take precedence, no matter how close to the size
+/// icons of later themes are. Unthemed icons are only used when no
+/// theme provides the name.
+pub fn index(chain: &[Theme], pixels: u32) -> HashMap<String, PathBuf> {
+    let mut found = HashMap::new();
+    for theme in chain {
+        theme.index(pixels, &mut found);
+    }
+    if let Ok(files) = fs::read_dir(PIXMAPS) {
+        for path in files.filter_map(|f| f.ok()).map(|f| f.path()) {
+            if let Some((name, _)) = icon_name(&path) {
+                found.entry(name).or_insert(path);
+            }
+        }
+    }
+    found
+}
+
+/// Latest Modification of any Directory Icons are Indexed from
+///
+/// Adding or removing icons changes the modification time of the size
+/// directory they are in, so lookups remembered from an older state of
+/// the themes can be told apart.
+pub fn modified(chain: &[Theme]) -> u64 {
+    chain
+        .iter()
+        .flat_map(|t| t.roots.iter().cloned().chain(t.paths()))
+        .chain([PathBuf::from(PIXMAPS)])
+        .filter_map(|p| fs::metadata(p).and_then(|m| m.modified()).ok())
+        .filter_map(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .max()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icon_theme_lookup() {
+        let base = std::env::temp_dir().join(format!("rmenu-test-icons-{}", std::process::id()));
+        let write = |path: &str, content: &str| {
+            let path = base.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "child/index.theme",
+            "[Icon Theme]\nInherits=parent\nDirectories=16x16/apps\n\n[16x16/apps]\nSize=16\nType=Fixed\n",
+        );
+        write(
+            "parent/index.theme",
+            "[Icon Theme]\nDirectories=48x48/apps,scalable/apps\n\n[48x48/apps]\nSize=48\n\n\
+             [scalable/apps]\nSize=48\nMinSize=8\nMaxSize=512\nType=Scalable\n",
+        );
+        write("child/16x16/apps/term.png", "");
+        write("parent/48x48/apps/term.png", "");
+        write("parent/48x48/apps/firefox.png", "");
+        write("parent/scalable/apps/firefox.svg", "");
+        let chain = themes(&[base.clone()], Some("child"));
+        let found = index(&chain, 64);
+        let paths: Vec<PathBuf> = chain.iter().flat_map(|t| t.paths()).collect();
+        std::fs::remove_dir_all(&base).unwrap();
+        // closest size within a theme, but earlier themes always take precedence
+        assert_eq!(
+            found["firefox"],
+            base.join("parent/scalable/apps/firefox.svg")
+        );
+        assert_eq!(found["term"], base.join("child/16x16/apps/term.png"));
+        assert!(paths.contains(&base.join("parent/48x48/apps")));
+    }
+}
@@ -1,5 +1,13 @@
 //! RMenu-Plugin Object Implementations
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufWriter, StdoutLock, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub mod icons;
 
 /// Methods allowed to Execute Actions on Selection
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -8,6 +16,20 @@ pub enum Method {
     Terminal(String),
     Run(String),
     Echo(String),
+    Type(String),
+    Menu(String),
+    RunPrivileged(String),
+    /// Run the Command w/ the JSON of its Entry Written to Stdin
+    Pipe(String),
+    /// Pipe Command Bound to the Entry JSON it Receives (Resolved on Execution)
+    #[serde(skip)]
+    PipeInput(String, String),
+    /// Re-Run the Named Source w/o its Cache (Added in Combined Mode)
+    #[serde(skip)]
+    RefreshSource(String),
+    /// Hide Entries of the Named Source (Added in Combined Mode)
+    #[serde(skip)]
+    DisableSource(String),
 }
 
 impl Method {
@@ -18,6 +40,19 @@ impl Method {
             false => Self::Run(exec),
         }
     }
+
+    /// Check if the Method is only Used inside RMenu (and never Serialized)
+    pub fn is_internal(&self) -> bool {
+        matches!(
+            self,
+            Self::PipeInput(..) | Self::RefreshSource(_) | Self::DisableSource(_)
+        )
+    }
+}
+
+#[inline]
+fn _true() -> bool {
+    true
 }
 
 /// RMenu Entry Action Definition
@@ -26,6 +61,16 @@ pub struct Action {
     pub name: String,
     pub exec: Method,
     pub comment: Option<String>,
+    pub icon: Option<String>,
+    /// Close the Menu after Execution (Otherwise Refresh its Entries)
+    #[serde(default = "_true")]
+    pub close: bool,
+    /// Additional Environment Variables of the Command
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    /// Working Directory of the Command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
 }
 
 impl Action {
@@ -35,6 +80,10 @@ impl Action {
             name: "main".to_string(),
             exec: Method::Run(exec.to_string()),
             comment: None,
+            icon: None,
+            close: true,
+            env: Default::default(),
+            cwd: None,
         }
     }
     /// Generate a simple Echo Action
@@ -43,6 +92,29 @@ impl Action {
             name: "main".to_string(),
             exec: Method::Echo(echo.to_string()),
             comment: None,
+            icon: None,
+            close: true,
+            env: Default::default(),
+            cwd: None,
+        }
+    }
+}
+
+/// Reference to an Entry Action by Index or Name
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ActionRef {
+    Index(usize),
+    Name(String),
+}
+
+impl FromStr for ActionRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<usize>() {
+            Ok(index) => Ok(Self::Index(index)),
+            Err(_) => Ok(Self::Name(s.to_owned())),
         }
     }
 }
@@ -56,6 +128,12 @@ pub struct Entry {
     pub comment: Option<String>,
     pub icon: Option<String>,
     pub icon_alt: Option<String>,
+    pub default_action: Option<ActionRef>,
+    pub uri: Option<String>,
+    pub source: Option<String>,
+    /// Arbitrary Fields Available to Action Placeholders (e.g. `{meta.path}`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub meta: BTreeMap<String, String>,
 }
 
 impl Entry {
@@ -67,6 +145,10 @@ impl Entry {
             comment: comment.map(|c| c.to_owned()),
             icon: Default::default(),
             icon_alt: Default::default(),
+            default_action: Default::default(),
+            uri: Default::default(),
+            source: Default::default(),
+            meta: Default::default(),
         }
     }
     /// Generate a simplified Echo Action Entry
@@ -77,6 +159,22 @@ impl Entry {
             comment: comment.map(|c| c.to_owned()),
             icon: Default::default(),
             icon_alt: Default::default(),
+            default_action: Default::default(),
+            uri: Default::default(),
+            source: Default::default(),
+            meta: Default::default(),
+        }
+    }
+    /// Retrieve Index of the Action Run when the Entry is Executed
+    pub fn default_index(&self) -> usize {
+        match self.default_action.as_ref() {
+            Some(ActionRef::Index(index)) if *index < self.actions.len() => *index,
+            Some(ActionRef::Name(name)) => self
+                .actions
+                .iter()
+                .position(|a| &a.name == name)
+                .unwrap_or_default(),
+            _ => 0,
         }
     }
 }
@@ -89,24 +187,56 @@ pub struct Options {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub css: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub page_size: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page_load: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub jump_dist: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_fallback: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_family: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_open: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multi_select: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multi_exec: Option<String>,
     // search settings
     #[serde(skip_serializing_if = "Option::is_none")]
     pub placeholder: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub search_restrict: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_restrict_input: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub search_min_length: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub search_max_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_matcher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_password: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_search: Option<bool>,
     // key settings
     #[serde(skip_serializing_if = "Option::is_none")]
     pub key_exec: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_exec_alt: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub key_exit: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub key_move_next: Option<Vec<String>>,
@@ -120,6 +250,20 @@ pub struct Options {
     pub key_jump_next: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub key_jump_prev: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_show_more: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_paste: Option<Vec<String>>,
+    #[serde(alias = "key_mark", skip_serializing_if = "Option::is_none")]
+    pub key_toggle_select: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_jump_letter: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_hide_entry: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_toggle_top: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_toggle_sticky: Option<Vec<String>>,
     // window settings
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
@@ -135,6 +279,40 @@ pub struct Options {
     pub window_height: Option<f64>,
 }
 
+impl Options {
+    /// Fill the Unset Fields w/ the Values of Lower-Precedence Options
+    ///
+    /// Styles are concatenated instead, keeping these rules last so they
+    /// still win the cascade. Returns the names of the fields taken.
+    pub fn merge(&mut self, other: &Options) -> Vec<String> {
+        let (Ok(Value::Object(mut fields)), Ok(Value::Object(others))) =
+            (serde_json::to_value(&*self), serde_json::to_value(other))
+        else {
+            return vec![];
+        };
+        let mut taken = vec![];
+        for (field, value) in others {
+            if field == "type" || field == "style" || fields.contains_key(&field) {
+                continue;
+            }
+            fields.insert(field.clone(), value);
+            taken.push(field);
+        }
+        let style = self.style.take();
+        if let Ok(merged) = serde_json::from_value(Value::Object(fields)) {
+            *self = merged;
+        }
+        self.style = match (other.style.as_ref(), style) {
+            (Some(other), Some(style)) => Some(format!("{other}\n{style}")),
+            (other, style) => style.or_else(|| other.cloned()),
+        };
+        if other.style.is_some() {
+            taken.push("style".to_owned());
+        }
+        taken
+    }
+}
+
 /// Valid RMenu Plugin Messages
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
@@ -143,6 +321,115 @@ pub enum Message {
     Options(Options),
 }
 
+/// Environment Variable Set when a Plugin Runs in Streaming Mode
+///
+/// Streaming plugins stay alive while the menu is open, receive every
+/// search as one line on stdin and answer each w/ their messages
+/// followed by an empty line (see [`EntryWriter::end_response`]).
+pub static STREAM_ENV: &'static str = "RMENU_STREAM";
+
+/// Search Query Sent to a Streaming Plugin
+///
+/// Plugins configured w/ `request_ids` receive queries as JSON lines and
+/// name the request they answer w/ a [`Batch`] header, so rmenu can
+/// discard late answers to searches the user already typed past.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "query")]
+pub struct Query {
+    pub request_id: u64,
+    pub search: String,
+}
+
+impl Query {
+    /// Parse a Query Line, Accepting Plain Searches w/o a Request ID
+    pub fn parse(line: &str) -> Self {
+        serde_json::from_str(line).unwrap_or_else(|_| Self {
+            request_id: 0,
+            search: line.to_owned(),
+        })
+    }
+}
+
+/// Header Naming the Request the Following Messages of an Answer Belong to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "batch")]
+pub struct Batch {
+    pub request_id: u64,
+}
+
+/// Read the Queries Sent to a Streaming Plugin (One per Line)
+pub fn requests() -> impl Iterator<Item = Query> {
+    io::stdin()
+        .lines()
+        .filter_map(|l| l.ok())
+        .map(|l| Query::parse(&l))
+}
+
+/// Read the Search Queries Sent to a Streaming Plugin (One per Line)
+pub fn queries() -> impl Iterator<Item = String> {
+    requests().map(|q| q.search)
+}
+
+/// Buffered Writer Serializing Plugin Messages as JSON Lines
+///
+/// Messages are serialized straight into a large buffer, so big entry
+/// sets are written in a few large writes instead of one per entry.
+pub struct EntryWriter<W: Write> {
+    out: BufWriter<W>,
+}
+
+impl EntryWriter<StdoutLock<'static>> {
+    /// Spawn Writer on a Single Locked Stdout Handle
+    pub fn stdout() -> Self {
+        Self::new(io::stdout().lock())
+    }
+}
+
+impl<W: Write> EntryWriter<W> {
+    /// Spawn Writer on the Specified Output
+    pub fn new(out: W) -> Self {
+        Self {
+            out: BufWriter::with_capacity(64 * 1024, out),
+        }
+    }
+
+    /// Serialize a Single Entry, Options or other Message
+    pub fn write<T: Serialize>(&mut self, message: &T) -> io::Result<()> {
+        serde_json::to_writer(&mut self.out, message)?;
+        self.out.write_all(b"\n")
+    }
+
+    /// Serialize every Entry of the Iterator
+    pub fn write_all<'a>(
+        &mut self,
+        entries: impl IntoIterator<Item = &'a Entry>,
+    ) -> io::Result<()> {
+        for entry in entries {
+            self.write(entry)?;
+        }
+        Ok(())
+    }
+
+    /// Flush Buffered Messages to the Output
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    /// Start the Answer to a Streamed Query w/ its Request ID (if Any)
+    pub fn begin_response(&mut self, query: &Query) -> io::Result<()> {
+        match query.request_id {
+            0 => Ok(()),
+            request_id => self.write(&Batch { request_id }),
+        }
+    }
+
+    /// End the Answer to a Streamed Query and Flush it to RMenu
+    pub fn end_response(&mut self) -> io::Result<()> {
+        self.out.write_all(b"\n")?;
+        self.out.flush()
+    }
+}
+
 /// Retrieve EXE of Self
 #[inline]
 pub fn self_exe() -> String {
@@ -152,3 +439,40 @@ pub fn self_exe() -> String {
         .unwrap()
         .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_environment() {
+        let json = r#"{"name":"build","exec":{"run":"make"},"env":{"CC":"clang"},"cwd":"/src"}"#;
+        let action: Action = serde_json::from_str(json).unwrap();
+        assert_eq!(action.env.get("CC").map(|s| s.as_str()), Some("clang"));
+        assert_eq!(action.cwd, Some(PathBuf::from("/src")));
+        assert!(action.close);
+        let plain = serde_json::to_string(&Action::exec("make")).unwrap();
+        assert!(!plain.contains("env") && !plain.contains("cwd"));
+    }
+
+    #[test]
+    fn test_options_aliases() {
+        let options: Options = serde_json::from_str(
+            r#"{"multi_select": true, "multi_exec": "echo-join", "key_mark": ["Ctrl+KeyM"]}"#,
+        )
+        .unwrap();
+        assert_eq!(options.multi_select, Some(true));
+        assert_eq!(
+            options.key_toggle_select,
+            Some(vec!["Ctrl+KeyM".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_query_parse() {
+        let query = Query::parse(r#"{"type":"query","request_id":7,"search":"fire"}"#);
+        assert_eq!((query.request_id, query.search.as_str()), (7, "fire"));
+        let plain = Query::parse("{fire}");
+        assert_eq!((plain.request_id, plain.search.as_str()), (0, "{fire}"));
+    }
+}
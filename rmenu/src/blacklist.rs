@@ -0,0 +1,65 @@
+//! Persisted Per-Plugin Blacklists of Hidden Entries
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use rmenu_plugin::Entry;
+
+use crate::search::Normalized;
+
+/// Retrieve the Blacklist File of the Specified Plugin
+#[inline]
+fn blacklist_path(plugin: &str) -> PathBuf {
+    crate::paths::data_dir().join("blacklist").join(plugin)
+}
+
+/// Load Entry Names Hidden for the Plugin in Earlier Sessions
+pub fn load(plugin: &str) -> Vec<String> {
+    fs::read_to_string(blacklist_path(plugin))
+        .map(|names| names.lines().map(|l| l.to_owned()).collect())
+        .unwrap_or_default()
+}
+
+/// Remove Blacklisted Entries (Configured or Persisted) of the Plugin
+///
+/// Entries are removed after caching, so changes to the blacklist apply
+/// without refreshing the cache.
+pub fn filter(
+    plugin: &str,
+    configured: &[String],
+    entries: &mut Vec<Entry>,
+    normalized: &mut Vec<Normalized>,
+) {
+    let mut names = load(plugin);
+    names.extend(configured.iter().cloned());
+    if names.is_empty() {
+        return;
+    }
+    let keep: Vec<bool> = entries.iter().map(|e| !names.contains(&e.name)).collect();
+    let mut flags = keep.iter();
+    entries.retain(|_| *flags.next().unwrap_or(&true));
+    let mut flags = keep.iter();
+    normalized.retain(|_| *flags.next().unwrap_or(&true));
+}
+
+/// Persist a Hidden Entry to the Blacklist of its Plugin
+///
+/// Entries not produced by a plugin (e.g. stdin) are only hidden for
+/// the current session.
+pub fn persist(entry: &Entry) {
+    let Some(plugin) = entry.source.as_ref() else {
+        log::warn!("cannot blacklist {:?} w/o a source plugin", entry.name);
+        return;
+    };
+    let path = blacklist_path(plugin);
+    let result = path
+        .parent()
+        .map(fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| OpenOptions::new().create(true).append(true).open(&path))
+        .and_then(|mut f| writeln!(f, "{}", entry.name));
+    match result {
+        Ok(_) => log::info!("blacklisted {:?} for plugin {plugin:?}", entry.name),
+        Err(err) => log::error!("failed to write blacklist {path:?}: {err:?}"),
+    }
+}
@@ -0,0 +1,141 @@
+//! RMenu First-Run Configuration Bootstrap
+use std::fs::{self, create_dir_all};
+use std::os::unix::fs::{symlink, PermissionsExt};
+use std::path::Path;
+
+use which::which;
+
+use crate::cli::Result;
+use crate::{DEFAULT_CONFIG, DEFAULT_THEME};
+
+static DEFAULT_CONFIG_CONTENT: &'static str = include_str!("../public/config.yaml");
+static DEFAULT_PLUGIN_DIR: &'static str = "~/.config/rmenu/plugins/";
+static DEFAULT_STYLE: &'static str = "themes/dark.css";
+
+/// Themes Installed by Default
+static THEMES: &[(&str, &str)] = &[
+    ("dark.css", include_str!("../../themes/dark.css")),
+    ("launchpad.css", include_str!("../../themes/launchpad.css")),
+    ("nord.css", include_str!("../../themes/nord.css")),
+    ("solarized.css", include_str!("../../themes/solarized.css")),
+];
+
+/// Script Plugins Installed by Default
+static SCRIPTS: &[(&str, &str)] = &[
+    (
+        "pactl-audio.sh",
+        include_str!("../../other-plugins/pactl-audio.sh"),
+    ),
+    (
+        "quick-adjust.sh",
+        include_str!("../../other-plugins/quick-adjust.sh"),
+    ),
+    (
+        "powermenu.sh",
+        include_str!("../../other-plugins/powermenu.sh"),
+    ),
+    (
+        "css/powermenu.css",
+        include_str!("../../other-plugins/css/powermenu.css"),
+    ),
+];
+
+/// Binary Plugins Detected on PATH
+static BINARIES: &[&str] = &[
+    "rmenu-run",
+    "rmenu-desktop",
+    "rmenu-network",
+    "rmenu-window",
+    "rmenu-emoji",
+    "rmenu-clipboard",
+    "rmenu-power",
+];
+
+/// Write File Contents Unless it Already Exists
+fn install(path: &Path, content: &str, force: bool) -> Result<bool> {
+    if path.exists() && !force {
+        println!("skipping existing {path:?}");
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    println!("created {path:?}");
+    Ok(true)
+}
+
+/// Generate Default Configuration w/ Detected Plugin Paths
+fn default_config(plugins: &Path) -> String {
+    let mut config = DEFAULT_CONFIG_CONTENT.to_owned();
+    for bin in BINARIES {
+        if plugins.join(bin).exists() {
+            println!("found plugin {bin:?} in {plugins:?}");
+            continue;
+        }
+        match which(bin) {
+            Ok(path) => {
+                println!("found plugin {bin:?} at {path:?}");
+                let default = format!("{DEFAULT_PLUGIN_DIR}{bin}");
+                config = config.replace(&default, &path.to_string_lossy());
+            }
+            Err(_) => println!("plugin {bin:?} not found, install it into {plugins:?}"),
+        }
+    }
+    config.replace(DEFAULT_PLUGIN_DIR, &plugin_prefix(plugins))
+}
+
+/// Prefix of Plugins Installed into the Given Directory
+///
+/// The default location keeps its `~` form, so the generated config stays
+/// portable across home directories.
+fn plugin_prefix(plugins: &Path) -> String {
+    let default = shellexpand::tilde(DEFAULT_PLUGIN_DIR).to_string();
+    let prefix = format!("{}/", plugins.to_string_lossy().trim_end_matches('/'));
+    match prefix == default {
+        true => DEFAULT_PLUGIN_DIR.to_owned(),
+        false => prefix,
+    }
+}
+
+/// Create Configuration Directory w/ Default Config, Themes and Plugins
+pub fn init(force: bool) -> Result<()> {
+    let root = crate::paths::config_dir();
+    let plugins = root.join("plugins");
+    create_dir_all(&plugins)?;
+    // install themes and link default stylesheet
+    for (name, content) in THEMES {
+        install(&root.join("themes").join(name), content, force)?;
+    }
+    let style = root.join(DEFAULT_THEME);
+    if force || !style.exists() {
+        let _ = fs::remove_file(&style);
+        symlink(root.join(DEFAULT_STYLE), &style)?;
+        println!("linked {style:?} -> {DEFAULT_STYLE:?}");
+    }
+    // install script plugins
+    for (name, content) in SCRIPTS {
+        let path = plugins.join(name);
+        if install(&path, content, force)? && name.ends_with(".sh") {
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        }
+    }
+    // write configuration w/ detected plugins registered
+    install(&root.join(DEFAULT_CONFIG), &default_config(&plugins), force)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_prefix() {
+        let plugins = Path::new("/tmp/rmenu-init/plugins");
+        let config = default_config(plugins);
+        assert!(!config.contains(DEFAULT_PLUGIN_DIR));
+        assert!(config.contains("/tmp/rmenu-init/plugins/quick-adjust.sh"));
+        let home = shellexpand::tilde(DEFAULT_PLUGIN_DIR).to_string();
+        assert_eq!(plugin_prefix(Path::new(&home)), DEFAULT_PLUGIN_DIR);
+    }
+}
@@ -1,13 +1,27 @@
 //! RMenu Plugin Result Cache
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
+use hmac::{Hmac, Mac};
+use once_cell::sync::OnceCell;
 use rmenu_plugin::Entry;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use thiserror::Error;
 
 use crate::config::{CacheSetting, PluginConfig};
-use crate::XDG_PREFIX;
+use crate::paths::cache_dir;
+use crate::search::Normalized;
+
+/// Cache Format Version (Bumped whenever the Layout Changes)
+static CACHE_VERSION: u32 = 4;
+
+static SECRET_NAME: &'static str = "cache.key";
+static SECRET: OnceCell<Vec<u8>> = OnceCell::new();
 
 #[derive(Debug, Error)]
 pub enum CacheError {
@@ -17,31 +31,149 @@ pub enum CacheError {
     InvalidCache,
     #[error("Cache Expired")]
     CacheExpired,
+    #[error("Cache Exceeds Size Limit")]
+    TooLarge(u64),
     #[error("Cache File Error")]
     FileError(#[from] std::io::Error),
     #[error("Encoding Error")]
     EncodingError(#[from] serde_json::Error),
+    #[error("Cache Integrity Check Failed (Modified outside of RMenu)")]
+    Tampered,
+}
+
+/// Header Line of a Cache File, Followed by the Payload it Authenticates
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheHeader {
+    version: u32,
+    mac: String,
+}
+
+/// Cached Plugin Results w/ Pre-Normalized Search Fields
+type CachePayload = (Vec<Entry>, Vec<Normalized>);
+
+/// Generate the Secret File, Keeping one Written Concurrently by Another Instance
+fn create_secret(dir: &Path, path: &Path) -> Result<Vec<u8>, CacheError> {
+    let mut secret = vec![0u8; 32];
+    fs::File::open("/dev/urandom")?.read_exact(&mut secret)?;
+    fs::create_dir_all(dir)?;
+    // written in full before being linked into place, so other instances
+    // never read a partial secret and only one secret ever wins
+    let temp = path.with_extension(format!("key.{}", std::process::id()));
+    fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&temp)?
+        .write_all(&secret)?;
+    let linked = fs::hard_link(&temp, path);
+    let _ = fs::remove_file(&temp);
+    match linked {
+        Ok(()) => {
+            log::debug!("generated cache secret {path:?}");
+            Ok(secret)
+        }
+        Err(err) if err.kind() == ErrorKind::AlreadyExists => Ok(fs::read(path)?),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Read (or Generate) the Per-User Random Secret Keying Cache Checksums
+///
+/// The secret lives in the data directory w/ owner-only permissions, so
+/// processes rewriting the cache directory cannot forge valid caches.
+fn secret() -> Result<&'static [u8], CacheError> {
+    if let Some(secret) = SECRET.get() {
+        return Ok(secret);
+    }
+    let dir = crate::paths::data_dir();
+    let path = dir.join(SECRET_NAME);
+    let secret = match fs::read(&path) {
+        Ok(secret) if secret.len() == 32 => secret,
+        Ok(_) => {
+            // replace a corrupt secret, unless another instance already did
+            let _ = fs::remove_file(&path);
+            create_secret(&dir, &path)?
+        }
+        Err(_) => create_secret(&dir, &path)?,
+    };
+    if secret.len() != 32 {
+        return Err(CacheError::InvalidCache);
+    }
+    Ok(SECRET.get_or_init(|| secret))
+}
+
+/// Compute the HMAC-SHA256 of the Plugin Name and the Stored Payload
+fn checksum(key: &[u8], name: &str, payload: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key size");
+    mac.update(name.as_bytes());
+    mac.update(&[0]);
+    mac.update(payload);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Encode the Cache File as its Header Line and the Payload it Authenticates
+fn encode(
+    key: &[u8],
+    name: &str,
+    entries: &[Entry],
+    normalized: &[Normalized],
+) -> Result<Vec<u8>, CacheError> {
+    let payload = serde_json::to_vec(&(entries, normalized))?;
+    let header = CacheHeader {
+        version: CACHE_VERSION,
+        mac: checksum(key, name, &payload),
+    };
+    let mut data = serde_json::to_vec(&header)?;
+    data.push(b'\n');
+    data.extend(payload);
+    Ok(data)
+}
+
+/// Decode the Cache File after Authenticating its Payload Bytes as Stored
+///
+/// Payloads are never re-serialized for the check, since the order of
+/// map fields (e.g. action environments) differs between processes.
+fn decode(key: &[u8], name: &str, data: &[u8]) -> Result<CachePayload, CacheError> {
+    let (header, payload) = match data.iter().position(|b| *b == b'\n') {
+        Some(split) => (&data[..split], &data[split + 1..]),
+        None => (data, &data[data.len()..]),
+    };
+    let header: CacheHeader = serde_json::from_slice(header)?;
+    if header.version != CACHE_VERSION {
+        return Err(CacheError::InvalidCache);
+    }
+    // never trust commands from caches that rmenu did not write itself
+    if !verify(&checksum(key, name, payload), &header.mac) {
+        log::warn!("cache of {name:?} failed its integrity check, re-running plugin");
+        return Err(CacheError::Tampered);
+    }
+    Ok(serde_json::from_slice(payload)?)
+}
+
+/// Compare Checksums w/o Leaking the Position of the First Mismatch
+fn verify(expected: &str, actual: &str) -> bool {
+    expected.len() == actual.len()
+        && expected
+            .bytes()
+            .zip(actual.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
 }
 
 #[inline]
 fn cache_file(name: &str) -> PathBuf {
-    xdg::BaseDirectories::with_prefix(XDG_PREFIX)
-        .expect("Failed to read xdg base dirs")
-        .place_cache_file(format!("{name}.cache"))
-        .expect("Failed to write xdg cache dirs")
+    let dir = cache_dir();
+    fs::create_dir_all(&dir).expect("Failed to write xdg cache dirs");
+    dir.join(format!("{name}.cache"))
 }
 
-/// Read Entries from Cache (if Valid and Available)
-pub fn read_cache(name: &str, cfg: &PluginConfig) -> Result<Vec<Entry>, CacheError> {
-    // confirm cache exists
-    let path = cache_file(name);
-    if !path.exists() {
-        return Err(CacheError::NotAvailable);
-    }
-    // get file modified date
-    let meta = path.metadata()?;
-    let modified = meta.modified()?;
-    // confirm cache is not expired
+/// Confirm Cache Modified at the Given Time is still Valid
+fn check_expiry(cfg: &PluginConfig, modified: SystemTime) -> Result<(), CacheError> {
     match cfg.cache {
         CacheSetting::NoCache => return Err(CacheError::InvalidCache),
         CacheSetting::Never => {}
@@ -65,23 +197,196 @@ pub fn read_cache(name: &str, cfg: &PluginConfig) -> Result<Vec<Entry>, CacheErr
             }
         }
     }
+    Ok(())
+}
+
+/// Cache File Age, Size and Validity for Diagnostics
+#[derive(Debug)]
+pub struct CacheStatus {
+    pub path: PathBuf,
+    pub age: Duration,
+    pub size: u64,
+    pub fresh: bool,
+}
+
+/// Retrieve Status of the Plugin Cache File (if it Exists)
+pub fn cache_status(name: &str, cfg: &PluginConfig) -> Option<CacheStatus> {
+    let path = cache_file(name);
+    let meta = path.metadata().ok()?;
+    let modified = meta.modified().ok()?;
+    Some(CacheStatus {
+        path,
+        age: SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default(),
+        size: meta.len(),
+        fresh: check_expiry(cfg, modified).is_ok(),
+    })
+}
+
+/// Read Entries from Cache (if Valid and Available)
+pub fn read_cache(
+    name: &str,
+    cfg: &PluginConfig,
+) -> Result<(Vec<Entry>, Vec<Normalized>), CacheError> {
+    // confirm cache exists
+    let path = cache_file(name);
+    if !path.exists() {
+        return Err(CacheError::NotAvailable);
+    }
+    // get file modified date and confirm cache is not expired
+    let meta = path.metadata()?;
+    check_expiry(cfg, meta.modified()?)?;
     // attempt to read content
-    let data = fs::read(path)?;
-    let results: Vec<Entry> = serde_json::from_slice(&data)?;
-    Ok(results)
+    let (entries, normalized) = decode(secret()?, name, &fs::read(&path)?)?;
+    if entries.len() != normalized.len() {
+        return Err(CacheError::InvalidCache);
+    }
+    touch(&path);
+    Ok((entries, normalized))
+}
+
+/// Pretty-Print Cached Entries as JSON (Regardless of Expiry)
+pub fn dump_cache(name: &str) -> Result<String, CacheError> {
+    let path = cache_file(name);
+    if !path.exists() {
+        return Err(CacheError::NotAvailable);
+    }
+    let (entries, _) = decode(secret()?, name, &fs::read(path)?)?;
+    Ok(serde_json::to_string_pretty(&entries)?)
+}
+
+/// Remove Expired Caches and Caches of Plugins no longer Configured
+pub fn prune_caches(plugins: &BTreeMap<String, PluginConfig>) -> Result<Vec<String>, CacheError> {
+    let mut removed = vec![];
+    let dir = cache_dir();
+    if !dir.exists() {
+        return Ok(removed);
+    }
+    for entry in fs::read_dir(dir)?.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.extension().map(|e| e != "cache").unwrap_or(true) {
+            continue;
+        }
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        let expired = match plugins.get(name.as_ref()) {
+            Some(cfg) => check_expiry(cfg, entry.metadata()?.modified()?).is_err(),
+            None => true,
+        };
+        if expired {
+            log::debug!("{name:?} removing cache {path:?}");
+            fs::remove_file(&path)?;
+            removed.push(name.to_string());
+        }
+    }
+    Ok(removed)
+}
+
+/// Mark Cache as Recently Used w/o Changing its Modified Time
+fn touch(path: &Path) {
+    let times = fs::FileTimes::new().set_accessed(SystemTime::now());
+    if let Err(err) = fs::File::options()
+        .write(true)
+        .open(path)
+        .and_then(|f| f.set_times(times))
+    {
+        log::debug!("failed to update cache access time: {err:?}");
+    }
+}
+
+/// Evict Least-Recently Used Caches until Total Size fits the Limit
+pub fn evict_caches(max_bytes: u64) -> Result<Vec<String>, CacheError> {
+    let mut removed = vec![];
+    let dir = cache_dir();
+    if max_bytes == 0 || !dir.exists() {
+        return Ok(removed);
+    }
+    let mut caches = vec![];
+    for entry in fs::read_dir(dir)?.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.extension().map(|e| e != "cache").unwrap_or(true) {
+            continue;
+        }
+        let meta = entry.metadata()?;
+        let used = meta.accessed().or_else(|_| meta.modified())?;
+        caches.push((used, meta.len(), path));
+    }
+    let mut total: u64 = caches.iter().map(|(_, size, _)| size).sum();
+    caches.sort_by_key(|(used, _, _)| *used);
+    for (_, size, path) in caches {
+        if total <= max_bytes {
+            break;
+        }
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        log::info!("{name:?} evicting cache ({size} bytes) to fit limit");
+        fs::remove_file(&path)?;
+        removed.push(name.to_string());
+        total -= size;
+    }
+    Ok(removed)
 }
 
 /// Write Results to Cache (if Allowed)
-pub fn write_cache(name: &str, cfg: &PluginConfig, entries: &Vec<Entry>) -> Result<(), CacheError> {
+pub fn write_cache(
+    name: &str,
+    cfg: &PluginConfig,
+    entries: &[Entry],
+    normalized: &[Normalized],
+) -> Result<(), CacheError> {
     // write cache if allowed
     match cfg.cache {
         CacheSetting::NoCache => {}
         _ => {
             log::debug!("{name:?} writing {} entries", entries.len());
+            // write to temporary file and rename so an interrupted
+            // write never leaves a truncated cache behind
             let path = cache_file(name);
-            let f = fs::File::create(path)?;
-            serde_json::to_writer(f, entries)?;
+            let data = encode(secret()?, name, entries, normalized)?;
+            let size = data.len() as u64;
+            if cfg.cache_max_bytes.map(|max| size > max).unwrap_or(false) {
+                // never serve a stale cache once results outgrow the limit
+                if path.exists() {
+                    fs::remove_file(&path)?;
+                }
+                return Err(CacheError::TooLarge(size));
+            }
+            let temp = path.with_extension("cache.tmp");
+            fs::write(&temp, data)?;
+            fs::rename(temp, path)?;
         }
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmenu_plugin::Action;
+
+    #[test]
+    fn test_cache_integrity() {
+        let key = [7u8; 32];
+        let mut action = Action::exec("make");
+        action.env.insert("CC".to_owned(), "clang".to_owned());
+        action.env.insert("CFLAGS".to_owned(), "-O2".to_owned());
+        action.env.insert("PREFIX".to_owned(), "/usr".to_owned());
+        let mut entry = Entry::new("Build", "make", None);
+        entry.actions.push(action);
+        let normalized = vec![Normalized::new(&entry)];
+        let data = encode(&key, "build", &[entry.clone()], &normalized).unwrap();
+        let (entries, _) = decode(&key, "build", &data).unwrap();
+        assert_eq!(entries, vec![entry]);
+        assert!(matches!(
+            decode(&key, "other", &data),
+            Err(CacheError::Tampered)
+        ));
+        assert!(matches!(
+            decode(&[8u8; 32], "build", &data),
+            Err(CacheError::Tampered)
+        ));
+        let mut forged = data.clone();
+        let last = forged.len() - 3;
+        forged[last] ^= 1;
+        assert!(decode(&key, "build", &forged).is_err());
+    }
+}
@@ -1,16 +1,27 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
-use std::path::PathBuf;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus, Stdio};
 use std::str::FromStr;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
 use std::{fmt::Display, fs::read_to_string};
 
-use clap::Parser;
-use rmenu_plugin::{Entry, Message};
+use clap::builder::PossibleValuesParser;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use rmenu_core::cfg_replace;
+use rmenu_plugin::{Action, Entry, Message, Method, Options};
 use thiserror::Error;
 
-use crate::config::{cfg_replace, Config, Keybind};
-use crate::{DEFAULT_CONFIG, DEFAULT_THEME, XDG_PREFIX};
+use crate::config::{
+    CacheSetting, Config, Ellipsis, FrontendKind, HttpSource, InstanceMode, KeyMatch, Keybind,
+    MultiExec, Pagination, Performance, PluginConfig, Scope, UpdateConfig,
+};
+use crate::search::Normalized;
+use crate::{DEFAULT_CONFIG, DEFAULT_THEME};
 
 /// Allowed Formats for Entry Ingestion
 #[derive(Debug, Clone)]
@@ -37,11 +48,78 @@ impl FromStr for Format {
     }
 }
 
+/// Additional RMenu Utility Commands
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Create config directory w/ default config, themes and plugins
+    Init {
+        /// Overwrite previously existing files
+        #[arg(long)]
+        force: bool,
+    },
+    /// Generate shell completions including configured plugin names
+    Completions {
+        /// Shell to generate completions for
+        shell: Shell,
+    },
+    /// Generate a man page for rmenu
+    Manpage,
+    /// Send a command to the open menu (set-query, select-index, close, refresh, toggle-top, toggle-sticky, watch-status, watch-events)
+    Control {
+        /// Command followed by its argument (e.g. `set-query firefox`)
+        #[arg(required = true)]
+        command: Vec<String>,
+    },
+    /// Inspect or clean up plugin result caches
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// Run the daemon holding entries pushed into named channels and service plugins
+    Daemon,
+    /// Push entry messages read from stdin into a daemon channel
+    Send {
+        /// Channel to push entries into (shown w/ `--show channel:<name>`)
+        #[arg(long)]
+        channel: String,
+        /// Replace previously pushed entries instead of appending
+        #[arg(long)]
+        replace: bool,
+    },
+    /// Rewrite deprecated config keys to the current schema (w/ a backup)
+    MigrateConfig {
+        /// Only print the changes w/o rewriting the config
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Plugin Cache Inspection Commands
+#[derive(Subcommand, Debug)]
+pub enum CacheCommand {
+    /// Show cache policy, age, size and expiry of a plugin cache
+    Info {
+        /// Configured plugin name
+        plugin: String,
+    },
+    /// Pretty-print the cached entries of a plugin as JSON
+    Dump {
+        /// Configured plugin name
+        plugin: String,
+    },
+    /// Remove expired caches and caches of unconfigured plugins
+    Prune,
+}
+
 /// Dynamic Applicaiton-Menu Tool (Built with Rust)
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
 pub struct Args {
+    /// Utility subcommand to run instead of the menu
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     // simple configuration arguments
     /// Filepath for entry input
     #[arg(short, long)]
@@ -49,40 +127,130 @@ pub struct Args {
     /// Format to accept entries
     #[arg(short, long, default_value_t=Format::Json)]
     format: Format,
-    /// Plugins to run
-    #[arg(short, long)]
+    /// Read dmenu-style lines from stdin and print the selection (or query)
+    #[arg(long)]
+    dmenu: bool,
+    /// Plugins to run (or `channel:<name>` for entries pushed to the daemon)
+    #[arg(short, long, visible_alias = "show")]
     run: Vec<String>,
     /// Override default configuration path
     #[arg(short, long, env = "RMENU_CONFIG")]
     config: Option<PathBuf>,
-    /// Override base css theme styling
+    /// Override directory searched for config, themes and plugins
+    #[arg(long, env = "RMENU_CONFIG_DIR")]
+    pub config_dir: Option<PathBuf>,
+    /// Fail on unknown config keys instead of warning about them
+    #[arg(long, env = "RMENU_STRICT_CONFIG")]
+    strict_config: bool,
+    /// Override base css theme styling (path or theme name)
     #[arg(long, env = "RMENU_THEME")]
     theme: Option<PathBuf>,
     /// Include additional css settings
     #[arg(long, env = "RMENU_CSS")]
     css: Option<PathBuf>,
+    /// Print the effective configuration and exit
+    #[arg(long)]
+    pub show_config: bool,
+    /// Print every configuration key w/ type and default and exit
+    #[arg(long)]
+    pub help_config: bool,
+    /// Print built-in and installed themes and exit
+    #[arg(long)]
+    pub list_themes: bool,
+    /// Print configured plugins w/ their exec and cache status and exit
+    #[arg(long)]
+    pub list_plugins: bool,
+    /// Print the runtime directory holding sockets and FIFOs and exit
+    #[arg(long)]
+    pub runtime_dir: bool,
+    /// Replace an already running instance
+    #[arg(long)]
+    replace: bool,
+    /// Only log errors (unless RUST_LOG is set)
+    #[arg(long)]
+    pub quiet: bool,
+    /// Write diagnostics to stderr as one JSON object per line
+    #[arg(long, env = "RMENU_JSON_LOGS")]
+    pub json_logs: bool,
 
     // root config settings
     /// Override terminal command
     #[arg(long, env = "RMENU_TERMINAL")]
     terminal: Option<String>,
-    /// Number of results to include for each page
+    /// Override command running privileged actions (e.g. `sudo -A {cmd}`)
+    #[arg(long, env = "RMENU_PRIVILEGED")]
+    privileged: Option<String>,
+    /// Override frontend used to display the menu (webview, native, tui)
+    #[arg(long)]
+    frontend: Option<FrontendKind>,
+    /// Render the menu in the terminal (same as `--frontend tui`)
+    #[arg(long)]
+    tui: bool,
+    /// Rendering strategy choosing the page size (smooth, low-latency)
+    #[arg(long)]
+    performance: Option<Performance>,
+    /// Pin number of results to include for each page
     #[arg(long)]
     page_size: Option<usize>,
     /// Control ratio on when to load next page
     #[arg(long)]
     page_load: Option<f64>,
+    /// Strategy loading the next page (auto, manual, scroll)
+    #[arg(long)]
+    pagination: Option<Pagination>,
+    /// Limit number of results rendered per plugin before hiding the rest
+    #[arg(long)]
+    max_entries: Option<usize>,
     /// Force enable/disable comments
     #[arg(long)]
     use_icons: Option<bool>,
     /// Force enable/disable comments
     #[arg(long)]
     use_comments: Option<bool>,
+    /// Force enable/disable tooltips w/ full entry details on hover
+    #[arg(long)]
+    use_tooltips: Option<bool>,
+    /// Override where long names/comments are shortened (end, middle)
+    #[arg(long)]
+    ellipsis: Option<Ellipsis>,
+    /// Shorten entry names longer than the given number of characters
+    #[arg(long)]
+    name_length: Option<usize>,
+    /// Shorten entry comments longer than the given number of characters
+    #[arg(long)]
+    comment_length: Option<usize>,
+    /// Force enable/disable showing comments underneath entry names
+    #[arg(long)]
+    two_line: Option<bool>,
+    /// Override font family (defaults to the desktop ui font)
+    #[arg(long)]
+    font_family: Option<String>,
+    /// Override font size in points
+    #[arg(long)]
+    font_size: Option<f64>,
+    /// Force enable/disable selecting multiple entries
+    #[arg(long)]
+    multi_select: Option<bool>,
+    /// Override how multiple selected entries are executed (sequential, parallel, echo-join)
+    #[arg(long)]
+    multi_exec: Option<MultiExec>,
+    /// Force enable/disable keeping the menu open after executing actions
+    #[arg(long)]
+    keep_open: Option<bool>,
 
     // search settings
+    /// Initial search query
+    #[arg(short, long)]
+    query: Option<String>,
+    /// Use clipboard contents as the initial search query
+    #[arg(long)]
+    query_from_clipboard: bool,
     /// Enforce Regex Pattern on Search
     #[arg(long)]
     search_restrict: Option<String>,
+    /// Block typed characters that violate the search restriction
+    #[arg(long)]
+    search_restrict_input: Option<bool>,
     /// Enforce Minimum Length on Search
     #[arg(long)]
     search_min_length: Option<usize>,
@@ -95,14 +263,41 @@ pub struct Args {
     /// Force enable/disable ignore-case in search
     #[arg(long)]
     ignore_case: Option<bool>,
+    /// Override search matcher (regex, substring, fuzzy, fzf/extended)
+    #[arg(long, visible_alias = "search-mode")]
+    matcher: Option<String>,
+    /// Mask the search and echo it on exec when no entry matches
+    #[arg(long)]
+    password: bool,
+    /// Hide the search and route all keys to navigation and quick-exec
+    #[arg(long)]
+    disable_search: bool,
+    /// Force enable/disable acronym matching in search
+    #[arg(long)]
+    search_acronym: Option<bool>,
+    /// Override ranking weight of acronym matches
+    #[arg(long)]
+    acronym_weight: Option<f64>,
     /// Override placeholder in searchbar
     #[arg(short, long)]
     placeholder: Option<String>,
+    /// Override prompt label shown before the searchbar
+    #[arg(long)]
+    prompt: Option<String>,
+    /// Override prompt icon (path or glyph) shown before the searchbar
+    #[arg(long)]
+    prompt_icon: Option<String>,
 
     // keybinding settings
+    /// Match keybinds by physical key position or produced character (code, key)
+    #[arg(long)]
+    key_match: Option<KeyMatch>,
     /// Override exec keybind
     #[arg(long)]
     key_exec: Option<Vec<Keybind>>,
+    /// Override alternate-action exec keybinds
+    #[arg(long)]
+    key_exec_alt: Option<Vec<Keybind>>,
     /// Override exit keybind
     #[arg(long)]
     key_exit: Option<Vec<Keybind>>,
@@ -124,6 +319,27 @@ pub struct Args {
     /// Override jump-previous keybind
     #[arg(long)]
     key_jump_prev: Option<Vec<Keybind>>,
+    /// Override show-more keybind
+    #[arg(long)]
+    key_show_more: Option<Vec<Keybind>>,
+    /// Override clipboard-paste keybind
+    #[arg(long)]
+    key_paste: Option<Vec<Keybind>>,
+    /// Override toggle-select keybind
+    #[arg(long, alias = "key-mark")]
+    key_toggle_select: Option<Vec<Keybind>>,
+    /// Override jump-to-letter keybind
+    #[arg(long)]
+    key_jump_letter: Option<Vec<Keybind>>,
+    /// Override hide-entry keybind
+    #[arg(long)]
+    key_hide_entry: Option<Vec<Keybind>>,
+    /// Override toggle-always-on-top keybind
+    #[arg(long)]
+    key_toggle_top: Option<Vec<Keybind>>,
+    /// Override toggle-sticky keybind
+    #[arg(long)]
+    key_toggle_sticky: Option<Vec<Keybind>>,
 
     //window settings
     /// Override Window Title
@@ -153,9 +369,16 @@ pub struct Args {
     /// Override Window Always-On-Top
     #[arg(long)]
     always_top: Option<bool>,
+    /// Override Window Visibility on all Workspaces
+    #[arg(long)]
+    sticky: Option<bool>,
     /// Override Fullscreen Settings
     #[arg(long)]
     fullscreen: Option<bool>,
+
+    /// Plugins Loaded in the Background after the Menu Opened
+    #[arg(skip)]
+    deferred: Vec<String>,
 }
 
 #[derive(Error, Debug)]
@@ -174,20 +397,255 @@ pub enum RMenuError {
     CommandError(Option<ExitStatus>),
     #[error("Invalid JSON Entry Object")]
     InvalidJson(#[from] serde_json::Error),
+    #[error("RMenu Already Running")]
+    AlreadyRunning,
+    #[error("RMenu Not Running")]
+    NotRunning,
+    #[error("Plugin Cache Error")]
+    CacheError(#[from] crate::cache::CacheError),
+    #[error("Frontend Not Available")]
+    FrontendUnavailable(String),
+    #[error("Frontend Runtime Exception")]
+    FrontendError(String),
+    #[error("Unknown Config Keys: {0:?}")]
+    UnknownKeys(Vec<String>),
 }
 
 pub type Result<T> = std::result::Result<T, RMenuError>;
 
+/// Render an Ignored Key Path as a Dotted Config Key
+fn dotted(path: &serde_ignored::Path) -> String {
+    use serde_ignored::Path;
+    let (parent, key) = match path {
+        Path::Root => return String::new(),
+        Path::Seq { parent, index } => (parent, index.to_string()),
+        Path::Map { parent, key } => (parent, key.to_owned()),
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => return dotted(parent),
+    };
+    match dotted(parent) {
+        parent if parent.is_empty() => key,
+        parent => format!("{parent}.{key}"),
+    }
+}
+
+/// Parse Configuration Content and Report Unknown or Deprecated Keys
+///
+/// Strict parsing fails when any key would be ignored, so typos
+/// never silently fall back to the defaults.
+pub fn parse_config(content: &str, strict: bool) -> Result<Config> {
+    let mut unknown = vec![];
+    let de = serde_yaml::Deserializer::from_str(content);
+    let config: Config = serde_ignored::deserialize(de, |path| unknown.push(dotted(&path)))?;
+    for key in unknown.iter() {
+        match crate::migrate::renamed(key) {
+            Some(new) => {
+                log::warn!(
+                    "deprecated config key {key:?} (now {new:?}), run `rmenu migrate-config`"
+                )
+            }
+            None => log::warn!("unknown config key {key:?} is ignored"),
+        }
+    }
+    if strict && !unknown.is_empty() {
+        return Err(RMenuError::UnknownKeys(unknown));
+    }
+    Ok(config)
+}
+
+/// Format Seconds as a Short Human-Readable Age (e.g. `5m`)
+fn format_age(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{s}s"),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86400),
+    }
+}
+
+/// Attribute Entries to the Plugin that Produced them
+fn set_source(name: &str, entries: &mut [Entry]) {
+    for entry in entries.iter_mut() {
+        entry.source = Some(name.to_owned());
+    }
+}
+
+/// Append the Entries Defined Directly in the Plugin Configuration
+///
+/// These are added after caching, so config edits apply immediately.
+fn add_static_entries(
+    name: &str,
+    plugin: &PluginConfig,
+    entries: &mut Vec<Entry>,
+    norm: &mut Vec<Normalized>,
+) {
+    let start = entries.len();
+    entries.extend(plugin.static_entries.iter().map(|e| e.entry()));
+    set_source(name, &mut entries[start..]);
+    norm.extend(entries[start..].iter().map(Normalized::new));
+}
+
+/// Merge Options of a Plugin w/ Lower Precedence and Log the Fields it Set
+fn merge_options(name: &str, options: &mut Options, plugin: &Options) {
+    for field in options.merge(plugin) {
+        log::info!("option {field:?} set by plugin {name:?}");
+    }
+}
+
+/// Add Actions Refreshing or Disabling the Source to its Entries
+///
+/// These are added after caching, so they never end up in cache files.
+fn add_source_actions(name: &str, entries: &mut [Entry]) {
+    let refresh = Action {
+        name: format!("Refresh {name}"),
+        exec: Method::RefreshSource(name.to_owned()),
+        comment: Some("Re-run this source w/o its cache".to_owned()),
+        icon: None,
+        close: true,
+        env: Default::default(),
+        cwd: None,
+    };
+    let disable = Action {
+        name: format!("Disable {name}"),
+        exec: Method::DisableSource(name.to_owned()),
+        comment: Some("Hide this source until the menu closes".to_owned()),
+        icon: None,
+        close: true,
+        env: Default::default(),
+        cwd: None,
+    };
+    for entry in entries.iter_mut() {
+        entry.actions.extend([refresh.clone(), disable.clone()]);
+    }
+}
+
+/// Parse a Single Line of Entry Input in the Given Format
+fn parse_message(format: &Format, line: &str) -> Result<Message> {
+    Ok(match format {
+        Format::DMenu => Message::Entry(Entry::echo(line.trim(), None)),
+        Format::Json => serde_json::from_str(line)?,
+    })
+}
+
+/// Collect an Entry or Merge Options Overriding the Ones Read before
+fn add_message(msg: Message, entries: &mut Vec<Entry>, options: &mut Options) {
+    match msg {
+        Message::Entry(entry) => entries.push(entry),
+        Message::Options(mut opts) => {
+            opts.merge(options);
+            *options = opts;
+        }
+    }
+}
+
+/// Scheme of Plugin Executables Reading Messages from a Named Pipe
+static FIFO_SCHEME: &'static str = "fifo://";
+
+/// Retrieve the Path of the Named Pipe a Plugin Reads from (if any)
+fn fifo_path(exec: &[String]) -> Option<String> {
+    let args = plugin_args(exec);
+    let main = args.get(0)?;
+    main.strip_prefix(FIFO_SCHEME).map(|path| path.to_owned())
+}
+
+/// Open the Named Pipe w/o Blocking until a Writer Connects
+///
+/// Returns nothing when no writer connected within the given time.
+fn open_fifo(path: &str, wait: Duration) -> Result<Option<File>> {
+    let file = OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)?;
+    let fd = file.as_raw_fd();
+    let mut poll = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let timeout = wait.as_millis().min(i32::MAX as u128) as i32;
+    match unsafe { libc::poll(&mut poll, 1, timeout) } {
+        0 => return Ok(None),
+        n if n < 0 => return Err(std::io::Error::last_os_error().into()),
+        _ => {}
+    }
+    // block on reads again until the writer closes the pipe
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_NONBLOCK) } < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(Some(file))
+}
+
+/// Read a Named Pipe in the Background, Handing Messages to the Menu
+///
+/// Entries written once the menu opened w/o them replace the shown ones
+/// in batches when the plugin runs alone, and are dropped otherwise.
+fn read_fifo(
+    name: String,
+    plugin: PluginConfig,
+    reader: BufReader<File>,
+    format: Format,
+    live: bool,
+    updates: UpdateConfig,
+    send: mpsc::Sender<Message>,
+) {
+    let mut entries = vec![];
+    let mut detached = false;
+    let mut batcher = crate::stream::Batcher::new(&updates);
+    let publish = |entries: &[Entry]| {
+        let mut entries = entries.to_vec();
+        set_source(&name, &mut entries);
+        let mut norm: Vec<Normalized> = entries.iter().map(Normalized::new).collect();
+        add_static_entries(&name, &plugin, &mut entries, &mut norm);
+        crate::blacklist::filter(&name, &plugin.blacklist, &mut entries, &mut norm);
+        crate::instance::queue(crate::instance::Control::Streamed(entries, norm));
+    };
+    for line in reader.lines().map_while(|l| l.ok()) {
+        let msg = match parse_message(&format, &line) {
+            Ok(msg) => msg,
+            Err(err) => {
+                log::warn!("invalid message in fifo of {name:?}: {err:?}");
+                continue;
+            }
+        };
+        if let Message::Entry(entry) = &msg {
+            entries.push(entry.clone());
+        }
+        if !detached && send.send(msg).is_err() {
+            if !live {
+                log::warn!("fifo of {name:?} still open, dropping later entries");
+                return;
+            }
+            detached = true;
+        }
+        if detached && batcher.ready(entries.len()) {
+            publish(&entries);
+        }
+    }
+    if detached {
+        publish(&entries);
+    }
+}
+
+/// Expand Plugin Command w/ the Executable Resolved in the Config Directory
+pub fn plugin_args(exec: &[String]) -> Vec<String> {
+    exec.iter()
+        .enumerate()
+        .map(|(n, s)| match n {
+            0 if s.starts_with(FIFO_SCHEME) => shellexpand::tilde(s).to_string(),
+            0 => crate::paths::expand_exec(s),
+            _ => shellexpand::tilde(s).to_string(),
+        })
+        .collect()
+}
+
 impl Args {
     /// Find a specifically named file across xdg config paths
     fn find_xdg_file(&self, name: &str, base: &Option<PathBuf>) -> Option<String> {
         return base
             .clone()
-            .or_else(|| {
-                xdg::BaseDirectories::with_prefix(XDG_PREFIX)
-                    .expect("Failed to read xdg base dirs")
-                    .find_config_file(name)
-            })
+            .or_else(|| crate::paths::find_config_file(name))
             .map(|f| {
                 let f = f.to_string_lossy().to_string();
                 shellexpand::tilde(&f).to_string()
@@ -198,37 +656,106 @@ impl Args {
     pub fn get_config(&self) -> Result<Config> {
         let config = self.find_xdg_file(DEFAULT_CONFIG, &self.config);
 
-        if let Some(path) = config {
-            let config: Config = match read_to_string(path) {
-                Ok(content) => serde_yaml::from_str(&content),
+        let mut config = match config {
+            Some(path) => match read_to_string(path) {
+                Ok(content) => parse_config(&content, self.strict_config)?,
                 Err(err) => {
                     log::error!("Failed to Load Config: {err:?}");
-                    Ok(Config::default())
+                    Config::default()
                 }
-            }?;
-            return Ok(config);
+            },
+            None => {
+                log::error!("Failed to Load Config: no file found in xdg config paths");
+                Config::default()
+            }
+        };
+        // plugins in the central config take precedence over manifests
+        for (name, plugin) in crate::manifest::discover() {
+            config.plugins.entry(name).or_insert(plugin);
+        }
+        Ok(config)
+    }
+
+    /// Rewrite Deprecated Keys of the Configuration File
+    pub fn migrate_config(&self, dry_run: bool) -> Result<()> {
+        let path = self
+            .find_xdg_file(DEFAULT_CONFIG, &self.config)
+            .ok_or_else(|| {
+                std::io::Error::new(ErrorKind::NotFound, "no config file in xdg config paths")
+            })?;
+        crate::migrate::run(Path::new(&path), dry_run)
+    }
+
+    /// Determine Single-Instance Behavior from CLI and Config
+    pub fn instance_mode(&self, config: &Config) -> InstanceMode {
+        match self.replace {
+            true => InstanceMode::Replace,
+            false => config.instance.clone(),
         }
-        log::error!("Failed to Load Config: no file found in xdg config paths");
-        Ok(Config::default())
     }
 
     /// Update Configuration w/ CLI Specified Settings
     pub fn update_config(&self, mut config: Config) -> Config {
         // override basic settings
         config.terminal = self.terminal.clone().or_else(|| config.terminal);
-        config.page_size = self.page_size.unwrap_or(config.page_size);
-        config.page_load = self.page_load.unwrap_or(config.page_load);
+        cfg_replace!(config.privileged, self.privileged);
+        cfg_replace!(config.frontend, self.frontend, true);
+        if self.tui {
+            config.frontend = FrontendKind::Tui;
+        }
+        cfg_replace!(config.performance, self.performance, true);
+        cfg_replace!(config.pagination, self.pagination, true);
+        cfg_replace!(config.page_size, self.page_size);
+        cfg_replace!(config.page_load, self.page_load);
+        cfg_replace!(config.max_entries, self.max_entries);
         config.use_icons = self.use_icons.unwrap_or(config.use_icons);
         config.use_comments = self.use_icons.unwrap_or(config.use_comments);
+        cfg_replace!(config.use_tooltips, self.use_tooltips, true);
+        // override display settings
+        cfg_replace!(config.display.ellipsis, self.ellipsis, true);
+        cfg_replace!(config.display.name_length, self.name_length);
+        cfg_replace!(config.display.comment_length, self.comment_length);
+        cfg_replace!(config.display.two_line, self.two_line, true);
+        // override appearance settings
+        cfg_replace!(config.appearance.font_family, self.font_family);
+        cfg_replace!(config.appearance.font_size, self.font_size);
+        cfg_replace!(config.multi_select, self.multi_select, true);
+        cfg_replace!(config.multi_exec, self.multi_exec, true);
+        cfg_replace!(config.keep_open, self.keep_open, true);
         // override search settings
+        cfg_replace!(config.search.query, self.query);
+        if self.query_from_clipboard {
+            cfg_replace!(config.search.query, crate::clipboard::read());
+        }
         cfg_replace!(config.search.restrict, self.search_restrict);
+        cfg_replace!(
+            config.search.restrict_input,
+            self.search_restrict_input,
+            true
+        );
         cfg_replace!(config.search.min_length, self.search_min_length);
         cfg_replace!(config.search.max_length, self.search_max_length);
         cfg_replace!(config.search.use_regex, self.search_regex, true);
+        cfg_replace!(config.search.matcher, self.matcher);
+        cfg_replace!(config.search.use_acronym, self.search_acronym, true);
+        cfg_replace!(config.search.acronym_weight, self.acronym_weight, true);
         cfg_replace!(config.search.ignore_case, self.ignore_case, true);
         cfg_replace!(config.search.placeholder, self.placeholder);
+        cfg_replace!(config.search.prompt, self.prompt);
+        cfg_replace!(config.search.prompt_icon, self.prompt_icon);
+        if self.password {
+            config.search.password = true;
+        }
+        if self.dmenu {
+            config.search.echo_query = true;
+        }
+        if self.disable_search {
+            config.search.disable = true;
+        }
         // override keybind settings
+        cfg_replace!(config.keybinds.match_by, self.key_match, true);
         cfg_replace!(config.keybinds.exec, self.key_exec, true);
+        cfg_replace!(config.keybinds.exec_alt, self.key_exec_alt, true);
         cfg_replace!(config.keybinds.exit, self.key_exit, true);
         cfg_replace!(config.keybinds.move_next, self.key_move_next, true);
         cfg_replace!(config.keybinds.move_prev, self.key_move_prev, true);
@@ -236,6 +763,13 @@ impl Args {
         cfg_replace!(config.keybinds.close_menu, self.key_close_menu, true);
         cfg_replace!(config.keybinds.jump_next, self.key_jump_next, true);
         cfg_replace!(config.keybinds.jump_prev, self.key_jump_prev, true);
+        cfg_replace!(config.keybinds.show_more, self.key_show_more, true);
+        cfg_replace!(config.keybinds.paste, self.key_paste, true);
+        cfg_replace!(config.keybinds.toggle_select, self.key_toggle_select, true);
+        cfg_replace!(config.keybinds.jump_letter, self.key_jump_letter, true);
+        cfg_replace!(config.keybinds.hide_entry, self.key_hide_entry, true);
+        cfg_replace!(config.keybinds.toggle_top, self.key_toggle_top, true);
+        cfg_replace!(config.keybinds.toggle_sticky, self.key_toggle_sticky, true);
         // override window settings
         cfg_replace!(config.window.title, self.title, true);
         cfg_replace!(config.window.size.width, self.width, true);
@@ -246,65 +780,192 @@ impl Args {
         cfg_replace!(config.window.decorate, self.decorate, true);
         cfg_replace!(config.window.transparent, self.transparent, true);
         cfg_replace!(config.window.always_top, self.always_top, true);
+        cfg_replace!(config.window.sticky, self.sticky, true);
         cfg_replace!(config.window.fullscreen, self.fullscreen);
+        if self.replace {
+            config.instance = InstanceMode::Replace;
+        }
         config
     }
 
-    /// Load CSS Theme or Default
-    pub fn get_theme(&self) -> String {
-        self.find_xdg_file(DEFAULT_THEME, &self.theme)
-            .map(read_to_string)
+    /// Load CSS Theme (CLI, then Config/Plugin Theme) or Default
+    ///
+    /// Themes are given as a path or by name, where theme files in the
+    /// config directory take precedence over built-in themes.
+    pub fn get_theme(&self, c: &Config) -> String {
+        let theme = self
+            .theme
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string())
+            .or_else(|| c.theme.clone());
+        let path = match theme.as_deref() {
+            Some(theme) if theme.contains('/') || theme.ends_with(".css") => {
+                Some(shellexpand::tilde(theme).to_string())
+            }
+            Some(theme) => {
+                let found = self.find_xdg_file(&format!("themes/{theme}.css"), &None);
+                if found.is_none() {
+                    if let Some(content) = crate::themes::builtin(theme) {
+                        return content.to_owned();
+                    }
+                    log::warn!("No such theme {theme:?}, using built-in stylesheet");
+                }
+                found
+            }
+            None => self.find_xdg_file(DEFAULT_THEME, &None),
+        };
+        path.map(read_to_string)
             .map(|f| {
                 f.unwrap_or_else(|err| {
-                    log::error!("Failed to load CSS: {err:?}");
+                    log::warn!("Failed to load CSS, using built-in stylesheet: {err:?}");
                     String::new()
                 })
             })
             .unwrap_or_else(String::new)
     }
 
-    /// Load Additional CSS or Default
+    /// Describe Configured Plugins w/ Executable and Cache Status
+    pub fn plugin_report(&self) -> Result<String> {
+        let config = self.get_config()?;
+        let mut out = String::new();
+        for (name, plugin) in config.plugins.iter() {
+            let exec = plugin_args(&plugin.exec);
+            let found = match exec.get(0) {
+                Some(main) if main.starts_with(FIFO_SCHEME) => {
+                    let path = main.trim_start_matches(FIFO_SCHEME);
+                    std::fs::metadata(path)
+                        .map(|m| m.file_type().is_fifo())
+                        .unwrap_or(false)
+                }
+                Some(main) if main.contains('/') => PathBuf::from(main).is_file(),
+                Some(main) => which::which(main).is_ok(),
+                None => false,
+            };
+            let found = if found { "found" } else { "missing" };
+            let status = match crate::cache::cache_status(name, plugin) {
+                Some(status) => format!(
+                    "{}, {} old, {:.1} KiB",
+                    if status.fresh { "fresh" } else { "expired" },
+                    format_age(status.age.as_secs()),
+                    status.size as f64 / 1024.0
+                ),
+                None => "no cache file".to_owned(),
+            };
+            let cache = serde_yaml::to_string(&plugin.cache).unwrap_or_default();
+            out.push_str(&format!("{name}\n"));
+            match plugin.http.as_ref() {
+                Some(http) => out.push_str(&format!("  url:   {}\n", http.url)),
+                None => out.push_str(&format!("  exec:  {} ({found})\n", exec.join(" "))),
+            }
+            out.push_str(&format!("  cache: {} ({status})\n", cache.trim()));
+            if plugin.service {
+                let state = match crate::daemon::service_status(name) {
+                    Ok(Some(status)) => status.to_string(),
+                    Ok(None) => "not supervised by the daemon".to_owned(),
+                    Err(_) => "daemon not running".to_owned(),
+                };
+                out.push_str(&format!("  state: {state}\n"));
+            }
+        }
+        Ok(out)
+    }
+
+    /// Run Plugin Cache Inspection Commands
+    pub fn cache(&self, command: &CacheCommand) -> Result<()> {
+        let config = self.get_config()?;
+        match command {
+            CacheCommand::Info { plugin: name } => {
+                let plugin = config
+                    .plugins
+                    .get(name)
+                    .ok_or_else(|| RMenuError::NoSuchPlugin(name.to_owned()))?;
+                let policy = serde_yaml::to_string(&plugin.cache).unwrap_or_default();
+                println!("plugin:  {name}");
+                println!("policy:  {}", policy.trim());
+                let Some(status) = crate::cache::cache_status(name, plugin) else {
+                    println!("status:  no cache file");
+                    return Ok(());
+                };
+                let expiry = match (&plugin.cache, status.fresh) {
+                    (CacheSetting::NoCache, _) => "caching disabled".to_owned(),
+                    (_, false) => "expired".to_owned(),
+                    (CacheSetting::Never, _) => "fresh, never expires".to_owned(),
+                    (CacheSetting::OnLogin, _) => "fresh, expires on next login".to_owned(),
+                    (CacheSetting::AfterSeconds(secs), _) => {
+                        let left = (*secs as u64).saturating_sub(status.age.as_secs());
+                        format!("fresh, expires in {}", format_age(left))
+                    }
+                };
+                println!("file:    {}", status.path.display());
+                println!("age:     {}", format_age(status.age.as_secs()));
+                println!("size:    {:.1} KiB", status.size as f64 / 1024.0);
+                println!("status:  {expiry}");
+            }
+            CacheCommand::Dump { plugin } => println!("{}", crate::cache::dump_cache(plugin)?),
+            CacheCommand::Prune => {
+                for name in crate::cache::prune_caches(&config.plugins)? {
+                    println!("removed {name}");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// List Built-In Themes and Theme Files in the Config Directory
+    pub fn themes(&self) -> String {
+        let mut out = String::new();
+        for name in crate::themes::names() {
+            out.push_str(&format!("{name:16} built-in\n"));
+        }
+        for path in crate::paths::list_config_files("themes") {
+            if path.extension().map(|e| e != "css").unwrap_or(true) {
+                continue;
+            }
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            out.push_str(&format!("{name:16} {}\n", path.display()));
+        }
+        out
+    }
+
+    /// Load Additional CSS and Plugin Style Snippets or Default
     pub fn get_css(&self, c: &Config) -> String {
         let css = self
             .css
             .clone()
             .map(|s| s.to_string_lossy().to_string())
             .or(c.css.clone());
+        let mut content = String::new();
         if let Some(path) = css {
             let path = shellexpand::tilde(&path).to_string();
             match read_to_string(&path) {
-                Ok(css) => return css,
-                Err(err) => log::error!("Failed to load Theme: {err:?}"),
+                Ok(css) => content = css,
+                Err(err) => log::warn!("Failed to load CSS, using built-in stylesheet: {err:?}"),
             }
         }
-        String::new()
+        if let Some(style) = c.style.as_ref() {
+            content.push('\n');
+            content.push_str(style);
+        }
+        content
     }
 
+    /// Read Entries and Option Messages from the Specified Reader
+    ///
+    /// Later option messages of the same source override earlier ones.
     fn read_entries<T: Read>(
         &mut self,
         r: BufReader<T>,
         v: &mut Vec<Entry>,
-        c: &mut Config,
+        o: &mut Options,
     ) -> Result<()> {
         for line in r.lines().filter_map(|l| l.ok()) {
-            match &self.format {
-                Format::DMenu => v.push(Entry::echo(line.trim(), None)),
-                Format::Json => {
-                    let msg: Message = serde_json::from_str(&line)?;
-                    match msg {
-                        Message::Entry(entry) => v.push(entry),
-                        Message::Options(options) => c
-                            .update(&options)
-                            .map_err(|s| RMenuError::InvalidKeybind(s))?,
-                    }
-                }
-            }
+            add_message(parse_message(&self.format, &line)?, v, o);
         }
         Ok(())
     }
 
     /// Read Entries from a Configured Input
-    fn load_input(&mut self, input: &str, config: &mut Config) -> Result<Vec<Entry>> {
+    fn load_input(&mut self, input: &str, options: &mut Options) -> Result<Vec<Entry>> {
         // retrieve input file
         let input = if input == "-" { "/dev/stdin" } else { input };
         let fpath = shellexpand::tilde(input).to_string();
@@ -313,14 +974,145 @@ impl Args {
         let file = File::open(fpath)?;
         let reader = BufReader::new(file);
         let mut entries = vec![];
-        self.read_entries(reader, &mut entries, config)?;
+        self.read_entries(reader, &mut entries, options)?;
+        Ok(entries)
+    }
+
+    /// Read Entries from a Plugin Command
+    fn load_exec(
+        &mut self,
+        name: &str,
+        exec: &[String],
+        options: &mut Options,
+    ) -> Result<Vec<Entry>> {
+        // build command arguments
+        let args = plugin_args(exec);
+        let main = args
+            .get(0)
+            .ok_or_else(|| RMenuError::InvalidPlugin(name.to_owned()))?;
+        let mut entries = vec![];
+        // spawn command
+        let mut command = Command::new(main)
+            .args(&args[1..])
+            .stdout(Stdio::piped())
+            .spawn()?;
+        crate::signals::track_child(command.id());
+        let stdout = command
+            .stdout
+            .as_mut()
+            .ok_or_else(|| RMenuError::CommandError(None))?;
+        // parse and read entries into vector of results
+        let reader = BufReader::new(stdout);
+        self.read_entries(reader, &mut entries, options)?;
+        let status = command.wait()?;
+        crate::signals::untrack_child(command.id());
+        if !status.success() {
+            return Err(RMenuError::CommandError(Some(status)));
+        }
+        Ok(entries)
+    }
+
+    /// Read Entries from the Named Pipe of a Plugin
+    ///
+    /// The menu waits up to `updates.startup` for a writer to connect and
+    /// close the pipe, and opens w/ the messages written so far otherwise.
+    /// Plugins run alone keep streaming their later entries into the menu.
+    fn load_fifo(
+        &mut self,
+        name: &str,
+        plugin: &PluginConfig,
+        path: &str,
+        live: bool,
+        updates: &UpdateConfig,
+        options: &mut Options,
+    ) -> Result<Vec<Entry>> {
+        log::info!("reading fifo: {path:?}");
+        let deadline = Instant::now() + updates.startup();
+        let mut entries = vec![];
+        let Some(file) = open_fifo(path, updates.startup())? else {
+            log::warn!("no writer connected to fifo {path:?}");
+            return Ok(entries);
+        };
+        let (send, messages) = mpsc::channel();
+        let (name, plugin) = (name.to_owned(), plugin.clone());
+        let (format, updates) = (self.format.clone(), updates.clone());
+        let reader = BufReader::new(file);
+        std::thread::spawn(move || read_fifo(name, plugin, reader, format, live, updates, send));
+        loop {
+            let wait = deadline.saturating_duration_since(Instant::now());
+            match messages.recv_timeout(wait) {
+                Ok(msg) => add_message(msg, &mut entries, options),
+                Err(RecvTimeoutError::Disconnected) => break,
+                Err(RecvTimeoutError::Timeout) => {
+                    log::info!("fifo {path:?} still open, opening w/ the entries read so far");
+                    break;
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Read Entries from an HTTP(S) Endpoint Returning Plugin Messages
+    fn load_http(&mut self, http: &HttpSource, options: &mut Options) -> Result<Vec<Entry>> {
+        log::info!("fetching: {:?}", http.url);
+        let body = crate::http::get(http)?;
+        let mut entries = vec![];
+        self.read_entries(BufReader::new(body.as_slice()), &mut entries, options)?;
         Ok(entries)
     }
 
     /// Read Entries from a Plugin Source
-    fn load_plugins(&mut self, config: &mut Config) -> Result<Vec<Entry>> {
+    ///
+    /// Plugins w/ a search prefix are scoped to their entries, which start
+    /// at the given offset in the final list of entries.
+    ///
+    /// Plugin options are merged per field, where plugins listed earlier
+    /// take precedence over the ones run after them.
+    fn load_plugins(
+        &mut self,
+        config: &mut Config,
+        options: &mut Options,
+        names: Vec<String>,
+        offset: usize,
+    ) -> Result<(Vec<Entry>, Vec<Normalized>)> {
         let mut entries = vec![];
-        for name in self.run.clone().into_iter() {
+        let mut normalized = vec![];
+        let combined = self.run.len() + self.input.is_some() as usize > 1;
+        for name in names.into_iter() {
+            let start = offset + entries.len();
+            // read entries pushed to a daemon channel (or published by a service)
+            let plugin = config.plugins.get(&name);
+            let prefix = plugin.and_then(|p| p.prefix.clone());
+            let service = plugin.filter(|p| p.service).map(|_| name.as_str());
+            let channel = name.strip_prefix(crate::daemon::CHANNEL_PREFIX).or(service);
+            if let Some(channel) = channel {
+                log::info!("reading daemon channel: {channel:?}");
+                let mut entry = vec![];
+                let mut channel_options = Options::default();
+                for msg in crate::daemon::fetch(channel)? {
+                    match msg {
+                        Message::Entry(e) => entry.push(e),
+                        Message::Options(mut opts) => {
+                            opts.merge(&channel_options);
+                            channel_options = opts;
+                        }
+                    }
+                }
+                set_source(&name, &mut entry);
+                merge_options(&name, options, &channel_options);
+                let norm: Vec<Normalized> = entry.iter().map(Normalized::new).collect();
+                if combined {
+                    add_source_actions(&name, &mut entry);
+                }
+                entries.append(&mut entry);
+                normalized.extend(norm);
+                config.search.scopes.push(Scope {
+                    name: name.clone(),
+                    prefix,
+                    entries: start..offset + entries.len(),
+                });
+                continue;
+            }
             // retrieve plugin configuration
             log::info!("running plugin: {name:?}");
             let plugin = config
@@ -328,73 +1120,323 @@ impl Args {
                 .get(&name)
                 .cloned()
                 .ok_or_else(|| RMenuError::NoSuchPlugin(name.to_owned()))?;
-            // update config w/ plugin options when available
-            if let Some(options) = plugin.options.as_ref() {
-                config
-                    .update(options)
-                    .map_err(|e| RMenuError::InvalidKeybind(e))?;
+            // collect configured plugin options, overridden by its own messages
+            let mut plugin_options = plugin.options.clone().unwrap_or_default();
+            // plugins made only of static entries have nothing to run
+            let sourceless = plugin.exec.is_empty() && plugin.http.is_none();
+            if sourceless && !plugin.static_entries.is_empty() {
+                if config.search.placeholder.is_none() {
+                    config.search.placeholder = plugin.placeholder.clone();
+                }
+                let mut entry = vec![];
+                let mut norm = vec![];
+                add_static_entries(&name, &plugin, &mut entry, &mut norm);
+                merge_options(&name, options, &plugin_options);
+                crate::blacklist::filter(&name, &plugin.blacklist, &mut entry, &mut norm);
+                if combined {
+                    add_source_actions(&name, &mut entry);
+                }
+                entries.append(&mut entry);
+                normalized.append(&mut norm);
+                config.search.scopes.push(Scope {
+                    name: name.clone(),
+                    prefix: plugin.prefix.clone(),
+                    entries: start..offset + entries.len(),
+                });
+                continue;
+            }
+            // streaming plugins answer every search instead of being cached
+            if plugin.stream {
+                if combined {
+                    log::warn!("streaming plugin {name:?} only answers searches when run alone");
+                }
+                let mut entry = vec![];
+                for msg in crate::stream::open(&name, &plugin, !combined, &config.updates)? {
+                    match msg {
+                        Message::Entry(e) => entry.push(e),
+                        Message::Options(mut opts) => {
+                            opts.merge(&plugin_options);
+                            plugin_options = opts;
+                        }
+                    }
+                }
+                merge_options(&name, options, &plugin_options);
+                if !combined {
+                    config.search.matcher = Some("passthrough".to_owned());
+                }
+                if config.search.placeholder.is_none() {
+                    config.search.placeholder = plugin.placeholder.clone();
+                }
+                set_source(&name, &mut entry);
+                let mut norm: Vec<Normalized> = entry.iter().map(Normalized::new).collect();
+                if combined {
+                    add_source_actions(&name, &mut entry);
+                }
+                entries.append(&mut entry);
+                normalized.append(&mut norm);
+                config.search.scopes.push(Scope {
+                    name: name.clone(),
+                    prefix: plugin.prefix.clone(),
+                    entries: start..offset + entries.len(),
+                });
+                continue;
             }
             // read cache when available
             match crate::cache::read_cache(&name, &plugin) {
                 Err(err) => log::error!("cache read failed: {err:?}"),
-                Ok(cached) => {
+                Ok((mut cached, mut norm)) => {
+                    set_source(&name, &mut cached);
+                    add_static_entries(&name, &plugin, &mut cached, &mut norm);
+                    merge_options(&name, options, &plugin_options);
+                    crate::blacklist::filter(&name, &plugin.blacklist, &mut cached, &mut norm);
+                    if combined {
+                        add_source_actions(&name, &mut cached);
+                    }
                     entries.extend(cached);
+                    normalized.extend(norm);
+                    config.search.scopes.push(Scope {
+                        name: name.clone(),
+                        prefix: plugin.prefix.clone(),
+                        entries: start..offset + entries.len(),
+                    });
                     continue;
                 }
             }
-            // build command arguments
-            let args: Vec<String> = plugin
-                .exec
-                .iter()
-                .map(|s| shellexpand::tilde(s).to_string())
-                .collect();
-            let main = args
-                .get(0)
-                .ok_or_else(|| RMenuError::InvalidPlugin(name.to_owned()))?;
-            // spawn command
-            let mut command = Command::new(main)
-                .args(&args[1..])
-                .stdout(Stdio::piped())
-                .spawn()?;
-            let stdout = command
-                .stdout
-                .as_mut()
-                .ok_or_else(|| RMenuError::CommandError(None))?;
-            // parse and read entries into vector of results
-            let reader = BufReader::new(stdout);
-            let mut entry = vec![];
-            self.read_entries(reader, &mut entry, config)?;
-            let status = command.wait()?;
-            if !status.success() {
-                return Err(RMenuError::CommandError(Some(status)));
-            }
+            let mut entry = match plugin.http.as_ref() {
+                Some(http) => self.load_http(http, &mut plugin_options)?,
+                None => match fifo_path(&plugin.exec) {
+                    Some(path) => {
+                        let (live, updates) = (!combined, &config.updates);
+                        self.load_fifo(&name, &plugin, &path, live, updates, &mut plugin_options)?
+                    }
+                    None => self.load_exec(&name, &plugin.exec, &mut plugin_options)?,
+                },
+            };
+            merge_options(&name, options, &plugin_options);
             // finalize settings and save to cache
             if config.search.placeholder.is_none() {
                 config.search.placeholder = plugin.placeholder.clone();
             }
-            match crate::cache::write_cache(&name, &plugin, &entry) {
+            set_source(&name, &mut entry);
+            let mut norm: Vec<Normalized> = entry.iter().map(Normalized::new).collect();
+            match crate::cache::write_cache(&name, &plugin, &entry, &norm) {
                 Ok(_) => {}
                 Err(err) => log::error!("cache write error: {err:?}"),
             }
+            add_static_entries(&name, &plugin, &mut entry, &mut norm);
+            crate::blacklist::filter(&name, &plugin.blacklist, &mut entry, &mut norm);
+            if combined {
+                add_source_actions(&name, &mut entry);
+            }
+            if let Err(err) = crate::cache::evict_caches(config.cache_max_bytes) {
+                log::error!("cache eviction error: {err:?}");
+            }
             // write collected entries to main output
             entries.append(&mut entry);
+            normalized.append(&mut norm);
+            config.search.scopes.push(Scope {
+                name: name.clone(),
+                prefix: plugin.prefix.clone(),
+                entries: start..offset + entries.len(),
+            });
         }
-        Ok(entries)
+        Ok((entries, normalized))
+    }
+
+    /// Write Shell Completions w/ Configured Plugin Names to Stdout
+    pub fn completions(&self, shell: Shell) -> Result<()> {
+        let config = self.get_config()?;
+        let names: Vec<String> = config.plugins.keys().cloned().collect();
+        let matchers = crate::search::matchers();
+        let mut command = Args::command()
+            .mut_arg("run", |arg| {
+                arg.value_parser(PossibleValuesParser::new(names))
+            })
+            .mut_arg("matcher", |arg| {
+                arg.value_parser(PossibleValuesParser::new(matchers))
+            });
+        clap_complete::generate(shell, &mut command, "rmenu", &mut std::io::stdout());
+        Ok(())
+    }
+
+    /// Write Roff Formatted Man Page to Stdout
+    pub fn manpage(&self) -> Result<()> {
+        let man = clap_mangen::Man::new(Args::command());
+        man.render(&mut std::io::stdout())?;
+        Ok(())
+    }
+
+    /// Retrieve Names of Plugins Specified to Run
+    #[inline]
+    pub fn plugins(&self) -> &[String] {
+        &self.run
     }
 
-    /// Load Entries from Enabled/Configured Entry-Sources
-    pub fn get_entries(&mut self, config: &mut Config) -> Result<Vec<Entry>> {
+    /// Check if any Entry-Sources were Explicitly Specified
+    #[inline]
+    pub fn has_sources(&self) -> bool {
+        self.input.is_some() || !self.run.is_empty()
+    }
+
+    /// Check if Entries can be Reloaded (Stdin and FIFOs can only be Read Once)
+    pub fn can_reload(&self) -> bool {
+        match self.input.as_deref() {
+            Some("-") => false,
+            Some(input) => std::fs::metadata(shellexpand::tilde(input).as_ref())
+                .map(|m| !m.file_type().is_fifo())
+                .unwrap_or(true),
+            None => !self.dmenu && !self.run.is_empty(),
+        }
+    }
+
+    /// Reload Entries from Entry-Sources w/ Plugin Caches Bypassed
+    ///
+    /// Only the cache of the given source is bypassed when specified.
+    /// The scopes of the reloaded plugins are returned w/ their entries.
+    pub fn reload_entries(
+        &mut self,
+        source: Option<&str>,
+    ) -> Result<(Vec<Entry>, Vec<Normalized>, Vec<Scope>)> {
+        let mut config = self.get_config()?;
+        for (name, plugin) in config.plugins.iter_mut() {
+            if source.map(|s| s == name).unwrap_or(true) {
+                plugin.cache = CacheSetting::NoCache;
+            }
+        }
+        self.deferred.clear();
+        let (entries, normalized) = self.get_entries(&mut config)?;
+        Ok((entries, normalized, config.search.scopes))
+    }
+
+    /// Defer all but the Historically Most-Used of the Combined Plugins
+    ///
+    /// The remaining plugins are loaded after the menu opened, and every
+    /// plugin is listed in the order of its usage. Plugins w/ a search
+    /// prefix or entries read from an input need the final entry offsets,
+    /// so they are always loaded up front.
+    pub fn prioritize(&mut self, config: &Config) {
+        if !config.prioritize_plugins || self.input.is_some() || self.dmenu || self.run.len() < 2 {
+            return;
+        }
+        let prefixed = self.run.iter().any(|name| {
+            let plugin = config.plugins.get(name);
+            plugin.map(|p| p.prefix.is_some()).unwrap_or(false)
+        });
+        if prefixed {
+            return;
+        }
+        self.run = crate::history::plugin_order(&self.run);
+        self.deferred = self.run[1..].to_vec();
+        log::info!(
+            "loading {:?} first, deferring {:?}",
+            self.run[0],
+            self.deferred
+        );
+    }
+
+    /// Take the Plugins Left to Load after the Menu Opened
+    #[inline]
+    pub fn take_deferred(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.deferred)
+    }
+
+    /// Load a Deferred Plugin and Append its Entries to the Loaded Ones
+    ///
+    /// Options sent by deferred plugins are ignored, as the menu already
+    /// opened w/ the configuration of the plugins loaded before it.
+    pub fn load_deferred(
+        &mut self,
+        config: &mut Config,
+        name: &str,
+        entries: &mut Vec<Entry>,
+        normalized: &mut Vec<Normalized>,
+    ) -> Result<()> {
+        let mut options = Options::default();
+        let names = vec![name.to_owned()];
+        config.search.scopes.retain(|scope| scope.name != name);
+        let (loaded, norm) = self.load_plugins(config, &mut options, names, entries.len())?;
+        entries.extend(loaded);
+        normalized.extend(norm);
+        crate::history::rank(config, entries, normalized);
+        Ok(())
+    }
+
+    /// Load Entries (and their Normalized Search Fields) from
+    /// Enabled/Configured Entry-Sources
+    pub fn get_entries(&mut self, config: &mut Config) -> Result<(Vec<Entry>, Vec<Normalized>)> {
         // configure default source if none are given
         let mut input = self.input.clone();
         let mut entries = vec![];
-        if input.is_none() && self.run.is_empty() {
+        let mut normalized = vec![];
+        if input.is_none() && (self.dmenu || self.run.is_empty()) {
             input = Some("-".to_owned());
         }
+        if self.dmenu {
+            self.format = Format::DMenu;
+        }
         // load entries
+        let mut options = Options::default();
         if let Some(input) = input {
-            entries.extend(self.load_input(&input, config)?);
+            let loaded = self.load_input(&input, &mut options)?;
+            normalized.extend(loaded.iter().map(Normalized::new));
+            entries.extend(loaded);
         }
-        entries.extend(self.load_plugins(config)?);
-        Ok(entries)
+        let names: Vec<String> = self
+            .run
+            .iter()
+            .filter(|name| !self.deferred.contains(name))
+            .cloned()
+            .collect();
+        let (loaded, norm) = self.load_plugins(config, &mut options, names, entries.len())?;
+        entries.extend(loaded);
+        normalized.extend(norm);
+        // deferred plugins own no entries until they are loaded
+        for name in self.deferred.iter() {
+            config.search.scopes.push(Scope {
+                name: name.clone(),
+                prefix: None,
+                entries: entries.len()..entries.len(),
+            });
+        }
+        config
+            .update(&options)
+            .map_err(|e| RMenuError::InvalidKeybind(e))?;
+        crate::history::rank(config, &mut entries, &mut normalized);
+        Ok((entries, normalized))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_config_keys() {
+        let content = "jump_distt: 2\nsearch:\n  ignore_case: false\n  regexx: true\n";
+        let config = parse_config(content, false).unwrap();
+        assert_eq!(config.jump_dist, Config::default().jump_dist);
+        assert!(!config.search.ignore_case);
+        match parse_config(content, true) {
+            Err(RMenuError::UnknownKeys(keys)) => {
+                assert_eq!(keys, vec!["jump_distt", "search.regexx"])
+            }
+            other => panic!("expected unknown keys, got {other:?}"),
+        }
+        assert!(parse_config("search:\n  mode: fuzzy\n", true).is_ok());
+    }
+
+    #[test]
+    fn test_can_reload() {
+        let path = std::env::temp_dir().join(format!("rmenu-input-{}.fifo", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let cpath = std::ffi::CString::new(path.to_string_lossy().as_bytes()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(cpath.as_ptr(), 0o600) }, 0);
+        let fifo = path.to_string_lossy().to_string();
+        assert!(!Args::parse_from(["rmenu", "--input", "-"]).can_reload());
+        assert!(!Args::parse_from(["rmenu", "--input", &fifo]).can_reload());
+        assert!(!Args::parse_from(["rmenu", "--dmenu", "--run", "drun"]).can_reload());
+        assert!(Args::parse_from(["rmenu", "--input", "/etc/hostname"]).can_reload());
+        assert!(Args::parse_from(["rmenu", "--run", "drun"]).can_reload());
+        let _ = std::fs::remove_file(&path);
     }
 }
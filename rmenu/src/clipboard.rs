@@ -0,0 +1,42 @@
+//! RMenu System Clipboard Access
+use std::process::{Command, Stdio};
+
+use which::which;
+
+/// Clipboard Readers in Order of Preference (Wayland First)
+static WAYLAND_READERS: &[&[&str]] = &[&["wl-paste", "--no-newline"]];
+static X11_READERS: &[&[&str]] = &[
+    &["xclip", "-o", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--output"],
+];
+
+/// Collapse Newlines and Other Whitespace into a Single-Line Query
+pub fn sanitize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Read Clipboard Contents using the First Available Reader
+pub fn read() -> Option<String> {
+    let readers = match std::env::var("WAYLAND_DISPLAY").is_ok() {
+        true => WAYLAND_READERS.iter().chain(X11_READERS.iter()),
+        false => X11_READERS.iter().chain(WAYLAND_READERS.iter()),
+    };
+    for reader in readers {
+        if which(reader[0]).is_err() {
+            continue;
+        }
+        let output = Command::new(reader[0])
+            .args(&reader[1..])
+            .stderr(Stdio::null())
+            .output();
+        match output {
+            Ok(out) if out.status.success() => {
+                return Some(sanitize(&String::from_utf8_lossy(&out.stdout)));
+            }
+            Ok(out) => log::debug!("{:?} failed: {:?}", reader[0], out.status),
+            Err(err) => log::error!("failed to read clipboard: {err:?}"),
+        }
+    }
+    log::warn!("no clipboard reader available");
+    None
+}
@@ -0,0 +1,98 @@
+//! RMenu Crash-Report Panic Handler
+use std::backtrace::Backtrace;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use which::which;
+
+use crate::config::Config;
+
+static CRASH_CONTEXT: Lazy<Mutex<Context>> = Lazy::new(|| Mutex::new(Context::default()));
+
+/// Plugin-List and Config Summary of the Running Menu
+#[derive(Debug, Clone, Default, Serialize)]
+struct Context {
+    plugins: Vec<String>,
+    config: Value,
+}
+
+/// Crash Report Written as JSON for Bug Reports and Wrapper Scripts
+#[derive(Debug, Serialize)]
+struct Report {
+    version: &'static str,
+    message: String,
+    location: Option<String>,
+    backtrace: String,
+    #[serde(flatten)]
+    context: Context,
+}
+
+/// Generate new Crash-Report Filepath in the Cache Directory
+fn report_file() -> Option<PathBuf> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let dir = crate::paths::cache_dir();
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir.join(format!("crash-{now}.json")))
+}
+
+/// Notify User of Crash-Report Location (if a Notifier is Available)
+fn notify(path: &PathBuf) {
+    let message = format!("crash report written to {path:?}");
+    eprintln!("rmenu crashed: {message}");
+    if which("notify-send").is_ok() {
+        let _ = Command::new("notify-send")
+            .args(["--urgency=critical", "RMenu Crashed", &message])
+            .spawn();
+    }
+}
+
+/// Update Config Summary and Plugin-List Included in Crash Reports
+pub fn set_context(plugins: &[String], config: &Config) {
+    let context = Context {
+        plugins: plugins.to_vec(),
+        config: serde_json::to_value(config).unwrap_or_else(|e| Value::String(format!("{e:?}"))),
+    };
+    if let Ok(mut ctx) = CRASH_CONTEXT.lock() {
+        *ctx = context;
+    }
+}
+
+/// Install Panic Hook that Writes a Crash Report instead of Silently Exiting
+pub fn install() {
+    std::panic::set_hook(Box::new(|info| {
+        let backtrace = Backtrace::force_capture();
+        let message = match info.payload().downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => info
+                .payload()
+                .downcast_ref::<String>()
+                .cloned()
+                .unwrap_or_default(),
+        };
+        let report = Report {
+            version: env!("CARGO_PKG_VERSION"),
+            message,
+            location: info.location().map(|l| l.to_string()),
+            backtrace: backtrace.to_string(),
+            context: CRASH_CONTEXT.lock().map(|c| c.clone()).unwrap_or_default(),
+        };
+        let report = serde_json::to_string_pretty(&report).unwrap_or_else(|e| format!("{e:?}"));
+        log::error!("{info}");
+        match report_file() {
+            Some(path) => match fs::write(&path, report) {
+                Ok(_) => notify(&path),
+                Err(err) => eprintln!("failed to write crash report: {err:?}\n{backtrace}"),
+            },
+            None => eprintln!("{report}"),
+        }
+    }));
+}
@@ -0,0 +1,150 @@
+//! RMenu User Stylesheet Sanity Checks
+
+/// Selectors the Menu is Unusable without
+static ESSENTIAL: &[&str] = &[
+    "html", "body", "*", "#content", "#navbar", "#search", "#results", ".result",
+];
+
+/// Declarations Hiding an Element Entirely
+static HIDING: &[&str] = &["display:none", "visibility:hidden", "opacity:0"];
+
+/// Remove Comments and String Contents so Braces can be Counted
+fn strip(css: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(css.len());
+    let mut chars = css.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if last == '*' => break,
+                        Some(c) => last = c,
+                        None => return Err("unterminated comment".to_owned()),
+                    }
+                }
+            }
+            '"' | '\'' => {
+                out.push(c);
+                loop {
+                    match chars.next() {
+                        Some('\\') => {
+                            chars.next();
+                        }
+                        Some(q) if q == c => break,
+                        Some('\n') | None => return Err("unterminated string".to_owned()),
+                        Some(_) => {}
+                    }
+                }
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+/// Split Stylesheet into Selector and Declaration Blocks (Unwrapping @-Rules)
+fn rules(css: &str) -> Vec<(String, String)> {
+    let mut out = vec![];
+    let mut depth = 0;
+    let mut selector = String::new();
+    let mut body = String::new();
+    for c in css.chars() {
+        match (c, depth) {
+            ('{', 0) => depth = 1,
+            ('}', 1) => {
+                depth = 0;
+                match selector.trim_start().starts_with('@') {
+                    true => out.extend(rules(&body)),
+                    false => out.push((selector.trim().to_owned(), body.clone())),
+                }
+                selector.clear();
+                body.clear();
+            }
+            ('{', _) => {
+                depth += 1;
+                body.push(c);
+            }
+            ('}', _) => {
+                depth -= 1;
+                body.push(c);
+            }
+            (c, 0) => selector.push(c),
+            (c, _) => body.push(c),
+        }
+    }
+    out
+}
+
+/// Check Stylesheet is Balanced and does not Hide Essential Elements
+pub fn check(css: &str) -> Result<(), String> {
+    let css = strip(css)?;
+    let mut depth: i64 = 0;
+    for c in css.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+        if depth < 0 {
+            return Err("unexpected closing brace".to_owned());
+        }
+    }
+    if depth != 0 {
+        return Err("unclosed block".to_owned());
+    }
+    for (selector, body) in rules(&css) {
+        let Some(essential) = selector
+            .split(',')
+            .map(|s| s.trim())
+            .find(|s| ESSENTIAL.contains(s))
+        else {
+            continue;
+        };
+        let hidden = body
+            .split(';')
+            .map(|d| {
+                d.split_whitespace()
+                    .collect::<String>()
+                    .to_ascii_lowercase()
+            })
+            .any(|d| {
+                let d = d.trim_end_matches("!important");
+                HIDING.contains(&d)
+            });
+        if hidden {
+            return Err(format!("hides essential element {essential:?}"));
+        }
+    }
+    Ok(())
+}
+
+/// Return the Stylesheet if Sane, otherwise Fall Back to the Built-In Styles
+pub fn sanitize(name: &str, css: String) -> String {
+    match check(&css) {
+        Ok(_) => css,
+        Err(err) => {
+            log::warn!("{name} css rejected ({err}), falling back to built-in stylesheet");
+            String::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_css_sanity() {
+        assert!(check(include_str!("../../themes/launchpad.css")).is_ok());
+        assert!(check(include_str!("../public/default.css")).is_ok());
+        assert!(check("@media (x) { .a { color: red; } }").is_ok());
+        assert!(check(".a { content: \"}\"; }").is_ok());
+        assert!(check(".a { color: red;").is_err());
+        assert!(check("/* .a { }").is_err());
+        assert!(check("#results, .x { display : none }").is_err());
+        assert!(check("body { opacity: 0 !important; }").is_err());
+    }
+}
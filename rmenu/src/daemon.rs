@@ -0,0 +1,201 @@
+//! RMenu Daemon Holding Entries Pushed by External Processes
+//!
+//! Clients connect to the daemon socket and send a single command line:
+//! `push <channel>` or `replace <channel>` followed by `Message` json lines,
+//! `clear <channel>`, or `fetch <channel>` which replies w/ the messages.
+//! `status <service>` replies w/ the supervision state of a service plugin.
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use rmenu_plugin::Message;
+
+use crate::cli::{RMenuError, Result};
+use crate::config::Config;
+use crate::service::Status;
+
+static SOCKET_NAME: &'static str = "rmenu-daemon.sock";
+static FD_NAME: &'static str = "daemon";
+static CHANNELS: Lazy<Mutex<BTreeMap<String, Vec<Message>>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// Prefix Selecting a Daemon Channel instead of a Plugin
+pub static CHANNEL_PREFIX: &'static str = "channel:";
+
+/// Retrieve Daemon Socket Path in the XDG Runtime Directory
+fn socket_path() -> Option<PathBuf> {
+    crate::paths::runtime_file(SOCKET_NAME)
+}
+
+/// Connect to the Running Daemon
+fn connect() -> Result<UnixStream> {
+    let path = socket_path().ok_or(RMenuError::NotRunning)?;
+    UnixStream::connect(&path).map_err(|_| RMenuError::NotRunning)
+}
+
+/// Handle a Single Client Connection
+pub fn handle(stream: UnixStream) {
+    let mut reader = BufReader::new(&stream);
+    let mut line = String::new();
+    if reader.read_line(&mut line).is_err() {
+        return;
+    }
+    let (cmd, channel) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+    let channel = channel.trim().to_owned();
+    if channel.is_empty() {
+        log::warn!("daemon command missing channel: {line:?}");
+        return;
+    }
+    match cmd {
+        "push" | "replace" => {
+            let mut messages = vec![];
+            for line in reader.lines().filter_map(|l| l.ok()) {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<Message>(&line) {
+                    Ok(msg) => messages.push(msg),
+                    Err(err) => log::warn!("invalid message for {channel:?}: {err}"),
+                }
+            }
+            log::info!("{cmd} {} messages into {channel:?}", messages.len());
+            let mut channels = CHANNELS.lock().expect("Failed to Access Global Mutex");
+            let stored = channels.entry(channel).or_default();
+            if cmd == "replace" {
+                stored.clear();
+            }
+            stored.append(&mut messages);
+        }
+        "clear" => {
+            log::info!("clearing channel {channel:?}");
+            CHANNELS
+                .lock()
+                .expect("Failed to Access Global Mutex")
+                .remove(&channel);
+        }
+        "fetch" => {
+            let messages = messages(&channel);
+            let mut stream = &stream;
+            for msg in messages {
+                let line = serde_json::to_string(&msg).expect("Failed to Serialize Message");
+                if writeln!(stream, "{line}").is_err() {
+                    break;
+                }
+            }
+        }
+        "status" => {
+            let status = crate::service::status(&channel);
+            let line = serde_json::to_string(&status).expect("Failed to Serialize Status");
+            let _ = writeln!(&stream, "{line}");
+        }
+        _ => log::warn!("unknown daemon command: {line:?}"),
+    }
+}
+
+/// Retrieve a Copy of the Messages Held in the Channel
+pub fn messages(channel: &str) -> Vec<Message> {
+    CHANNELS
+        .lock()
+        .expect("Failed to Access Global Mutex")
+        .get(channel)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Replace the Messages Held in the Channel
+pub fn publish(channel: &str, messages: Vec<Message>) {
+    log::debug!("publishing {} messages into {channel:?}", messages.len());
+    CHANNELS
+        .lock()
+        .expect("Failed to Access Global Mutex")
+        .insert(channel.to_owned(), messages);
+}
+
+/// Claim the Daemon Socket (Removing Stale Sockets)
+fn bind() -> Result<UnixListener> {
+    let path = socket_path().ok_or(RMenuError::NotRunning)?;
+    if UnixStream::connect(&path).is_ok() {
+        return Err(RMenuError::AlreadyRunning);
+    }
+    let _ = fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+    crate::signals::remove_on_exit(path.clone());
+    log::info!("daemon listening on {path:?}");
+    Ok(listener)
+}
+
+/// Run the Daemon Accepting Channel Commands until Terminated
+///
+/// Service plugins of the configuration are started and supervised
+/// for as long as the daemon runs. Under systemd socket activation the
+/// passed socket is served instead, and readiness is reported once
+/// services were started.
+pub fn run(config: &Config) -> Result<()> {
+    let listener = match crate::systemd::take_listener(FD_NAME) {
+        Some(listener) => listener,
+        None => bind()?,
+    };
+    crate::service::start(config);
+    crate::systemd::notify_ready();
+    for stream in listener.incoming().filter_map(|s| s.ok()) {
+        std::thread::spawn(move || handle(stream));
+    }
+    Ok(())
+}
+
+/// Push Messages Read from Stdin into a Daemon Channel
+pub fn send(channel: &str, replace: bool) -> Result<()> {
+    let mut stream = connect()?;
+    let cmd = if replace { "replace" } else { "push" };
+    writeln!(stream, "{cmd} {channel}")?;
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    stream.write_all(input.as_bytes())?;
+    Ok(())
+}
+
+/// Retrieve the Supervision State of a Service Plugin from the Daemon
+pub fn service_status(name: &str) -> Result<Option<Status>> {
+    let mut stream = connect()?;
+    writeln!(stream, "status {name}")?;
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// Retrieve the Messages Held in a Daemon Channel
+pub fn fetch(channel: &str) -> Result<Vec<Message>> {
+    let mut stream = connect()?;
+    writeln!(stream, "fetch {channel}")?;
+    let mut messages = vec![];
+    for line in BufReader::new(stream).lines() {
+        messages.push(serde_json::from_str(&line?)?);
+    }
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmenu_plugin::Entry;
+
+    #[test]
+    fn test_daemon_channel() {
+        let entry = Message::Entry(Entry::echo("Notification", None));
+        let line = serde_json::to_string(&entry).unwrap();
+        let (mut client, server) = UnixStream::pair().unwrap();
+        write!(client, "push test-channel\n{line}\nnot json\n").unwrap();
+        drop(client);
+        handle(server);
+        let (mut client, server) = UnixStream::pair().unwrap();
+        writeln!(client, "fetch test-channel").unwrap();
+        handle(server);
+        let mut reply = String::new();
+        client.read_to_string(&mut reply).unwrap();
+        assert_eq!(reply, format!("{line}\n"));
+    }
+}
@@ -1,61 +1,440 @@
 //! Execution Implementation for Entry Actions
-use std::process::Command;
-use std::{collections::HashMap, os::unix::process::CommandExt};
-
-use rmenu_plugin::{Action, Method};
-use shell_words::split;
-use strfmt::strfmt;
-use which::which;
-
-/// Find Best Terminal To Execute
-fn find_terminal() -> String {
-    vec![
-        ("alacritty", "-e {cmd}"),
-        ("kitty", "{cmd}"),
-        ("gnome-terminal", "-x {cmd}"),
-        ("foot", "-e {cmd}"),
-        ("xterm", "-C {cmd}"),
-    ]
-    .into_iter()
-    .map(|(t, v)| (which(t), v))
-    .filter(|(c, _)| c.is_ok())
-    .map(|(c, v)| (c.unwrap(), v))
-    .map(|(p, v)| {
-        (
-            p.to_str()
-                .expect("Failed to Parse Terminal Path")
-                .to_owned(),
-            v,
-        )
-    })
-    .find_map(|(p, v)| Some(format!("{p} {v}")))
-    .expect("Failed to Find Terminal Executable!")
-}
-
-#[inline]
-fn parse_args(exec: &str) -> Vec<String> {
-    match split(exec) {
-        Ok(args) => args,
-        Err(err) => panic!("{:?} invalid command {err}", exec),
-    }
-}
-
-pub fn execute(action: &Action, term: Option<String>) {
-    log::info!("executing: {:?} {:?}", action.name, action.exec);
-    let args = match &action.exec {
-        Method::Run(exec) => parse_args(&exec),
-        Method::Terminal(exec) => {
-            let mut args = HashMap::new();
-            let terminal = term.unwrap_or_else(find_terminal);
-            args.insert("cmd".to_string(), exec.to_owned());
-            let command = strfmt(&terminal, &args).expect("Failed String Format");
-            parse_args(&command)
-        }
-        Method::Echo(echo) => {
+use std::io::Write;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+
+use clap::Parser;
+
+use rmenu_core::config::{Config, MultiExec};
+use rmenu_core::events::{emit, Event};
+use rmenu_core::search::Normalized;
+use rmenu_plugin::{Action, Entry, Message, Method};
+use zeroize::Zeroizing;
+
+pub use rmenu_core::exec::command_args;
+use rmenu_core::exec::{desktop_action, fill_placeholders, sandboxed, PLUGIN_MENU};
+
+/// Run a Nested Menu Command and Collect the Entries it Prints
+///
+/// Lines that are not JSON messages are treated as dmenu-style echo entries.
+pub fn load_menu(args: &[String]) -> Option<(Vec<Entry>, Vec<Normalized>)> {
+    log::info!("loading menu: {args:?}");
+    let output = Command::new(&args[0])
+        .args(&args[1..])
+        .stdin(Stdio::null())
+        .output();
+    let output = match output {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            log::error!("menu command failed: {:?}", output.status);
+            return None;
+        }
+        Err(err) => {
+            log::error!("failed to spawn {:?}: {err:?}", args[0]);
+            return None;
+        }
+    };
+    let mut entries = vec![];
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        match serde_json::from_str::<Message>(line) {
+            Ok(Message::Entry(entry)) => entries.push(entry),
+            Ok(Message::Options(_)) => log::warn!("nested menus ignore plugin options"),
+            Err(_) if !line.trim().is_empty() => entries.push(Entry::echo(line.trim(), None)),
+            Err(_) => {}
+        }
+    }
+    let normalized = entries.iter().map(Normalized::new).collect();
+    Some((entries, normalized))
+}
+
+/// Load the Entries of a Configured Plugin Opened as a Nested Menu
+///
+/// The plugin runs like any other source (w/ its cache, static entries
+/// and source type), but its options are left out for the nested menu.
+fn load_plugin(mut config: Config, name: &str) -> Option<(Vec<Entry>, Vec<Normalized>)> {
+    log::info!("loading plugin menu: {name:?}");
+    let mut cli = crate::cli::Args::parse_from(["rmenu", "--run", name]);
+    match cli.get_entries(&mut config) {
+        Ok(loaded) => Some(loaded),
+        Err(err) => {
+            log::error!("failed to load plugin {name:?}: {err:?}");
+            None
+        }
+    }
+}
+
+/// Nested Menu Resolved from the Config, Ready to Load on Another Thread
+pub enum Menu {
+    Command(Vec<String>),
+    /// Plugin Name w/ a Config Holding only that Plugin
+    Plugin(String, Config),
+}
+
+impl Menu {
+    /// Resolve the Nested Menu of a Plugin Reference or its Command
+    pub fn new(config: &Config, args: &[String]) -> Option<Self> {
+        let Some(name) = args.first().and_then(|a| a.strip_prefix(PLUGIN_MENU)) else {
+            return Some(Self::Command(args.to_vec()));
+        };
+        let name = name.trim();
+        let Some(plugin) = config.plugins.get(name).cloned() else {
+            log::error!("no such plugin for nested menu: {name:?}");
+            return None;
+        };
+        let config = Config {
+            plugins: [(name.to_owned(), plugin)].into_iter().collect(),
+            cache_max_bytes: config.cache_max_bytes,
+            use_history: config.use_history,
+            ..Default::default()
+        };
+        Some(Self::Plugin(name.to_owned(), config))
+    }
+
+    /// Run the Menu Command (or Plugin) and Collect its Entries
+    pub fn load(self) -> Option<(Vec<Entry>, Vec<Normalized>)> {
+        match self {
+            Self::Command(args) => load_menu(&args),
+            Self::Plugin(name, config) => load_plugin(config, &name),
+        }
+    }
+}
+
+/// Load a Nested Menu from a Plugin Reference or its Command
+pub fn open_menu(config: &Config, args: &[String]) -> Option<(Vec<Entry>, Vec<Normalized>)> {
+    Menu::new(config, args)?.load()
+}
+
+/// Build the Plugin Command Receiving a Submitted Query as its Last Argument
+pub fn submit_args(config: &Config, plugin: &str, query: &str) -> Option<Vec<String>> {
+    let exec = &config.plugins.get(plugin)?.exec;
+    if exec.is_empty() {
+        return None;
+    }
+    let mut args = crate::cli::plugin_args(exec);
+    args.push(query.to_owned());
+    Some(args)
+}
+
+/// Serialize the Entry Piped into an Action w/o RMenu's Internal Actions
+fn pipe_input(entry: &Entry) -> serde_json::Result<String> {
+    let mut entry = entry.clone();
+    entry.actions.retain(|a| !a.exec.is_internal());
+    serde_json::to_string(&entry)
+}
+
+/// Bind Actions to the Entry they were Selected from before Execution
+///
+/// Placeholders are filled w/ the entry fields and the query (or the
+/// field codes expanded for entries of desktop files), and pipe
+/// actions receive the JSON of their entry.
+pub fn bind_actions(selected: Vec<(Entry, Action)>, query: &str) -> Vec<Action> {
+    selected
+        .into_iter()
+        .map(|(entry, mut action)| {
+            match desktop_action(&action, &entry) {
+                Some(desktop) => action = desktop,
+                None => action.exec = fill_placeholders(&action.exec, &entry, query),
+            }
+            if let Method::Pipe(exec) = &action.exec {
+                match pipe_input(&entry) {
+                    Ok(input) => action.exec = Method::PipeInput(exec.to_owned(), input),
+                    Err(err) => log::error!("failed to serialize entry for {exec:?}: {err:?}"),
+                }
+            }
+            action
+        })
+        .collect()
+}
+
+/// Build the Command w/ the Environment and Working Directory of the Action
+///
+/// Inside a sandbox both are handed to `flatpak-spawn`, since the command
+/// itself runs on the host.
+fn command(args: &[String], action: &Action) -> Command {
+    let mut command = Command::new(&args[0]);
+    if sandboxed() && args.get(1).map(|a| a.as_str()) == Some("--host") {
+        command.arg(&args[1]);
+        command.args(action.env.iter().map(|(k, v)| format!("--env={k}={v}")));
+        if let Some(cwd) = action.cwd.as_ref() {
+            command.arg(format!("--directory={}", cwd.display()));
+        }
+        command.args(&args[2..]);
+        return command;
+    }
+    command.args(&args[1..]).envs(&action.env);
+    if let Some(cwd) = action.cwd.as_ref() {
+        command.current_dir(cwd);
+    }
+    command
+}
+
+/// Spawn the Command w/ the Bound Input of Pipe Actions Written to Stdin
+fn spawn(args: &[String], action: &Action) -> std::io::Result<Child> {
+    let input = match &action.exec {
+        Method::PipeInput(_, input) => Some(input.as_str()),
+        Method::Pipe(_) => Some(""),
+        _ => None,
+    };
+    let mut command = command(args, action);
+    if input.is_some() {
+        command.stdin(Stdio::piped());
+    }
+    let mut child = command.spawn()?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // the stdin handle closes once written so the command sees EOF
+        if let Err(err) = stdin.write_all(input.as_bytes()) {
+            log::error!("failed to pipe entry into {:?}: {err:?}", args[0]);
+        }
+    }
+    Ok(child)
+}
+
+/// Check if the Menu stays Open after Executing all the Actions
+pub fn keeps_open(actions: &[Action], config: &Config) -> bool {
+    !actions.is_empty() && (config.keep_open || actions.iter().all(|a| !a.close))
+}
+
+/// Run Actions in the Background and Refresh the Entries once they Exit
+///
+/// Commands are spawned instead of replacing rmenu, so the menu stays
+/// open and the refreshed entries reflect their effects.
+pub fn execute_open(actions: &[Action], config: &Config) {
+    let mut commands = vec![];
+    for action in actions {
+        log::info!("executing w/o closing: {:?}", action.name);
+        emit(Event::ActionExecuted(action.clone()));
+        match command_args(&action.exec, config) {
+            Some(args) => commands.push((args, action.clone())),
+            None => match &action.exec {
+                Method::Echo(echo) => println!("{echo}"),
+                _ => log::warn!("cannot run {:?} w/o closing", action.name),
+            },
+        }
+    }
+    std::thread::spawn(move || {
+        for (args, action) in commands {
+            match spawn(&args, &action) {
+                Ok(mut child) => {
+                    crate::signals::track_child(child.id());
+                    let _ = child.wait();
+                    crate::signals::untrack_child(child.id());
+                }
+                Err(err) => log::error!("failed to spawn {:?}: {err:?}", args[0]),
+            }
+        }
+        crate::instance::queue(crate::instance::Control::Update);
+    });
+}
+
+pub fn execute(action: &Action, config: &Config) {
+    if keeps_open(std::slice::from_ref(action), config) {
+        return execute_open(std::slice::from_ref(action), config);
+    }
+    log::info!("executing: {:?}", action.name);
+    emit(Event::ActionExecuted(action.clone()));
+    crate::signals::teardown();
+    let Some(args) = command_args(&action.exec, config) else {
+        if let Method::Echo(echo) = &action.exec {
             println!("{echo}");
-            std::process::exit(0);
         }
+        std::process::exit(0);
     };
-    let err = Command::new(&args[0]).args(&args[1..]).exec();
-    panic!("Command Error: {err:?}");
+    if !matches!(action.exec, Method::Pipe(_) | Method::PipeInput(..)) {
+        let err = command(&args, action).exec();
+        panic!("Command Error: {err:?}");
+    }
+    // pipes need rmenu to write the input, so the command is waited on instead
+    let status = spawn(&args, action).and_then(|mut child| child.wait());
+    match status {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(err) => panic!("Command Error: {err:?}"),
+    }
+}
+
+/// Print a Masked Search w/o Logging it and Exit
+pub fn echo_secret(secret: Zeroizing<String>) {
+    log::info!("echoing masked search");
+    crate::signals::teardown();
+    println!("{}", secret.as_str());
+    drop(secret);
+    std::process::exit(0);
+}
+
+/// Execute Multiple Selected Actions using the Specified Semantics
+///
+/// Every action spawns its own command, waiting for each to finish
+/// before the next unless they run in parallel.
+pub fn execute_many(actions: &[Action], config: &Config) {
+    if let [action] = actions {
+        return execute(action, config);
+    }
+    if keeps_open(actions, config) {
+        return execute_open(actions, config);
+    }
+    let mode = &config.multi_exec;
+    log::info!("executing {} actions ({mode:?})", actions.len());
+    crate::signals::teardown();
+    let mut echoes = vec![];
+    let mut children = vec![];
+    let mut failed = false;
+    for action in actions {
+        emit(Event::ActionExecuted(action.clone()));
+        let Some(args) = command_args(&action.exec, config) else {
+            match (&action.exec, mode) {
+                (Method::Echo(echo), MultiExec::EchoJoin) => echoes.push(echo.as_str()),
+                (Method::Echo(echo), _) => println!("{echo}"),
+                _ => failed = true,
+            }
+            continue;
+        };
+        match spawn(&args, action) {
+            Ok(child) if mode == &MultiExec::Parallel => children.push(child),
+            Ok(mut child) => failed |= !child.wait().map(|s| s.success()).unwrap_or(false),
+            Err(err) => {
+                log::error!("failed to spawn {:?}: {err:?}", args[0]);
+                failed = true;
+            }
+        }
+    }
+    for mut child in children {
+        failed |= !child.wait().map(|s| s.success()).unwrap_or(false);
+    }
+    if !echoes.is_empty() {
+        println!("{}", echoes.join(" "));
+    }
+    std::process::exit(failed as i32);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::testing::fixture_plugin;
+    use rmenu_plugin::{Entry, Method};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_keep_open() {
+        let mut config = Config::default();
+        let json = r#"{"name":"main","exec":{"run":"pactl"},"comment":null,"icon":null}"#;
+        let closing: Action = serde_json::from_str(json).unwrap();
+        assert!(closing.close);
+        let mut open = closing.clone();
+        open.close = false;
+        assert!(!keeps_open(&[closing.clone()], &config));
+        assert!(keeps_open(&[open.clone()], &config));
+        assert!(!keeps_open(&[open, closing.clone()], &config));
+        assert!(!keeps_open(&[], &config));
+        config.keep_open = true;
+        assert!(keeps_open(&[closing], &config));
+    }
+
+    #[test]
+    fn test_plugin_menu() {
+        let mut config = Config::default();
+        config
+            .plugins
+            .insert("basic.sh".to_owned(), fixture_plugin("basic.sh"));
+        let method = Method::Menu("plugin:basic.sh".to_owned());
+        let args = rmenu_core::exec::menu_args(&method).unwrap();
+        assert_eq!(args, vec!["plugin:basic.sh".to_owned()]);
+        let (entries, normalized) = open_menu(&config, &args).unwrap();
+        assert_eq!(entries.len(), 5);
+        assert_eq!(normalized.len(), 5);
+        assert_eq!(entries[0].source.as_deref(), Some("basic.sh"));
+        assert!(open_menu(&config, &["plugin:missing".to_owned()]).is_none());
+    }
+
+    /// Select every Action of the Entry
+    fn select(entry: &Entry) -> Vec<(Entry, Action)> {
+        entry
+            .actions
+            .iter()
+            .map(|a| (entry.clone(), a.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_masked_query_binding() {
+        use crate::search::Normalized;
+        use crate::state::InnerState;
+        let mut config = Config::default();
+        config.search.password = true;
+        let entries = vec![Entry::new("Unlock", "unlock --key {query}", None)];
+        let normalized: Vec<Normalized> = entries.iter().map(Normalized::new).collect();
+        let mut state = InnerState::new(&config);
+        assert!(state.set_search(&config, "hunter2".to_owned()));
+        let results = state.results(&config, &entries, &normalized);
+        let actions = bind_actions(state.actions(&results), state.query());
+        let Method::Run(exec) = &actions[0].exec else {
+            panic!("run action was not bound");
+        };
+        assert!(!exec.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_pipe_binding() {
+        let mut entry = Entry::new("Report", "cat", Some("it's \"quoted\""));
+        entry.actions[0].exec = Method::Pipe("jq .comment".to_owned());
+        let plain = entry.clone();
+        entry.actions.push(Action {
+            exec: Method::DisableSource("reports".to_owned()),
+            ..Action::exec("")
+        });
+        let actions = bind_actions(vec![(entry.clone(), plain.actions[0].clone())], "");
+        let Method::PipeInput(exec, input) = &actions[0].exec else {
+            panic!("pipe action was not bound");
+        };
+        assert_eq!(exec, "jq .comment");
+        // internal source actions are left out of the piped entry
+        let piped: Entry = serde_json::from_str(input).unwrap();
+        assert_eq!(piped, plain);
+        let args = command_args(&actions[0].exec, &Config::default()).unwrap();
+        assert_eq!(args.last().map(|s| s.as_str()), Some(".comment"));
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let mut entry = Entry::new("Report", "notify-send {name} {meta.path}", None);
+        entry
+            .meta
+            .insert("path".to_owned(), "/tmp/it's here".to_owned());
+        entry.actions.push(Action::echo("{query}: {meta.missing}"));
+        entry
+            .actions
+            .push(Action::exec("awk '{print $1}' {source}"));
+        let actions = bind_actions(select(&entry), "fire fox");
+        let config = Config::default();
+        assert_eq!(
+            command_args(&actions[0].exec, &config).unwrap(),
+            vec!["notify-send", "Report", "/tmp/it's here"]
+        );
+        assert_eq!(
+            actions[1].exec,
+            Method::Echo("fire fox: {meta.missing}".to_owned())
+        );
+        assert_eq!(
+            command_args(&actions[2].exec, &config).unwrap(),
+            vec!["awk", "{print $1}", ""]
+        );
+    }
+
+    #[test]
+    fn test_desktop_exec() {
+        use rmenu_core::exec::{DESKTOP_FILE, DESKTOP_PATH};
+        let mut entry = Entry::new("Editor", "edit", None);
+        entry
+            .meta
+            .insert(DESKTOP_FILE.to_owned(), "/apps/edit.desktop".to_owned());
+        entry.actions[0].exec = Method::Terminal("vim %F".to_owned());
+        entry
+            .meta
+            .insert(DESKTOP_PATH.to_owned(), "/src/my project".to_owned());
+        let actions = bind_actions(select(&entry), "");
+        assert_eq!(actions[0].exec, Method::Terminal("vim".to_owned()));
+        assert_eq!(actions[0].cwd, Some(PathBuf::from("/src/my project")));
+        let plain = Entry::new("Date", "date +%s", None);
+        let actions = bind_actions(select(&plain), "");
+        assert_eq!(actions, plain.actions);
+    }
 }
@@ -0,0 +1,203 @@
+//! Desktop UI Font Detection for Matching the System Look
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::config::AppearanceConfig;
+
+/// Run a Command and Collect its Output when it Succeeds
+fn output(cmd: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(cmd).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Split a GTK Font Description (e.g. `Cantarell 11`) into Family and Size
+fn parse_gtk(desc: &str) -> Option<(String, f64)> {
+    let desc = desc.trim().trim_matches(['\'', '"']);
+    let (family, size) = desc.rsplit_once(' ')?;
+    let family = family.trim_end_matches(',').trim();
+    if family.is_empty() {
+        return None;
+    }
+    Some((family.to_owned(), size.parse().ok()?))
+}
+
+/// Split a Qt Font Description (e.g. `Noto Sans,10,-1,5,50,0`) into Family and Size
+fn parse_qt(desc: &str) -> Option<(String, f64)> {
+    let mut fields = desc.trim().split(',');
+    let family = fields.next()?.trim();
+    let size = fields.next()?.trim().parse().ok()?;
+    (!family.is_empty()).then(|| (family.to_owned(), size))
+}
+
+/// Read the Interface Font through the XDG Settings Portal
+fn portal_font() -> Option<(String, f64)> {
+    let reply = output(
+        "dbus-send",
+        &[
+            "--session",
+            "--print-reply=literal",
+            "--reply-timeout=200",
+            "--dest=org.freedesktop.portal.Desktop",
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Settings.Read",
+            "string:org.gnome.desktop.interface",
+            "string:font-name",
+        ],
+    )?;
+    // the literal reply wraps the value in nested variants
+    let value = reply.rsplit("variant").next()?.trim();
+    parse_gtk(value.trim_start_matches("string "))
+}
+
+/// Read the Interface Font from the GNOME Settings
+fn gsettings_font() -> Option<(String, f64)> {
+    let desc = output(
+        "gsettings",
+        &["get", "org.gnome.desktop.interface", "font-name"],
+    )?;
+    parse_gtk(&desc)
+}
+
+/// Read the General Font from the KDE Globals
+fn kde_font() -> Option<(String, f64)> {
+    let home = xdg::BaseDirectories::new().ok()?.get_config_home();
+    let content = std::fs::read_to_string(home.join("kdeglobals")).ok()?;
+    let mut general = false;
+    for line in content.lines().map(|l| l.trim()) {
+        if line.starts_with('[') {
+            general = line == "[General]";
+            continue;
+        }
+        if let Some(desc) = line.strip_prefix("font=").filter(|_| general) {
+            return parse_qt(desc);
+        }
+    }
+    None
+}
+
+/// Detect the Desktop UI Font through the Portal, GNOME or KDE Settings
+fn detect() -> Option<(String, f64)> {
+    portal_font().or_else(gsettings_font).or_else(kde_font)
+}
+
+/// Retrieve the Detected Font Cached Next to the Plugin Caches
+#[inline]
+fn cache_path() -> PathBuf {
+    crate::paths::cache_dir().join("font.json")
+}
+
+/// Remember the Detected Font (or its Absence) for Later Runs
+fn save(font: &Option<(String, f64)>) {
+    let path = cache_path();
+    let result = path
+        .parent()
+        .map(fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| fs::write(&path, serde_json::to_vec(font)?));
+    if let Err(err) = result {
+        log::error!("failed to write font cache {path:?}: {err:?}");
+    }
+}
+
+/// Fill Unset Font Settings w/ the Configured Desktop UI Font
+///
+/// Detection runs external commands, so the font found by an earlier
+/// run is used right away and refreshed in the background for the next.
+pub fn resolve(appearance: &mut AppearanceConfig) {
+    let unset = appearance.font_family.is_none() || appearance.font_size.is_none();
+    if !appearance.system_font || !unset {
+        return;
+    }
+    let cached = fs::read(cache_path())
+        .ok()
+        .and_then(|data| serde_json::from_slice::<Option<(String, f64)>>(&data).ok());
+    let font = match cached {
+        Some(font) => {
+            std::thread::spawn(|| save(&detect()));
+            font
+        }
+        None => {
+            let font = detect();
+            save(&font);
+            font
+        }
+    };
+    let Some((family, size)) = font else {
+        log::debug!("no desktop font configuration found");
+        return;
+    };
+    log::debug!("using desktop font {family:?} ({size}pt)");
+    appearance.font_family.get_or_insert(family);
+    appearance.font_size.get_or_insert(size);
+}
+
+/// Quote a Font Family Name w/o Characters Escaping the CSS Value
+fn quote(family: &str) -> String {
+    let family: String = family
+        .chars()
+        .filter(|c| !matches!(c, '"' | ';' | '{' | '}' | '\\'))
+        .collect();
+    format!("\"{family}\"")
+}
+
+/// Build the CSS Variables Exposing the Configured Font to Themes
+///
+/// The fallback stack renders emoji and scripts missing from the main
+/// font, and ends w/ the generic `emoji` family.
+pub fn css_vars(appearance: &AppearanceConfig) -> String {
+    let mut vars = vec![];
+    if let Some(family) = appearance.font_family.as_ref() {
+        vars.push(format!("--font-family: {}, sans-serif;", quote(family)));
+    }
+    if !appearance.font_fallback.is_empty() {
+        let stack: Vec<String> = appearance
+            .font_fallback
+            .iter()
+            .map(|f| quote(f.as_str()))
+            .collect();
+        vars.push(format!("--font-fallback: {}, emoji;", stack.join(", ")));
+    }
+    if let Some(size) = appearance.font_size.filter(|s| *s > 0.0) {
+        vars.push(format!("--font-size: {size}pt;"));
+    }
+    match vars.is_empty() {
+        true => String::new(),
+        false => format!(":root {{ {} }}", vars.join(" ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_font_vars() {
+        let mut appearance = AppearanceConfig {
+            system_font: false,
+            font_fallback: vec![],
+            ..Default::default()
+        };
+        resolve(&mut appearance);
+        assert_eq!(css_vars(&appearance), "");
+        appearance.font_family = Some("Noto \"Sans\"; }".to_owned());
+        appearance.font_size = Some(10.5);
+        let vars = css_vars(&appearance);
+        assert_eq!(
+            vars,
+            ":root { --font-family: \"Noto Sans \", sans-serif; --font-size: 10.5pt; }"
+        );
+        assert!(crate::css::check(&vars).is_ok());
+        appearance.font_family = None;
+        appearance.font_size = None;
+        appearance.font_fallback = vec!["Noto Color Emoji".to_owned(), "Sym}bola".to_owned()];
+        let vars = css_vars(&appearance);
+        assert_eq!(
+            vars,
+            ":root { --font-fallback: \"Noto Color Emoji\", \"Symbola\", emoji; }"
+        );
+    }
+}
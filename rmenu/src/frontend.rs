@@ -0,0 +1,510 @@
+//! RMenu Frontend Selection and Frontend-Agnostic Session State
+use std::sync::Arc;
+use std::time::Instant;
+
+use dioxus_html::input_data::keyboard_types::{Code, Modifiers};
+use rmenu_plugin::{Action, Entry, Method};
+use zeroize::Zeroizing;
+
+use crate::cli::Result;
+use crate::config::{Config, FrontendKind};
+use crate::exec::{bind_actions, echo_secret, execute_many};
+use crate::instance::{queue_sources, Control, Status};
+use crate::search::Normalized;
+use crate::state::{key_event, InnerState, KeyEvent};
+use crate::App;
+
+/// User-Interface Implementation Displaying Entries and Handling Input
+pub trait Frontend {
+    /// Run the Menu until an Action is Executed or it Exits
+    fn run(&self, app: App) -> Result<()>;
+}
+
+/// Default Dioxus Webview Frontend
+pub struct Webview;
+
+impl Frontend for Webview {
+    fn run(&self, app: App) -> Result<()> {
+        crate::gui::run(app);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "native")]
+fn native() -> Result<Box<dyn Frontend>> {
+    Ok(Box::new(crate::native::Native))
+}
+
+#[cfg(not(feature = "native"))]
+fn native() -> Result<Box<dyn Frontend>> {
+    Err(crate::cli::RMenuError::FrontendUnavailable(
+        "rmenu was built without the `native` feature".to_owned(),
+    ))
+}
+
+#[cfg(feature = "tui")]
+fn tui() -> Result<Box<dyn Frontend>> {
+    Ok(Box::new(crate::tui::Tui))
+}
+
+#[cfg(not(feature = "tui"))]
+fn tui() -> Result<Box<dyn Frontend>> {
+    Err(crate::cli::RMenuError::FrontendUnavailable(
+        "rmenu was built without the `tui` feature".to_owned(),
+    ))
+}
+
+/// Collect Live Search Counters for the Current State
+pub fn status(state: &InnerState, config: &Config) -> Status {
+    Status {
+        query_length: match config.search.password {
+            true => 0,
+            false => state.search().chars().count(),
+        },
+        matches: state.matches(),
+        plugin: state.plugin(config).map(|p| p.to_owned()),
+    }
+}
+
+/// Fill the Window Title Template w/ the Active Plugin and Match Count
+///
+/// `{plugin}` falls back to `rmenu` while no single plugin is active.
+pub fn title(template: &str, status: &Status) -> String {
+    template
+        .replace("{plugin}", status.plugin.as_deref().unwrap_or("rmenu"))
+        .replace("{count}", &status.matches.to_string())
+}
+
+/// Fill the Window Title Template before Anything was Searched
+pub fn initial_title(app: &App) -> String {
+    let status = Status {
+        matches: app.entries.len(),
+        plugin: app.config.search.plugin("").map(|p| p.to_owned()),
+        ..Default::default()
+    };
+    title(&app.config.window.title, &status)
+}
+
+/// Build Text Prompt shown before the Search w/o Image Support
+///
+/// Icon paths are skipped, while glyph icons are kept before the label.
+pub fn prompt(config: &Config) -> String {
+    let icon = config.search.prompt_icon.clone();
+    [
+        icon.filter(|i| !i.contains('/')),
+        config.search.prompt.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Build Tooltip w/ Full Name, Comment and Commands of an Entry
+pub fn tooltip(entry: &Entry) -> String {
+    let mut lines = vec![entry.name.clone()];
+    lines.extend(entry.comment.clone());
+    lines.extend(entry.source.as_ref().map(|s| format!("source: {s}")));
+    for action in entry.actions.iter() {
+        let exec = match &action.exec {
+            Method::Run(exec) => format!("run: {exec}"),
+            Method::Terminal(exec) => format!("terminal: {exec}"),
+            Method::Echo(echo) => format!("echo: {echo}"),
+            Method::Type(text) => format!("type: {text}"),
+            Method::Menu(exec) => format!("menu: {exec}"),
+            Method::RunPrivileged(exec) => format!("privileged: {exec}"),
+            Method::Pipe(exec) | Method::PipeInput(exec, _) => format!("pipe: {exec}"),
+            Method::RefreshSource(_) | Method::DisableSource(_) => continue,
+        };
+        lines.push(match entry.actions.len() {
+            1 => exec,
+            _ => format!("{} — {exec}", action.name),
+        });
+    }
+    lines.join("\n")
+}
+
+/// Retrieve Implementation of the Specified Frontend
+pub fn select(kind: &FrontendKind) -> Result<Box<dyn Frontend>> {
+    match kind {
+        FrontendKind::Webview => Ok(Box::new(Webview)),
+        FrontendKind::Native => native(),
+        FrontendKind::Tui => tui(),
+    }
+}
+
+/// Final Result of a Menu Session
+#[cfg_attr(not(any(feature = "native", feature = "tui")), allow(dead_code))]
+#[derive(Clone, PartialEq)]
+pub enum Outcome {
+    Execute(Vec<Action>),
+    Secret(Zeroizing<String>),
+    Exit,
+}
+
+/// Run Actions Keeping the Menu Open Right Away or Finish the Session w/ them
+#[cfg_attr(not(any(feature = "native", feature = "tui")), allow(dead_code))]
+fn outcome(actions: Vec<Action>, config: &Config) -> Option<Outcome> {
+    if actions.is_empty() {
+        return None;
+    }
+    if crate::exec::keeps_open(&actions, config) {
+        crate::exec::execute_open(&actions, config);
+        return None;
+    }
+    Some(Outcome::Execute(actions))
+}
+
+/// Search, Selection and Keybind State Shared by Non-Webview Frontends
+#[cfg_attr(not(any(feature = "native", feature = "tui")), allow(dead_code))]
+pub struct Session {
+    app: App,
+    state: InnerState,
+    levels: Vec<(Arc<[Entry]>, Arc<[Normalized]>)>,
+    viewport: usize,
+    title: String,
+}
+
+#[cfg_attr(not(any(feature = "native", feature = "tui")), allow(dead_code))]
+impl Session {
+    /// Spawn new Session for the Specified Application Context
+    pub fn new(app: App) -> Self {
+        Self {
+            state: InnerState::new(&app.config),
+            app,
+            levels: vec![],
+            viewport: 0,
+            title: String::new(),
+        }
+    }
+
+    /// Update Number of Visible Result Rows used to Tune the Page Size
+    #[inline]
+    pub fn set_viewport(&mut self, rows: usize) {
+        self.viewport = rows;
+    }
+
+    /// Record the First Visible Result Row Quick-Exec Keys Count from
+    #[inline]
+    pub fn set_top(&mut self, top: usize) {
+        self.state.set_top(top);
+    }
+
+    /// Retrieve the Quick-Exec Slot of the Result at the Given Index (if Any)
+    #[inline]
+    pub fn quick_slot(&self, index: usize) -> Option<usize> {
+        self.state.quick_slot(index)
+    }
+
+    /// Open the Nested Menu Printed by the Specified Command
+    fn enter_menu(&mut self, title: String, args: &[String]) {
+        let Some((entries, normalized)) = crate::exec::open_menu(&self.app.config, args) else {
+            return;
+        };
+        let entries = std::mem::replace(&mut self.app.entries, entries.into());
+        let normalized = std::mem::replace(&mut self.app.normalized, normalized.into());
+        self.levels.push((entries, normalized));
+        self.state.enter(title);
+    }
+
+    /// Return to the Parent Menu and Report if a Menu was Left
+    pub fn back(&mut self) -> bool {
+        let Some((entries, normalized)) = self.levels.pop() else {
+            return false;
+        };
+        self.app.entries = entries;
+        self.app.normalized = normalized;
+        self.state.back()
+    }
+
+    /// Retrieve Titles of the Nested Menus Entered so Far
+    #[inline]
+    pub fn breadcrumbs(&self) -> Vec<&str> {
+        self.state.breadcrumbs()
+    }
+
+    /// Check if the Keypress Returns to the Parent Menu
+    #[inline]
+    pub fn is_back(&self, key: &Code) -> bool {
+        self.state.is_back(key)
+    }
+
+    /// Collect Live Search Counters for the Current State
+    #[inline]
+    pub fn status(&self) -> Status {
+        status(&self.state, &self.app.config)
+    }
+
+    /// Retrieve the Window Title when it Changed since the Last Call
+    pub fn changed_title(&mut self) -> Option<String> {
+        let title = title(&self.app.config.window.title, &self.status());
+        if title == self.title {
+            return None;
+        }
+        self.title = title.clone();
+        Some(title)
+    }
+
+    /// Translate a Keypress into a Jump while Waiting for a Letter
+    #[inline]
+    pub fn letter_event(&self, mods: &Modifiers, key: &Code) -> Option<KeyEvent> {
+        self.state.letter_event(&self.app.config, mods, key)
+    }
+
+    /// Retrieve Configuration
+    #[inline]
+    pub fn config(&self) -> &Config {
+        &self.app.config
+    }
+
+    /// Retrieve Current Position State
+    #[inline]
+    pub fn position(&self) -> (usize, usize) {
+        self.state.position()
+    }
+
+    /// Retrieve Current Search String
+    #[inline]
+    pub fn search(&self) -> &str {
+        self.state.search()
+    }
+
+    /// Copy the Search into a Buffer to Edit w/o Reallocating
+    #[inline]
+    pub fn edit_buffer(&self) -> Zeroizing<String> {
+        self.state.edit_buffer()
+    }
+
+    /// Update Search String and Return if it was Accepted
+    pub fn set_search(&mut self, search: String) -> bool {
+        let changed = self.state.set_search(&self.app.config, search);
+        if changed {
+            crate::stream::search(&self.app.config, self.state.search());
+        }
+        changed
+    }
+
+    /// Update Current Position
+    #[inline]
+    pub fn set_position(&mut self, pos: usize, subpos: usize) {
+        self.state.set_position(pos, subpos)
+    }
+
+    /// Retrieve Currently Rendered Results
+    pub fn results(&mut self) -> Vec<&Entry> {
+        let app = &self.app;
+        self.state
+            .results(&app.config, &app.entries, &app.normalized)
+    }
+
+    /// Retrieve Currently Rendered Results w/ their Marked State
+    ///
+    /// The page size is tuned to the viewport once it is known.
+    pub fn marked_results(&mut self) -> Vec<(&Entry, bool)> {
+        let app = &self.app;
+        let started = Instant::now();
+        let results = self
+            .state
+            .results(&app.config, &app.entries, &app.normalized);
+        if self.viewport > 0 {
+            let elapsed = started.elapsed();
+            self.state
+                .tune(&app.config, self.viewport, results.len(), elapsed);
+        }
+        results
+            .into_iter()
+            .map(|e| (e, self.state.is_marked(e)))
+            .collect()
+    }
+
+    /// Retrieve Number of Matching Results Hidden by the Entry Limit
+    #[inline]
+    pub fn hidden(&self) -> usize {
+        self.state.hidden()
+    }
+
+    /// Retrieve Number of Characters Missing to Meet the Minimum Length
+    #[inline]
+    pub fn missing(&self) -> usize {
+        self.state.missing(&self.app.config)
+    }
+
+    /// Apply the Specified KeyEvent
+    pub fn handle(&mut self, event: &KeyEvent) -> Option<Outcome> {
+        if *event == KeyEvent::Paste {
+            let text = crate::clipboard::read()?;
+            let search = format!("{}{text}", self.search());
+            self.set_search(search);
+            return None;
+        }
+        if *event == KeyEvent::Back {
+            self.back();
+            return None;
+        }
+        let app = &self.app;
+        let results = self
+            .state
+            .results(&app.config, &app.entries, &app.normalized);
+        match event {
+            KeyEvent::Exit => Some(Outcome::Exit),
+            KeyEvent::Exec if !self.state.is_submittable() => None,
+            KeyEvent::Exec => {
+                if let Some(secret) = self.state.secret(&app.config, &results) {
+                    return Some(Outcome::Secret(secret));
+                }
+                let submitted = self.state.submit(&app.config, &results);
+                let submitted = submitted.and_then(|(plugin, query)| {
+                    let args = crate::exec::submit_args(&app.config, &plugin, &query)?;
+                    Some((query, args))
+                });
+                if let Some((query, args)) = submitted {
+                    self.enter_menu(query, &args);
+                    return None;
+                }
+                if let Some((title, args)) = self.state.submenu(&results) {
+                    self.enter_menu(title, &args);
+                    return None;
+                }
+                let selected = self.state.actions(&results);
+                crate::history::record(&selected, &app.config);
+                let actions = queue_sources(bind_actions(selected, self.state.query()));
+                outcome(actions, &app.config)
+            }
+            KeyEvent::ExecAlt(index) => {
+                let (entry, action) = self.state.alt_action(&results, *index)?;
+                let selected = vec![(entry.clone(), action.clone())];
+                crate::history::record(&selected, &app.config);
+                let actions = bind_actions(selected, self.state.query());
+                let actions = queue_sources(actions);
+                outcome(actions, &app.config)
+            }
+            KeyEvent::HideEntry => {
+                let entry = self.state.hide_entry(&results)?;
+                if app.config.persist_hidden {
+                    crate::blacklist::persist(entry);
+                }
+                None
+            }
+            KeyEvent::QuickExec(index) => {
+                let (entry, action) = self.state.quick_action(&results, *index)?;
+                let selected = vec![(entry.clone(), action.clone())];
+                crate::history::record(&selected, &app.config);
+                let actions = bind_actions(selected, self.state.query());
+                let actions = queue_sources(actions);
+                outcome(actions, &app.config)
+            }
+            _ => {
+                self.state.navigate(event, &app.config, &results);
+                None
+            }
+        }
+    }
+
+    /// Apply Remote-Control Commands Received since the Last Call
+    pub fn poll_controls(&mut self) -> Option<Outcome> {
+        for control in crate::instance::take() {
+            match control {
+                Control::Focus | Control::ToggleTop | Control::ToggleSticky => {}
+                Control::SetQuery(query) => {
+                    self.set_search(query);
+                }
+                Control::SelectIndex(index) => {
+                    let max = self.results().len().saturating_sub(1);
+                    self.set_position(std::cmp::min(index, max), 0);
+                }
+                Control::Close => return Some(Outcome::Exit),
+                Control::Refresh | Control::RefreshSource(_) => {
+                    let source = match &control {
+                        Control::RefreshSource(name) => Some(name.as_str()),
+                        _ => None,
+                    };
+                    if let Some((entries, normalized, scopes)) = crate::instance::reload(source) {
+                        self.app.entries = entries.into();
+                        self.app.normalized = normalized.into();
+                        self.levels.clear();
+                        self.state.set_scopes(scopes);
+                        self.state.refresh();
+                    }
+                }
+                Control::Update | Control::Streamed(..) | Control::Loaded(..) => {
+                    let loaded = match control {
+                        Control::Streamed(entries, normalized) => Some((entries, normalized, None)),
+                        Control::Loaded(entries, normalized, scopes) => {
+                            Some((entries, normalized, Some(scopes)))
+                        }
+                        _ => crate::instance::reload(None).map(|(e, n, s)| (e, n, Some(s))),
+                    };
+                    if let Some((entries, normalized, scopes)) = loaded {
+                        match self.levels.first_mut() {
+                            Some(level) => *level = (entries.into(), normalized.into()),
+                            None => {
+                                self.app.entries = entries.into();
+                                self.app.normalized = normalized.into();
+                            }
+                        }
+                        if let Some(scopes) = scopes {
+                            self.state.set_scopes(scopes);
+                        }
+                        self.state.update();
+                    }
+                }
+                Control::DisableSource(name) => self.state.disable_source(&name),
+            }
+        }
+        None
+    }
+
+    /// Apply the Keybind Matching the Specified Keypress (if Any)
+    pub fn press(&mut self, mods: &Modifiers, key: &Code) -> Option<Outcome> {
+        if self.state.is_back(key) {
+            self.back();
+            return None;
+        }
+        let event = match self.letter_event(mods, key) {
+            Some(event) => event,
+            None => key_event(&self.app.config.keybinds, mods, key)?,
+        };
+        self.handle(&event)
+    }
+
+    /// Complete Session by Executing the Action or Exiting
+    pub fn finish(&mut self, outcome: Outcome) {
+        self.state.wipe();
+        match outcome {
+            Outcome::Execute(actions) => execute_many(&actions, &self.app.config),
+            Outcome::Secret(secret) => echo_secret(secret),
+            Outcome::Exit => {
+                crate::signals::teardown();
+                std::process::exit(0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title() {
+        let mut status = Status {
+            matches: 3,
+            ..Default::default()
+        };
+        let template = "RMenu — {plugin} ({count})";
+        assert_eq!(title(template, &status), "RMenu — rmenu (3)");
+        status.plugin = Some("drun".to_owned());
+        assert_eq!(title(template, &status), "RMenu — drun (3)");
+    }
+
+    #[test]
+    fn test_prompt() {
+        let mut config = Config::default();
+        assert_eq!(prompt(&config), "");
+        config.search.prompt = Some("run".to_owned());
+        config.search.prompt_icon = Some("/usr/share/icons/search.svg".to_owned());
+        assert_eq!(prompt(&config), "run");
+        config.search.prompt_icon = Some("🔒".to_owned());
+        assert_eq!(prompt(&config), "🔒 run");
+    }
+}
@@ -0,0 +1,52 @@
+//! Window Geometry Remembered across Runs (per Monitor)
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{WindowPosition, WindowSize};
+
+/// Last Size and Position of the Window in Logical Pixels
+///
+/// The position is missing on compositors that never report it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Geometry {
+    pub size: WindowSize,
+    #[serde(default)]
+    pub position: Option<WindowPosition>,
+}
+
+/// Retrieve the Geometry File Stored Next to the Plugin Caches
+#[inline]
+fn geometry_path() -> PathBuf {
+    crate::paths::cache_dir().join("geometry.json")
+}
+
+/// Load the Geometries Saved for every Monitor
+fn load_all() -> BTreeMap<String, Geometry> {
+    fs::read(geometry_path())
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Load the Geometry Last Used on the Named Monitor
+pub fn load(monitor: &str) -> Option<Geometry> {
+    load_all().remove(monitor)
+}
+
+/// Save the Current Geometry for the Named Monitor
+pub fn save(monitor: &str, geometry: Geometry) {
+    let path = geometry_path();
+    let mut all = load_all();
+    all.insert(monitor.to_owned(), geometry);
+    let result = path
+        .parent()
+        .map(fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| fs::write(&path, serde_json::to_vec(&all)?));
+    if let Err(err) = result {
+        log::error!("failed to write window geometry {path:?}: {err:?}");
+    }
+}
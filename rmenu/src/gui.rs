@@ -1,17 +1,30 @@
 //! RMENU GUI Implementation using Dioxus
 #![allow(non_snake_case)]
 use std::fmt::Display;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use dioxus::prelude::*;
-use dioxus_html::input_data::keyboard_types::{Code, Modifiers};
+use dioxus_desktop::tao::dpi::{LogicalPosition, LogicalSize};
+use dioxus_desktop::tao::window::Fullscreen;
+use dioxus_html::input_data::keyboard_types::Key;
 use rmenu_plugin::Entry;
 
-use crate::config::Keybind;
-use crate::state::{AppState, KeyEvent};
+use crate::config::{KeyMatch, Pagination, WindowConfig, WindowPosition, WindowSize};
+use crate::geometry::Geometry;
+use crate::instance::Control;
+use crate::search::Normalized;
+use crate::state::{char_code, key_event, AppState, KeyEvent, Nested};
 use crate::{App, DEFAULT_CSS_CONTENT};
 
+/// height of the fixed search bar in the default stylesheet
+static NAVBAR_HEIGHT: f64 = 60.0;
+/// approximate height of a single result row
+static ROW_HEIGHT: f64 = 32.0;
+
 /// spawn and run the app on the configured platform
-pub fn run(app: App) {
+pub fn run(mut app: App) {
+    crate::font::resolve(&mut app.config.appearance);
     let theme = match app.config.window.dark_mode {
         Some(dark) => match dark {
             true => Some(dioxus_desktop::tao::window::Theme::Dark),
@@ -19,29 +32,65 @@ pub fn run(app: App) {
         },
         None => None,
     };
+    let (size, position) = (app.config.window.size, app.config.window.position);
+    // remembered geometries are restored before the window is shown
+    let visible = !app.config.window.hide_until_ready && !remembers_geometry(&app.config.window);
     let builder = dioxus_desktop::WindowBuilder::new()
-        .with_title(app.config.window.title.clone())
-        .with_inner_size(app.config.window.size)
-        .with_position(app.config.window.position)
+        .with_title(crate::frontend::initial_title(&app))
+        .with_inner_size(LogicalSize::new(size.width, size.height))
+        .with_position(LogicalPosition::new(position.x, position.y))
         .with_focused(app.config.window.focus)
         .with_decorations(app.config.window.decorate)
         .with_transparent(app.config.window.transparent)
         .with_always_on_top(app.config.window.always_top)
-        .with_fullscreen(app.config.window.get_fullscreen())
+        .with_visible_on_all_workspaces(app.config.window.sticky)
+        .with_visible(visible)
+        .with_fullscreen(get_fullscreen(&app.config.window))
         .with_theme(theme);
     let config = dioxus_desktop::Config::new().with_window(builder);
     dioxus_desktop::launch_with_props(App, app, config);
 }
 
+/// Retrieve Desktop Compatabible Fullscreen Settings
+fn get_fullscreen(window: &WindowConfig) -> Option<Fullscreen> {
+    window.fullscreen.and_then(|fs| match fs {
+        true => Some(Fullscreen::Borderless(None)),
+        false => None,
+    })
+}
+
 #[derive(PartialEq, Props)]
 struct GEntry<'a> {
     pos: usize,
     subpos: usize,
     index: usize,
-    entry: &'a Entry,
     state: AppState<'a>,
 }
 
+/// percent-encode a filepath segment-wise for use in a `file://` uri (RFC 8089)
+fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// convert entry filepaths into uris for drag-and-drop
+#[inline]
+fn drag_uri(uri: Option<&String>) -> String {
+    match uri {
+        Some(path) if path.starts_with('/') => format!("file://{}", encode_path(path)),
+        Some(uri) => uri.to_owned(),
+        None => String::new(),
+    }
+}
+
 #[inline]
 fn render_comment(comment: Option<&String>) -> &str {
     comment.map(|s| s.as_str()).unwrap_or("")
@@ -52,16 +101,19 @@ fn render_image<'a, T>(
     cx: Scope<'a, T>,
     image: Option<&String>,
     alt: Option<&String>,
+    recolor: bool,
 ) -> Element<'a> {
-    if let Some(img) = image {
-        if img.ends_with(".svg") {
-            if let Some(content) = crate::image::convert_svg(img.to_owned()) {
-                return cx.render(rsx! { img { class: "image", src: "{content}" } });
-            }
-        }
-        if crate::image::image_exists(img.to_owned()) {
-            return cx.render(rsx! { img { class: "image", src: "{img}" } });
+    if let Some(icon) = image.and_then(|img| crate::image::resolve(img)) {
+        let src = &icon.src;
+        if icon.symbolic && recolor {
+            return cx.render(rsx! {
+                div {
+                    class: "image symbolic",
+                    style: "-webkit-mask-image: url('{src}'); mask-image: url('{src}');",
+                }
+            });
         }
+        return cx.render(rsx! { img { class: "image", src: "{src}" } });
     }
     let alt = alt.map(|s| s.as_str()).unwrap_or_else(|| "?");
     return cx.render(rsx! { div { class: "icon_alt", dangerous_inner_html: "{alt}" } });
@@ -69,6 +121,8 @@ fn render_image<'a, T>(
 
 /// render a single result entry w/ the given information
 fn TableEntry<'a>(cx: Scope<'a, GEntry<'a>>) -> Element<'a> {
+    // lookup entry by index in the shared results
+    let entry = cx.props.state.result(cx.props.index)?;
     // build css classes for result and actions (if nessesary)
     let main_select = cx.props.index == cx.props.pos;
     let action_select = main_select && cx.props.subpos > 0;
@@ -76,7 +130,7 @@ fn TableEntry<'a>(cx: Scope<'a, GEntry<'a>>) -> Element<'a> {
         true => "active",
         false => "",
     };
-    let multi_classes = match cx.props.entry.actions.len() > 1 {
+    let multi_classes = match entry.actions.len() > 1 {
         true => "submenu",
         false => "",
     };
@@ -84,10 +138,38 @@ fn TableEntry<'a>(cx: Scope<'a, GEntry<'a>>) -> Element<'a> {
         true => "selected",
         false => "",
     };
-    // build sub-actions if present
-    let actions = cx
+    let marked_classes = match cx.props.state.is_marked(entry) {
+        true => "marked",
+        false => "",
+    };
+    // show shortcut badges while the hint modifier is held
+    let keybinds = &cx.props.state.config().keybinds;
+    let hinting = cx.props.state.hinting();
+    let quick_hint = cx
         .props
-        .entry
+        .state
+        .quick_slot(cx.props.index)
+        .and_then(|slot| keybinds.quick_hint(slot))
+        .filter(|_| hinting)
+        .map(|hint| cx.render(rsx! { span { class: "shortcut", "{hint}" } }));
+    let draggable = entry.uri.is_some();
+    let uri = drag_uri(entry.uri.as_ref());
+    let source = entry.source.as_deref().unwrap_or_default();
+    let display = &cx.props.state.config().display;
+    let fallback = cx.props.state.config().icon_fallback.as_ref();
+    let recolor = cx.props.state.config().recolor_icons;
+    let name = display.name(&entry.name);
+    let comment = display.comment(render_comment(entry.comment.as_ref()));
+    let layout_classes = match display.two_line {
+        true => "two-line",
+        false => "",
+    };
+    let tooltip = match cx.props.state.config().use_tooltips {
+        true => crate::frontend::tooltip(entry),
+        false => String::new(),
+    };
+    // build sub-actions if present
+    let actions = entry
         .actions
         .iter()
         .skip(1)
@@ -97,11 +179,23 @@ fn TableEntry<'a>(cx: Scope<'a, GEntry<'a>>) -> Element<'a> {
                 true => "selected",
                 false => "",
             };
+            let action_hint = keybinds
+                .action_hint(idx + 1)
+                .filter(|_| hinting)
+                .map(|hint| cx.render(rsx! { span { class: "shortcut", "{hint}" } }));
             cx.render(rsx! {
                 div {
                     class: "action {act_class}",
                     onclick: move |_| cx.props.state.set_position(cx.props.index, idx + 1),
                     ondblclick: |_| cx.props.state.set_event(KeyEvent::Exec),
+                    if action.icon.is_some() {
+                        cx.render(rsx! {
+                            div {
+                                class: "action-icon",
+                                render_image(cx, action.icon.as_ref(), fallback, recolor)
+                            }
+                        })
+                    }
                     div {
                         class: "action-name",
                         dangerous_inner_html: "{action.name}"
@@ -110,6 +204,7 @@ fn TableEntry<'a>(cx: Scope<'a, GEntry<'a>>) -> Element<'a> {
                         class: "action-comment",
                         render_comment(action.comment.as_ref())
                     }
+                    action_hint
                 }
             })
         });
@@ -118,7 +213,11 @@ fn TableEntry<'a>(cx: Scope<'a, GEntry<'a>>) -> Element<'a> {
             class: "result-entry",
             div {
                 id: "result-{cx.props.index}",
-                class: "result {result_classes} {multi_classes}",
+                class: "result {result_classes} {multi_classes} {marked_classes} {layout_classes}",
+                draggable: "{draggable}",
+                "data-uri": "{uri}",
+                "data-source": "{source}",
+                title: "{tooltip}",
                 // onmouseenter: |_| cx.props.state.set_position(cx.props.index, 0),
                 onclick: |_| cx.props.state.set_position(cx.props.index, 0),
                 ondblclick: |_| cx.props.state.set_event(KeyEvent::Exec),
@@ -126,28 +225,47 @@ fn TableEntry<'a>(cx: Scope<'a, GEntry<'a>>) -> Element<'a> {
                     cx.render(rsx! {
                         div {
                             class: "icon",
-                            render_image(cx, cx.props.entry.icon.as_ref(), cx.props.entry.icon_alt.as_ref())
+                            render_image(
+                                cx,
+                                entry.icon.as_ref(),
+                                entry.icon_alt.as_ref().or(fallback),
+                                recolor
+                            )
                         }
                     })
                 }
-                match cx.props.state.config().use_comments {
-                    true => cx.render(rsx! {
+                match (cx.props.state.config().use_comments, display.two_line) {
+                    (true, true) => cx.render(rsx! {
+                        div {
+                            class: "details",
+                            div {
+                                class: "name",
+                                dangerous_inner_html: "{name}"
+                            }
+                            div {
+                                class: "comment",
+                                dangerous_inner_html: "{comment}"
+                            }
+                        }
+                    }),
+                    (true, false) => cx.render(rsx! {
                         div {
                             class: "name",
-                            dangerous_inner_html: "{cx.props.entry.name}"
+                            dangerous_inner_html: "{name}"
                         }
                         div {
                             class: "comment",
-                            dangerous_inner_html: render_comment(cx.props.entry.comment.as_ref())
+                            dangerous_inner_html: "{comment}"
                         }
                     }),
-                    false => cx.render(rsx! {
+                    (false, _) => cx.render(rsx! {
                         div {
                             class: "entry",
-                            dangerous_inner_html: "{cx.props.entry.name}"
+                            dangerous_inner_html: "{name}"
                         }
                     })
                 }
+                quick_hint
             }
             div {
                 id: "result-{cx.props.index}-actions",
@@ -165,114 +283,670 @@ fn focus<T>(cx: Scope<T>) {
     let _ = eval(js);
 }
 
-/// check if the current inputs match any of the given keybindings
-#[inline]
-fn matches(bind: &Vec<Keybind>, mods: &Modifiers, key: &Code) -> bool {
-    bind.iter().any(|b| mods.contains(b.mods) && &b.key == key)
-}
-
 /// retrieve string value for display-capable enum
 #[inline]
 fn get_str<T: Display>(item: Option<T>) -> String {
     item.map(|i| i.to_string()).unwrap_or_else(String::new)
 }
 
+/// estimate number of result rows visible in the window
+#[inline]
+fn viewport_rows(window: &dioxus_desktop::DesktopContext) -> usize {
+    let size = window.inner_size().to_logical::<f64>(window.scale_factor());
+    ((size.height - NAVBAR_HEIGHT) / ROW_HEIGHT).max(1.0) as usize
+}
+
+/// input-method composition in progress in the search (if any)
+#[derive(Default)]
+struct Composition {
+    active: bool,
+    ended: Option<Instant>,
+}
+
+impl Composition {
+    /// check if keypresses currently belong to the input-method
+    ///
+    /// webkit reports the end of a composition before the keypress confirming
+    /// it, so keypresses right after the composition ended are included as well
+    fn composing(&self) -> bool {
+        let recent = self.ended.map(|t| t.elapsed() < Duration::from_millis(50));
+        self.active || recent.unwrap_or(false)
+    }
+}
+
+/// remote-control commands and reloaded entries awaiting the next render
+#[derive(Default)]
+struct Remote {
+    commands: Vec<Control>,
+    reloaded: Option<(Arc<[Entry]>, Arc<[Normalized]>)>,
+    updated: Option<(Arc<[Entry]>, Arc<[Normalized]>)>,
+    /// scopes of the reloaded or updated entries
+    scopes: Option<Vec<crate::config::Scope>>,
+}
+
+/// poll for commands forwarded by newer rmenu instances or external tools
+///
+/// window toggles are applied right away, starting from the configured settings
+fn use_instance_commands<'a, T>(cx: Scope<'a, T>, config: &WindowConfig) -> &'a UseRef<Remote> {
+    let window = dioxus_desktop::use_window(cx);
+    let remote = use_ref(cx, Remote::default);
+    let (mut top, mut sticky) = (config.always_top, config.sticky);
+    use_future(cx, (), |_| {
+        let window = window.clone();
+        let remote = remote.clone();
+        async move {
+            loop {
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                for command in crate::instance::take() {
+                    match command {
+                        Control::Focus => {
+                            window.set_minimized(false);
+                            window.set_visible(true);
+                            window.set_focus();
+                        }
+                        Control::ToggleTop => {
+                            top = !top;
+                            window.set_always_on_top(top);
+                        }
+                        Control::ToggleSticky => {
+                            sticky = !sticky;
+                            window.set_visible_on_all_workspaces(sticky);
+                        }
+                        Control::Refresh | Control::RefreshSource(_) => {
+                            let source = match command {
+                                Control::RefreshSource(name) => Some(name),
+                                _ => None,
+                            };
+                            let reload = tokio::task::spawn_blocking(move || {
+                                crate::instance::reload(source.as_deref())
+                            });
+                            if let Ok(Some((entries, normalized, scopes))) = reload.await {
+                                let mut remote = remote.write();
+                                remote.reloaded.replace((entries.into(), normalized.into()));
+                                remote.scopes.replace(scopes);
+                            }
+                        }
+                        Control::Update => {
+                            let reload =
+                                tokio::task::spawn_blocking(|| crate::instance::reload(None));
+                            if let Ok(Some((entries, normalized, scopes))) = reload.await {
+                                let mut remote = remote.write();
+                                remote.updated.replace((entries.into(), normalized.into()));
+                                remote.scopes.replace(scopes);
+                            }
+                        }
+                        Control::Streamed(entries, normalized) => {
+                            remote
+                                .write()
+                                .updated
+                                .replace((entries.into(), normalized.into()));
+                        }
+                        Control::Loaded(entries, normalized, scopes) => {
+                            let mut remote = remote.write();
+                            remote.updated.replace((entries.into(), normalized.into()));
+                            remote.scopes.replace(scopes);
+                        }
+                        command => remote.write().commands.push(command),
+                    }
+                }
+            }
+        }
+    });
+    remote
+}
+
+/// show the initially hidden window once the first render was painted
+///
+/// the window is shown after a second regardless, should the webview never answer
+fn show_when_ready<T>(cx: Scope<T>, config: &WindowConfig) {
+    let window = dioxus_desktop::use_window(cx);
+    let eval = use_eval(cx);
+    let (hidden, focus) = (config.hide_until_ready, config.focus);
+    use_future(cx, (), |_| {
+        let window = window.clone();
+        let eval = eval.clone();
+        async move {
+            if !hidden {
+                return;
+            }
+            let js = "requestAnimationFrame(() => requestAnimationFrame(() => dioxus.send(true)))";
+            if let Ok(painted) = eval(js) {
+                let wait = std::time::Duration::from_secs(1);
+                let _ = tokio::time::timeout(wait, painted.recv()).await;
+            }
+            window.set_visible(true);
+            if focus {
+                window.set_focus();
+            }
+        }
+    });
+}
+
+/// retrieve the name of the monitor currently showing the window
+fn monitor_name(window: &dioxus_desktop::DesktopContext) -> String {
+    window
+        .current_monitor()
+        .and_then(|m| m.name())
+        .unwrap_or_default()
+}
+
+/// check if the window geometry is restored from and saved for later runs
+#[inline]
+fn remembers_geometry(config: &WindowConfig) -> bool {
+    config.remember_geometry && !config.fullscreen.unwrap_or(false)
+}
+
+/// restore and persist the window size and position per monitor when configured
+///
+/// the window starts hidden so it is only shown once moved into place. the
+/// geometry is saved whenever it changes, since rmenu usually exits by
+/// replacing itself w/ the executed action rather than closing the window
+fn remember_geometry<T>(cx: Scope<T>, config: &WindowConfig) {
+    let window = dioxus_desktop::use_window(cx);
+    let (remember, hidden, focus) = (
+        remembers_geometry(config),
+        config.hide_until_ready,
+        config.focus,
+    );
+    use_future(cx, (), |_| {
+        let window = window.clone();
+        async move {
+            if !remember {
+                return;
+            }
+            let mut last = crate::geometry::load(&monitor_name(&window));
+            if let Some(geometry) = last {
+                let size = geometry.size;
+                window.set_inner_size(LogicalSize::new(size.width, size.height));
+                if let Some(position) = geometry.position {
+                    window.set_outer_position(LogicalPosition::new(position.x, position.y));
+                }
+            }
+            // windows kept hidden until ready are shown after the first paint
+            if !hidden {
+                window.set_visible(true);
+                if focus {
+                    window.set_focus();
+                }
+            }
+            loop {
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                let scale = window.scale_factor();
+                let size = window.inner_size().to_logical::<f64>(scale);
+                // some compositors (e.g. wayland) never report window positions
+                let position = window.outer_position().ok().map(|position| {
+                    let position = position.to_logical::<f64>(scale);
+                    WindowPosition {
+                        x: position.x,
+                        y: position.y,
+                    }
+                });
+                let current = Geometry {
+                    size: WindowSize {
+                        width: size.width,
+                        height: size.height,
+                    },
+                    position,
+                };
+                if last != Some(current) {
+                    crate::geometry::save(&monitor_name(&window), current);
+                    last = Some(current);
+                }
+            }
+        }
+    });
+}
+
+/// load a nested menu (or the response to a submitted query) in the background
+///
+/// further submissions are ignored until the menu was loaded
+fn load_nested<T, F>(
+    cx: Scope<T>,
+    nested: &UseRef<Option<Nested>>,
+    loading: &UseRef<bool>,
+    title: String,
+    load: F,
+) where
+    F: FnOnce() -> Option<(Vec<Entry>, Vec<Normalized>)> + Send + 'static,
+{
+    if *loading.read() {
+        return;
+    }
+    loading.set(true);
+    let nested = nested.clone();
+    let loading = loading.clone();
+    cx.spawn(async move {
+        let menu = tokio::task::spawn_blocking(load).await;
+        if let Ok(Some((entries, normalized))) = menu {
+            nested.set(Some(Nested::Enter(
+                title,
+                entries.into(),
+                normalized.into(),
+            )));
+        }
+        loading.set(false);
+    });
+}
+
+/// register handler loading the next page when scrolling near the end
+#[inline]
+fn infinite_scroll<T>(cx: Scope<T>) {
+    let eval = use_eval(cx);
+    let js = r#"
+        if (!window.rmenuScroll) {
+            window.rmenuScroll = true;
+            document.addEventListener('scroll', (e) => {
+                const r = e.target;
+                if (r.id === 'results' && r.scrollTop + 1.5 * r.clientHeight >= r.scrollHeight) {
+                    document.getElementById('load-page')?.click();
+                }
+            }, true);
+        }
+    "#;
+    let _ = eval(js);
+}
+
+/// register handler reporting the first visible result row when scrolling
+///
+/// the index is passed through the hidden `scroll-top` input, so quick-exec
+/// badges and keys follow the rows on screen
+#[inline]
+fn track_top<T>(cx: Scope<T>) {
+    let eval = use_eval(cx);
+    let js = r#"
+        if (!window.rmenuTop) {
+            window.rmenuTop = true;
+            document.addEventListener('scroll', (e) => {
+                const r = e.target;
+                if (r.id !== 'results') {
+                    return;
+                }
+                const nav = document.getElementById('navbar')?.getBoundingClientRect().bottom ?? 0;
+                const edge = Math.max(r.getBoundingClientRect().top, nav);
+                const row = [...r.querySelectorAll('[id^=result-]:not([id$=-actions])')]
+                    .find((el) => el.getBoundingClientRect().bottom > edge);
+                const top = document.getElementById('scroll-top');
+                const index = row ? row.id.slice('result-'.length) : '0';
+                if (top && top.value !== index) {
+                    top.value = index;
+                    top.dispatchEvent(new Event('input', { bubbles: true }));
+                }
+            }, true);
+        }
+    "#;
+    let _ = eval(js);
+}
+
+/// register handler attaching entry uris to drag events
+#[inline]
+fn dragdrop<T>(cx: Scope<T>) {
+    let eval = use_eval(cx);
+    let js = r#"
+        if (!window.rmenuDrag) {
+            window.rmenuDrag = true;
+            document.addEventListener('dragstart', (e) => {
+                const uri = e.target.closest('[data-uri]')?.dataset.uri;
+                if (uri) {
+                    e.dataTransfer.setData('text/uri-list', uri);
+                    e.dataTransfer.setData('text/plain', uri);
+                }
+            });
+        }
+    "#;
+    let _ = eval(js);
+}
+
 /// main application function/loop
 fn App<'a>(cx: Scope<App>) -> Element {
     let mut state = AppState::new(cx, cx.props);
 
     // always ensure focus
     focus(cx);
+    show_when_ready(cx, &cx.props.config.window);
+    remember_geometry(cx, &cx.props.config.window);
+    dragdrop(cx);
+    infinite_scroll(cx);
+    track_top(cx);
+    let remote = use_instance_commands(cx, &cx.props.config.window);
+    let window = dioxus_desktop::use_window(cx);
+
+    // swap in refreshed entries or nested menus before generating results
+    let levels = cx.use_hook(|| {
+        vec![(
+            Arc::clone(&cx.props.entries),
+            Arc::clone(&cx.props.normalized),
+        )]
+    });
+    if let Some(scopes) = remote.write_silent().scopes.take() {
+        state.set_scopes(scopes);
+    }
+    if let Some(reloaded) = remote.write_silent().reloaded.take() {
+        *levels = vec![reloaded];
+        state.refresh();
+    }
+    if let Some(updated) = remote.write_silent().updated.take() {
+        levels[0] = updated;
+        state.update();
+    }
+    let nested = use_ref(cx, || None::<Nested>);
+    match nested.write_silent().take() {
+        Some(Nested::Enter(title, entries, normalized)) => {
+            levels.push((entries, normalized));
+            state.enter(title);
+        }
+        Some(Nested::Back) if levels.len() > 1 => {
+            levels.pop();
+            state.back();
+        }
+        _ => {}
+    }
+    let (entries, normalized) = levels.last().expect("no menu loaded");
 
-    // log current position
-    let search = state.search();
+    // retrieve current search and position
+    // masked searches stay in the input instead of being copied into the vdom
+    let search = match cx.props.config.search.password {
+        true => String::new(),
+        false => state.search(),
+    };
     let (pos, subpos) = state.position();
-    log::debug!("search: {search:?}, pos: {pos}, {subpos}");
+    let prevent = if state.jumping() { "onkeydown" } else { "" };
 
     // generate state tracker instances
-    let results = state.results(&cx.props.entries);
+    let results = state.results(entries, normalized, viewport_rows(window));
     let k_updater = state.partial_copy();
     let s_updater = state.partial_copy();
 
     // build keyboard actions event handler
     let keybinds = &cx.props.config.keybinds;
+    let hint_mods = keybinds.hint_mods();
+    let h_updater = state.partial_copy();
+    let hint_controls = move |e: KeyboardEvent| {
+        let held = hint_mods.map(|m| e.modifiers().contains(m));
+        h_updater.set_hinting(held.unwrap_or(false));
+    };
+    let composition = use_ref(cx, Composition::default);
+    let composition_start = move |_: CompositionEvent| {
+        composition.write_silent().active = true;
+    };
+    let composition_end = move |_: CompositionEvent| {
+        let mut composition = composition.write_silent();
+        composition.active = false;
+        composition.ended = Some(Instant::now());
+    };
     let keyboard_controls = move |e: KeyboardEvent| {
-        let code = e.code();
+        // let enter confirm ime compositions w/o executing the selected entry
+        let ime = e.key() == Key::Process || composition.read().composing();
+        if keybinds.ignore_composing && ime {
+            return;
+        }
+        let code = match keybinds.match_by {
+            KeyMatch::Code => e.code(),
+            KeyMatch::Key => char_code(&e.key()).unwrap_or_else(|| e.code()),
+        };
         let mods = e.modifiers();
-        if matches(&keybinds.exec, &mods, &code) {
-            k_updater.set_event(KeyEvent::Exec);
-        } else if matches(&keybinds.exit, &mods, &code) {
-            k_updater.set_event(KeyEvent::Exit);
-        } else if matches(&keybinds.move_next, &mods, &code) {
-            k_updater.set_event(KeyEvent::MoveNext);
-        } else if matches(&keybinds.move_prev, &mods, &code) {
-            k_updater.set_event(KeyEvent::MovePrev);
-        } else if matches(&keybinds.open_menu, &mods, &code) {
-            k_updater.set_event(KeyEvent::OpenMenu);
-        } else if matches(&keybinds.close_menu, &mods, &code) {
-            k_updater.set_event(KeyEvent::CloseMenu);
-        } else if matches(&keybinds.jump_next, &mods, &code) {
-            k_updater.set_event(KeyEvent::JumpNext)
-        } else if matches(&keybinds.jump_prev, &mods, &code) {
-            k_updater.set_event(KeyEvent::JumpPrev)
+        k_updater.set_hinting(hint_mods.map(|m| mods.contains(m)).unwrap_or(false));
+        if let Some(event) = k_updater.letter_event(&mods, &code) {
+            k_updater.set_event(event);
+        } else if k_updater.is_back(&code) {
+            k_updater.set_event(KeyEvent::Back);
+        } else if let Some(event) = key_event(keybinds, &mods, &code) {
+            k_updater.set_event(event);
         }
     };
 
-    // handle keyboard events
-    state.handle_events(cx);
+    // handle keyboard events and remote-control commands
+    let loading = use_ref(cx, || false);
+    match state.handle_events(cx) {
+        Some(Nested::Submit(query, args)) => load_nested(cx, nested, loading, query, move || {
+            crate::exec::load_menu(&args)
+        }),
+        Some(Nested::Open(title, menu)) => {
+            load_nested(cx, nested, loading, title, move || menu.load())
+        }
+        Some(menu) => nested.set(Some(menu)),
+        None => {}
+    }
+    let commands: Vec<Control> = remote.write_silent().commands.drain(..).collect();
+    for command in commands {
+        state.control(cx, command);
+    }
 
     // render results objects
-    let rendered_results = results.iter().enumerate().map(|(i, e)| {
+    let rendered_results = (0..results.len()).map(|i| {
         let state = state.partial_copy();
         cx.render(rsx! {
             TableEntry{
                 pos:    pos,
                 subpos: subpos,
                 index:  i,
-                entry:  e,
                 state: state,
             }
         })
     });
 
+    // render footer when results are hidden by the entry limit
+    let hidden = state.hidden();
+    let footer = (hidden > 0).then(|| {
+        cx.render(rsx! {
+            div {
+                id: "footer",
+                class: "footer",
+                "{hidden} more results hidden — refine your search"
+            }
+        })
+    });
+
+    // render loading state while a submitted query is answered
+    let is_loading = *loading.read();
+    let content_class = match is_loading {
+        true => "content loading",
+        false => "content",
+    };
+    let spinner = is_loading.then(|| {
+        cx.render(rsx! {
+            div {
+                id: "loading",
+                class: "hint loading",
+                "Loading…"
+            }
+        })
+    });
+
+    // render page controls unless pages are loaded automatically
+    let pagination = &cx.props.config.pagination;
+    let (page, pages) = state.pages();
+    let pager = (*pagination != Pagination::Auto && pages > 1).then(|| {
+        let prev = state.partial_copy();
+        let next = state.partial_copy();
+        cx.render(rsx! {
+            div {
+                id: "pager",
+                class: "pager",
+                button {
+                    class: "prev",
+                    onclick: move |_| prev.set_event(KeyEvent::PrevPage),
+                    "‹"
+                }
+                span { class: "page", "{page} / {pages}" }
+                button {
+                    class: "next",
+                    onclick: move |_| next.set_event(KeyEvent::NextPage),
+                    "›"
+                }
+            }
+        })
+    });
+    let top = state.partial_copy();
+    let load = state.partial_copy();
+    let load_page = (*pagination == Pagination::Scroll).then(|| {
+        cx.render(rsx! {
+            div {
+                id: "load-page",
+                onclick: move |_| load.load_page(),
+            }
+        })
+    });
+
+    // render hint while the search is below the minimum length
+    let missing = state.missing();
+    let hint = (missing > 0).then(|| {
+        let min = cx.props.config.search.min_length.unwrap_or_default();
+        cx.render(rsx! {
+            div {
+                id: "hint",
+                class: "hint min-length",
+                "type at least {min} characters"
+            }
+        })
+    });
+
+    // render titles of the entered nested menus
+    let crumbs = state.breadcrumbs();
+    let breadcrumbs = (!crumbs.is_empty()).then(|| {
+        let trail = crumbs.join(" › ");
+        cx.render(rsx! {
+            div {
+                id: "breadcrumbs",
+                class: "breadcrumbs",
+                "{trail}"
+            }
+        })
+    });
+
+    // expose live counters to themes and external status bars
+    let status = state.status();
+    let query_length = status.query_length;
+    let matches = status.matches;
+    let plugin = status.plugin.clone().unwrap_or_default();
+    let title = crate::frontend::title(&cx.props.config.window.title, &status);
+    let last_title = cx.use_hook(String::new);
+    if *last_title != title {
+        window.set_title(&title);
+        *last_title = title;
+    }
+    crate::instance::publish(status);
+
+    // render prompt label and icon before the search
+    let search_cfg = &cx.props.config.search;
+    let prompt_class = match search_cfg.password {
+        true => "prompt password",
+        false => "prompt",
+    };
+    let prompt_icon = search_cfg
+        .prompt_icon
+        .as_ref()
+        .map(|icon| match icon.contains('/') {
+            true => render_image(cx, Some(icon), None, cx.props.config.recolor_icons),
+            false => cx.render(rsx! { div { class: "icon_alt", "{icon}" } }),
+        });
+    let prompt_label = search_cfg
+        .prompt
+        .as_ref()
+        .map(|label| cx.render(rsx! { span { class: "prompt-label", "{label}" } }));
+    let prompt = (prompt_icon.is_some() || prompt_label.is_some()).then(|| {
+        cx.render(rsx! {
+            div {
+                id: "prompt",
+                class: "{prompt_class}",
+                prompt_icon
+                prompt_label
+            }
+        })
+    });
+
+    // classify search length for theming
+    let at_max = cx
+        .props
+        .config
+        .search
+        .max_length
+        .map(|max| state.search_length() >= max)
+        .unwrap_or(false);
+    let length_class = match (missing > 0, at_max) {
+        (true, _) => "too-short",
+        (false, true) => "at-max",
+        _ => "",
+    };
+
     // get input settings
+    let input_type = match cx.props.config.search.password {
+        true => "password",
+        false => "text",
+    };
     let minlen = get_str(cx.props.config.search.min_length.as_ref());
     let maxlen = get_str(cx.props.config.search.max_length.as_ref());
     let placeholder = get_str(cx.props.config.search.placeholder.as_ref());
 
+    // expose configured fonts to the stylesheets
+    let font_vars = crate::font::css_vars(&cx.props.config.appearance);
+
     // complete final rendering
     cx.render(rsx! {
         style { DEFAULT_CSS_CONTENT }
+        style { "{font_vars}" }
         style { "{cx.props.theme}" }
         style { "{cx.props.css}" }
         div {
             id: "content",
-            class: "content",
+            class: "{content_class}",
+            "data-query-length": "{query_length}",
+            "data-matches": "{matches}",
+            "data-plugin": "{plugin}",
             div {
                 id: "navbar",
                 class: "navbar",
+                breadcrumbs
+                prompt
+                div {
+                    id: "status",
+                    class: "status",
+                    span { class: "query-length", "{query_length}" }
+                    span { class: "matches", "{matches}" }
+                    span { class: "plugin", "{plugin}" }
+                }
                 match cx.props.config.search.restrict.as_ref() {
+                    _ if cx.props.config.search.disable => cx.render(rsx! {
+                        div {
+                            id: "search",
+                            class: "disabled",
+                            tabindex: "0",
+                            onkeydown: keyboard_controls,
+                            onkeyup: hint_controls,
+                        }
+                    }),
                     Some(pattern) => cx.render(rsx! {
                         input {
                             id: "search",
+                            r#type: "{input_type}",
+                            class: "{length_class}",
                             value: "{search}",
                             pattern: "{pattern}",
                             minlength: "{minlen}",
                             maxlength: "{maxlen}",
                             placeholder: "{placeholder}",
                             oninput: move |e| s_updater.set_search(cx, e.value.clone()),
+                            oncompositionstart: composition_start,
+                            oncompositionend: composition_end,
+                            prevent_default: "{prevent}",
                             onkeydown: keyboard_controls,
+                            onkeyup: hint_controls,
                         }
                     }),
                     None => cx.render(rsx! {
                         input {
                             id: "search",
+                            r#type: "{input_type}",
+                            class: "{length_class}",
                             value: "{search}",
                             minlength: "{minlen}",
                             maxlength: "{maxlen}",
                             placeholder: "{placeholder}",
                             oninput: move |e| s_updater.set_search(cx, e.value.clone()),
+                            oncompositionstart: composition_start,
+                            oncompositionend: composition_end,
+                            prevent_default: "{prevent}",
                             onkeydown: keyboard_controls,
+                            onkeyup: hint_controls,
                         }
                     })
                 }
@@ -280,8 +954,35 @@ fn App<'a>(cx: Scope<App>) -> Element {
             div {
                 id: "results",
                 class: "results",
+                spinner
+                hint
                 rendered_results.into_iter()
+                footer
+                load_page
+                input {
+                    id: "scroll-top",
+                    r#type: "hidden",
+                    oninput: move |e| top.set_top(e.value.parse().unwrap_or_default()),
+                }
             }
+            pager
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drag_uri() {
+        let path = "/home/user/my notes/#1 100%.txt".to_owned();
+        let uri = drag_uri(Some(&path));
+        assert_eq!(uri, "file:///home/user/my%20notes/%231%20100%25.txt");
+        let path = "/tmp/caf\u{e9}".to_owned();
+        assert_eq!(drag_uri(Some(&path)), "file:///tmp/caf%C3%A9");
+        let web = "https://example.com/a b".to_owned();
+        assert_eq!(drag_uri(Some(&web)), web);
+        assert_eq!(drag_uri(None), "");
+    }
+}
@@ -0,0 +1,187 @@
+//! Persisted Usage History Ranking Entries by Frecency
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rmenu_plugin::{Action, Entry};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::search::Normalized;
+
+/// Time after which the Weight of a Use is Halved (One Week)
+static HALF_LIFE: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+/// Time after which Unused Entries are Forgotten (90 Days)
+static MAX_AGE: u64 = 90 * 24 * 60 * 60;
+
+/// Number and Time of Executions of a Single Entry
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Usage {
+    pub count: u64,
+    pub last: u64,
+}
+
+impl Usage {
+    /// Score Frequency Decayed by the Time since the Last Use
+    pub fn frecency(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.last) as f64;
+        self.count as f64 * 0.5f64.powf(age / HALF_LIFE)
+    }
+}
+
+/// Retrieve the History File Stored Next to the Plugin Caches
+#[inline]
+fn history_path() -> PathBuf {
+    crate::paths::cache_dir().join("history.json")
+}
+
+#[inline]
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Identify an Entry across Sessions by its Plugin and Name
+fn key(entry: &Entry) -> String {
+    match entry.source.as_ref() {
+        Some(source) => format!("{source}:{}", entry.name),
+        None => entry.name.to_owned(),
+    }
+}
+
+/// Load the Usage History of Earlier Sessions
+pub fn load() -> BTreeMap<String, Usage> {
+    fs::read(history_path())
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Write the Usage History w/o Leaving Partial Files Behind
+fn save(history: &BTreeMap<String, Usage>) -> std::io::Result<()> {
+    let path = history_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, serde_json::to_vec(history)?)?;
+    fs::rename(temp, path)
+}
+
+/// Record the Entries the Executed Actions were Selected from (when Enabled)
+///
+/// Refreshing or disabling a plugin does not count as using the entry.
+pub fn record(selected: &[(Entry, Action)], config: &Config) {
+    if !config.use_history {
+        return;
+    }
+    let used: BTreeSet<String> = selected
+        .iter()
+        .filter(|(_, a)| !a.exec.is_internal())
+        .map(|(e, _)| key(e))
+        .collect();
+    if used.is_empty() {
+        return;
+    }
+    let now = now();
+    let mut history = load();
+    history.retain(|_, usage| now.saturating_sub(usage.last) < MAX_AGE);
+    for key in used {
+        let usage = history.entry(key).or_default();
+        usage.count += 1;
+        usage.last = now;
+    }
+    if let Err(err) = save(&history) {
+        log::error!("failed to write history {:?}: {err:?}", history_path());
+    }
+}
+
+/// Move Items into the Specified Order
+fn permute<T>(items: &mut Vec<T>, order: &[usize]) {
+    let mut slots: Vec<Option<T>> = items.drain(..).map(Some).collect();
+    items.extend(order.iter().filter_map(|i| slots[*i].take()));
+}
+
+/// Order Plugins by the Summed Frecency of their Entries (Most-Used First)
+///
+/// Plugins w/o any recorded use keep their given order.
+pub fn plugin_order(plugins: &[String]) -> Vec<String> {
+    let history = load();
+    let now = now();
+    let score = |plugin: &str| -> f64 {
+        history
+            .iter()
+            .filter(|(key, _)| {
+                let name = key.strip_prefix(plugin);
+                name.map(|n| n.starts_with(':')).unwrap_or(false)
+            })
+            .map(|(_, usage)| usage.frecency(now))
+            .sum()
+    };
+    let scores: Vec<f64> = plugins.iter().map(|p| score(p)).collect();
+    let mut order: Vec<usize> = (0..plugins.len()).collect();
+    order.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+    order.into_iter().map(|i| plugins[i].clone()).collect()
+}
+
+/// Order Entries by Frecency within each Plugin (when Enabled)
+///
+/// Entries of a plugin stay within its scope, and the frecency is kept
+/// with the normalized entries for the tie-breakers of ranked matchers.
+pub fn rank(config: &Config, entries: &mut Vec<Entry>, normalized: &mut Vec<Normalized>) {
+    if !config.use_history || entries.len() != normalized.len() {
+        return;
+    }
+    let history = load();
+    if history.is_empty() {
+        return;
+    }
+    let now = now();
+    let scores: Vec<f64> = entries
+        .iter()
+        .map(|e| history.get(&key(e)).map(|u| u.frecency(now)).unwrap_or(0.0))
+        .collect();
+    for (normalized, score) in normalized.iter_mut().zip(scores.iter()) {
+        normalized.frecency = *score;
+    }
+    let mut bounds: Vec<usize> = config
+        .search
+        .scopes
+        .iter()
+        .flat_map(|s| [s.entries.start, s.entries.end])
+        .chain([0, entries.len()])
+        .filter(|b| *b <= entries.len())
+        .collect();
+    bounds.sort();
+    bounds.dedup();
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    for pair in bounds.windows(2) {
+        order[pair[0]..pair[1]].sort_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+    }
+    permute(entries, &order);
+    permute(normalized, &order);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_frecency() {
+        let week = 7 * 24 * 60 * 60;
+        let recent = Usage {
+            count: 2,
+            last: 10 * week,
+        };
+        let frequent = Usage {
+            count: 6,
+            last: 8 * week,
+        };
+        assert_eq!(recent.frecency(10 * week), 2.0);
+        assert_eq!(frequent.frecency(10 * week), 1.5);
+        assert_eq!(frequent.frecency(9 * week), 3.0);
+    }
+}
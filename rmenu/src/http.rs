@@ -0,0 +1,55 @@
+//! HTTP(S) Plugin Sources Fetched through `curl`
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::cli::{RMenuError, Result};
+use crate::config::HttpSource;
+
+/// Build the Authentication Header from the Configured Environment Variable
+fn auth_header(source: &HttpSource) -> Option<String> {
+    let var = source.auth_env.as_ref()?;
+    match std::env::var(var) {
+        Ok(value) => Some(format!("{}: {value}", source.auth_header)),
+        Err(_) => {
+            log::warn!("auth variable {var:?} is not set, requesting w/o credentials");
+            None
+        }
+    }
+}
+
+/// Request the Endpoint and Return the Response Body
+///
+/// The auth header is passed on stdin so credentials never show up in
+/// the process list, and redirects are only followed w/o one so the
+/// credentials are never sent on to another host.
+pub fn get(source: &HttpSource) -> Result<Vec<u8>> {
+    let header = auth_header(source);
+    let timeout = source.timeout.max(1).to_string();
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--fail"])
+        .args(["--max-time", &timeout, "--connect-timeout", &timeout])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped());
+    match header.is_some() {
+        true => command.args(["--header", "@-"]),
+        false => command.arg("--location"),
+    };
+    let mut child = command.arg(&source.url).spawn().map_err(|err| {
+        log::error!("http sources require curl: {err}");
+        err
+    })?;
+    let pid = child.id();
+    crate::signals::track_child(pid);
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Some(header) = header {
+            writeln!(stdin, "{header}")?;
+        }
+    }
+    let output = child.wait_with_output()?;
+    crate::signals::untrack_child(pid);
+    if !output.status.success() {
+        return Err(RMenuError::CommandError(Some(output.status)));
+    }
+    Ok(output.stdout)
+}
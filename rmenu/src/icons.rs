@@ -0,0 +1,172 @@
+//! Freedesktop Icon Theme Lookup Resolving Icon Names into Files
+//!
+//! Entries may name their icons (e.g. `firefox`) instead of linking files.
+//! Names are resolved by the shared theme lookup of `rmenu_plugin::icons`,
+//! and the files found are cached between runs.
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use once_cell::sync::OnceCell;
+use rmenu_plugin::icons::{self, is_name, Theme};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+static LOOKUP: OnceCell<Lookup> = OnceCell::new();
+
+/// Previously Resolved Names for the same Themes and Size
+///
+/// Only names found are remembered, so icons installed later are never
+/// hidden behind an earlier failed lookup.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cache {
+    key: String,
+    stamp: u64,
+    icons: HashMap<String, PathBuf>,
+    #[serde(skip)]
+    changed: bool,
+}
+
+/// Configured Lookup w/ the Index Built on the First Unknown Name
+struct Lookup {
+    pixels: u32,
+    chain: Vec<Theme>,
+    index: OnceCell<HashMap<String, PathBuf>>,
+    cache: Mutex<Cache>,
+}
+
+impl Lookup {
+    fn index(&self) -> &HashMap<String, PathBuf> {
+        self.index.get_or_init(|| {
+            log::debug!("indexing icon themes for {}px icons", self.pixels);
+            icons::index(&self.chain, self.pixels)
+        })
+    }
+
+    /// Remember the Names Found in the Index
+    fn remember<'a>(&self, names: impl Iterator<Item = &'a str>) {
+        let index = self.index();
+        let mut cache = self.cache.lock().expect("Failed to Access Global Mutex");
+        for name in names {
+            if let Some(path) = index.get(name) {
+                cache.icons.insert(name.to_owned(), path.to_owned());
+                cache.changed = true;
+            }
+        }
+    }
+}
+
+/// Retrieve the Cache File Stored Next to the Plugin Caches
+#[inline]
+fn cache_path() -> PathBuf {
+    crate::paths::cache_dir().join("icons.json")
+}
+
+/// Base Directories Containing Icon Themes in Order of Preference
+fn base_dirs() -> Vec<PathBuf> {
+    let mut bases = vec![];
+    if let Some(home) = std::env::var_os("HOME") {
+        bases.push(PathBuf::from(home).join(".icons"));
+    }
+    if let Ok(xdg) = xdg::BaseDirectories::new() {
+        bases.push(xdg.get_data_home().join("icons"));
+        bases.extend(xdg.get_data_dirs().into_iter().map(|d| d.join("icons")));
+    }
+    bases
+}
+
+/// Prepare Icon Name Lookups for the Configured Theme and Size
+///
+/// The theme defaults to the one configured for GTK applications.
+pub fn configure(config: &Config) {
+    let theme = config.icon_theme.clone().or_else(|| {
+        let config = xdg::BaseDirectories::new().ok()?.get_config_home();
+        icons::detect_theme(&config)
+    });
+    let chain = icons::themes(&base_dirs(), theme.as_deref());
+    let key = format!("{}:{}", theme.unwrap_or_default(), config.icon_size);
+    let stamp = icons::modified(&chain);
+    let cache = fs::read(cache_path())
+        .ok()
+        .and_then(|data| serde_json::from_slice::<Cache>(&data).ok())
+        .filter(|c| c.key == key && c.stamp == stamp)
+        .unwrap_or_else(|| Cache {
+            key,
+            stamp,
+            ..Default::default()
+        });
+    let _ = LOOKUP.set(Lookup {
+        pixels: config.icon_size,
+        chain,
+        index: OnceCell::new(),
+        cache: Mutex::new(cache),
+    });
+}
+
+/// Retrieve the Remembered File of an Icon Name w/o Indexing the Themes
+pub fn cached(icon: &str) -> Option<String> {
+    let lookup = LOOKUP.get()?;
+    let cache = lookup.cache.lock().expect("Failed to Access Global Mutex");
+    let path = cache.icons.get(icon).filter(|p| p.exists())?;
+    path.to_str().map(|s| s.to_owned())
+}
+
+/// Resolve an Icon Name into the Path of its Preferred File
+///
+/// Paths, glyphs and names without any matching icon return None, as
+/// does every icon before the lookup was configured. Indexes the themes
+/// on the first name that was not remembered.
+pub fn locate(icon: &str) -> Option<String> {
+    if !is_name(icon) {
+        return None;
+    }
+    if let Some(path) = cached(icon) {
+        return Some(path);
+    }
+    let lookup = LOOKUP.get()?;
+    lookup.remember([icon].into_iter());
+    let path = lookup.index().get(icon)?;
+    path.to_str().map(|s| s.to_owned())
+}
+
+/// Index the Themes in the Background and Remember the Names Found
+///
+/// Keeps the index off the startup path, so the first render that needs
+/// it only waits for whatever is left of it.
+pub fn prefetch(names: Vec<String>) {
+    if names.is_empty() || LOOKUP.get().is_none() {
+        return;
+    }
+    std::thread::spawn(move || {
+        let Some(lookup) = LOOKUP.get() else {
+            return;
+        };
+        lookup.remember(names.iter().map(|s| s.as_str()));
+        save();
+    });
+}
+
+/// Persist Newly Resolved Names for Later Runs
+pub fn save() {
+    let Some(lookup) = LOOKUP.get() else {
+        return;
+    };
+    let mut cache = lookup.cache.lock().expect("Failed to Access Global Mutex");
+    if !cache.changed {
+        return;
+    }
+    let path = cache_path();
+    let temp = path.with_extension("json.tmp");
+    let result = path
+        .parent()
+        .map(fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| fs::write(&temp, serde_json::to_vec(&*cache)?))
+        .and_then(|_| fs::rename(&temp, &path));
+    match result {
+        Ok(()) => cache.changed = false,
+        Err(err) => log::error!("failed to write icon cache {path:?}: {err:?}"),
+    }
+}
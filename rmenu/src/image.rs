@@ -1,16 +1,34 @@
 //! GUI Image Processing
+use std::collections::HashMap;
 use std::fs::{create_dir_all, write};
 use std::io;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
-use cached::proc_macro::cached;
 use once_cell::sync::Lazy;
 use resvg::usvg::TreeParsing;
+use rmenu_plugin::Entry;
 use thiserror::Error;
 
 static TEMP_EXISTS: Lazy<Mutex<Vec<bool>>> = Lazy::new(|| Mutex::new(vec![]));
-static TEMP_DIR: Lazy<PathBuf> = Lazy::new(|| PathBuf::from("/tmp/rmenu"));
+static TEMP_DIR: Lazy<PathBuf> = Lazy::new(|| match crate::paths::runtime_dir() {
+    Some(dir) => dir.join("icons"),
+    None => std::env::temp_dir().join("rmenu"),
+});
+/// Resolved Icons Interned by the Path (or Name) Entries Refer to them by
+///
+/// Only icons are interned, other entry text (names, comments, sources and
+/// actions) stays owned by each entry as the plugin API defines it.
+static RESOLVED: Lazy<Mutex<HashMap<Arc<str>, Option<Icon>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Renderable Image Source w/ its Recoloring Eligibility
+#[derive(Debug, Clone)]
+pub struct Icon {
+    pub src: Arc<str>,
+    /// Monochrome SVGs that themes may recolor like GTK `-symbolic` icons
+    pub symbolic: bool,
+}
 
 #[derive(Debug, Error)]
 enum SvgError {
@@ -56,8 +74,7 @@ fn svg_to_png(path: &str, dest: &PathBuf, pixels: u32) -> Result<(), SvgError> {
     Ok(write(dest, png)?)
 }
 
-#[cached]
-pub fn convert_svg(path: String) -> Option<String> {
+fn convert_svg(path: &str) -> Option<String> {
     // ensure temporary directory exists
     let _ = make_temp();
     // convert path to new temporary png filepath
@@ -68,7 +85,7 @@ pub fn convert_svg(path: String) -> Option<String> {
     // generate png if it doesnt already exist
     if !new_path.exists() {
         log::debug!("generating png {new_path:?}");
-        match svg_to_png(&path, &new_path, 64) {
+        match svg_to_png(path, &new_path, 64) {
             Err(err) => log::error!("failed svg->png: {err:?}"),
             _ => {}
         }
@@ -76,7 +93,154 @@ pub fn convert_svg(path: String) -> Option<String> {
     Some(new_path.to_str()?.to_string())
 }
 
-#[cached]
-pub fn image_exists(path: String) -> bool {
-    PathBuf::from(path).exists()
+/// Presentation Attributes (and CSS Properties) Painting in a Color
+static PAINTS: &[&str] = &[
+    "fill",
+    "stroke",
+    "color",
+    "stop-color",
+    "flood-color",
+    "lighting-color",
+];
+/// Paint Values that Never Introduce a Color of their Own
+static UNPAINTED: &[&str] = &["", "none", "currentcolor", "inherit", "transparent"];
+
+/// Collect the Color Values of every Paint Attribute and CSS Property
+///
+/// Values are compared as written, so any color form (hex, `rgb()`,
+/// named) counts, and differently written equal colors count twice.
+fn paints(xml: &str) -> Vec<String> {
+    let mut colors = vec![];
+    for paint in PAINTS {
+        for (i, _) in xml.match_indices(paint) {
+            let before = xml[..i].chars().next_back();
+            if before
+                .map(|c| c.is_alphanumeric() || c == '-')
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            let rest = xml[i + paint.len()..].trim_start();
+            let Some(rest) = rest.strip_prefix(['=', ':']) else {
+                continue;
+            };
+            let rest = rest.trim_start();
+            let value = match rest.chars().next() {
+                Some(quote @ ('"' | '\'')) => rest[1..].split(quote).next(),
+                _ => rest.split([';', '"', '\'', '}', '>']).next(),
+            };
+            let value = value
+                .unwrap_or_default()
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches("!important")
+                .replace(char::is_whitespace, "")
+                .to_ascii_lowercase();
+            if !UNPAINTED.contains(&value.as_str()) {
+                colors.push(value);
+            }
+        }
+    }
+    colors
+}
+
+/// Check if the SVG Document only Draws in a Single Flat Color
+fn is_monochrome(xml: &str) -> bool {
+    if xml.contains("Gradient") || xml.contains("<image") {
+        return false;
+    }
+    let mut colors = paints(xml);
+    colors.sort();
+    colors.dedup();
+    colors.len() <= 1
+}
+
+/// Check if the SVG is a Symbolic Icon by Name or Content
+fn is_symbolic(path: &str) -> bool {
+    path.ends_with("-symbolic.svg")
+        || std::fs::read_to_string(path)
+            .map(|xml| is_monochrome(&xml))
+            .unwrap_or(false)
+}
+
+/// Resolve Icon Path (or Themed Icon Name) into a Renderable Image Source
+///
+/// Results are interned by path, so icons repeated across thousands of
+/// entries share a single allocation and are only resolved once.
+pub fn resolve(path: &str) -> Option<Icon> {
+    let mut resolved = RESOLVED.lock().expect("Failed to Access Global Mutex");
+    if let Some(src) = resolved.get(path) {
+        return src.clone();
+    }
+    let located = crate::icons::locate(path);
+    let file = located.as_deref().unwrap_or(path);
+    if !Path::new(file).exists() {
+        log::warn!("missing icon {path:?}");
+        resolved.insert(Arc::from(path), None);
+        return None;
+    }
+    let icon = match file.ends_with(".svg") {
+        true => convert_svg(file).map(|src| Icon {
+            src: Arc::from(src),
+            symbolic: is_symbolic(file),
+        }),
+        false => Some(Icon {
+            src: Arc::from(file),
+            symbolic: false,
+        }),
+    };
+    resolved.insert(Arc::from(path), icon.clone());
+    icon
+}
+
+/// Pre-Validate Entry Icon Paths during Ingestion
+///
+/// Missing icons are recorded once so renders never touch the filesystem
+/// for them again, while existing icons are still resolved lazily. Icon
+/// names not remembered from earlier runs are left to the theme lookup,
+/// which is indexed in the background rather than before the menu opens.
+pub fn validate(entries: &[Entry]) {
+    let mut resolved = RESOLVED.lock().expect("Failed to Access Global Mutex");
+    let icons = entries.iter().flat_map(|e| {
+        let actions = e.actions.iter().filter_map(|a| a.icon.as_ref());
+        e.icon.iter().chain(actions)
+    });
+    let mut pending = vec![];
+    for icon in icons {
+        if resolved.contains_key(icon.as_str()) {
+            continue;
+        }
+        let located = crate::icons::cached(icon);
+        if Path::new(located.as_deref().unwrap_or(icon)).exists() {
+            continue;
+        }
+        if rmenu_plugin::icons::is_name(icon) {
+            pending.push(icon.to_owned());
+            continue;
+        }
+        log::warn!("missing icon {icon:?}");
+        resolved.insert(Arc::from(icon.as_str()), None);
+    }
+    crate::icons::prefetch(pending);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monochrome_colors() {
+        let flat =
+            r##"<svg><path fill="#2e3436"/><path style="fill:#2e3436;stroke-width:2"/></svg>"##;
+        assert!(is_monochrome(flat));
+        assert!(is_monochrome(r##"<svg><path d="M0 0h16v16z"/></svg>"##));
+        let named = r##"<svg><path fill="red"/><path fill="blue"/></svg>"##;
+        assert!(!is_monochrome(named));
+        let rgb = r##"<svg><style>.a{fill: rgb(0, 0, 0)} .b{stroke:rgb(255,0,0)}</style></svg>"##;
+        assert!(!is_monochrome(rgb));
+        let current =
+            r##"<svg><path fill="currentColor"/><path stroke="#000" fill="none"/></svg>"##;
+        assert!(is_monochrome(current));
+    }
 }
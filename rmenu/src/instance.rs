@@ -0,0 +1,447 @@
+//! RMenu Single-Instance Enforcement
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+
+use once_cell::sync::{Lazy, OnceCell};
+use rmenu_core::events::{emit, subscribe, Event};
+use rmenu_plugin::{Action, Entry, Method};
+use serde::Serialize;
+
+use crate::cli::{Args, RMenuError, Result};
+use crate::config::{InstanceMode, Scope};
+use crate::search::Normalized;
+
+static SOCKET_NAME: &'static str = "rmenu.sock";
+static FD_NAME: &'static str = "instance";
+static PENDING: Lazy<Mutex<VecDeque<Control>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+static SOURCES: OnceCell<Mutex<Args>> = OnceCell::new();
+static STATUS: Lazy<Mutex<Status>> = Lazy::new(|| Mutex::new(Status::default()));
+static STATUS_WATCHERS: Lazy<Mutex<Vec<UnixStream>>> = Lazy::new(|| Mutex::new(vec![]));
+static EVENT_WATCHERS: Lazy<Mutex<Vec<UnixStream>>> = Lazy::new(|| Mutex::new(vec![]));
+static PUBLISHER: Lazy<Mutex<mpsc::Sender<(Feed, String)>>> = Lazy::new(|| {
+    let (send, recv) = mpsc::channel();
+    std::thread::spawn(move || notify(recv));
+    Mutex::new(send)
+});
+
+/// Live Search Counters for Themes and External Status Bars
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Status {
+    pub query_length: usize,
+    pub matches: usize,
+    pub plugin: Option<String>,
+}
+
+/// Line Feeds Clients can Watch over the Instance Socket
+#[derive(Debug, Clone, Copy)]
+enum Feed {
+    Status,
+    Events,
+}
+
+impl Feed {
+    /// Parse the Feed Subscribed to by a Single Socket Line
+    fn parse(line: &str) -> Option<Self> {
+        match line.trim() {
+            "watch-status" => Some(Self::Status),
+            "watch-events" => Some(Self::Events),
+            _ => None,
+        }
+    }
+
+    /// Retrieve the Clients Watching the Feed
+    fn watchers(self) -> &'static Mutex<Vec<UnixStream>> {
+        match self {
+            Self::Status => &STATUS_WATCHERS,
+            Self::Events => &EVENT_WATCHERS,
+        }
+    }
+}
+
+/// Commands Forwarded from Newer Instances or External Tools to the GUI
+#[derive(Debug, Clone, PartialEq)]
+pub enum Control {
+    Focus,
+    SetQuery(String),
+    SelectIndex(usize),
+    Close,
+    Refresh,
+    RefreshSource(String),
+    /// Refresh Entries w/o Moving the Selection (after Keep-Open Actions)
+    Update,
+    /// Replace Entries w/ the Answer of a Streaming Plugin to the Search
+    Streamed(Vec<Entry>, Vec<Normalized>),
+    /// Replace Entries and their Scopes once another Deferred Plugin Finished Loading
+    Loaded(Vec<Entry>, Vec<Normalized>, Vec<Scope>),
+    DisableSource(String),
+    /// Toggle whether the Window Stays Above Others
+    ToggleTop,
+    /// Toggle whether the Window is Shown on all Workspaces
+    ToggleSticky,
+}
+
+impl Control {
+    /// Parse Control Command from a Single Socket Line
+    pub fn parse(line: &str) -> Option<Self> {
+        let (cmd, arg) = line.split_once(' ').unwrap_or((line, ""));
+        match cmd {
+            "focus" => Some(Self::Focus),
+            "set-query" => Some(Self::SetQuery(arg.to_owned())),
+            "select-index" => arg.trim().parse().ok().map(Self::SelectIndex),
+            "close" => Some(Self::Close),
+            "refresh" => Some(Self::Refresh),
+            "refresh-source" => Some(Self::RefreshSource(arg.trim().to_owned())),
+            "disable-source" => Some(Self::DisableSource(arg.trim().to_owned())),
+            "toggle-top" => Some(Self::ToggleTop),
+            "toggle-sticky" => Some(Self::ToggleSticky),
+            _ => None,
+        }
+    }
+}
+
+/// Retrieve Instance Socket Path in the XDG Runtime Directory
+fn socket_path() -> Option<PathBuf> {
+    crate::paths::runtime_file(SOCKET_NAME)
+}
+
+/// Listen for Commands from Newer Instances
+fn serve(listener: UnixListener) {
+    for stream in listener.incoming().filter_map(|s| s.ok()) {
+        let reader = BufReader::new(&stream);
+        for line in reader.lines().filter_map(|l| l.ok()) {
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line.trim() == "quit" {
+                log::info!("replaced by newer instance");
+                crate::signals::teardown();
+                std::process::exit(0);
+            }
+            if let Some(feed) = Feed::parse(line) {
+                watch(&stream, feed);
+                break;
+            }
+            match Control::parse(line) {
+                Some(control) => PENDING
+                    .lock()
+                    .expect("Failed to Access Global Mutex")
+                    .push_back(control),
+                None => log::warn!("unknown instance command: {line:?}"),
+            }
+        }
+    }
+}
+
+/// Subscribe the Client to the Feed (Status Updates start w/ the Current Status)
+fn watch(stream: &UnixStream, feed: Feed) {
+    let Ok(mut stream) = stream.try_clone() else {
+        return;
+    };
+    // never stall the menu on clients that stopped reading
+    let _ = stream.set_write_timeout(Some(Duration::from_millis(50)));
+    if let Feed::Events = feed {
+        feed.watchers()
+            .lock()
+            .expect("Failed to Access Global Mutex")
+            .push(stream);
+        return;
+    }
+    // holding the status lock keeps updates from slipping in before subscribing
+    let status = STATUS.lock().expect("Failed to Access Global Mutex");
+    let line = serde_json::to_string(&*status).expect("Failed to Serialize Status");
+    if writeln!(stream, "{line}").is_ok() {
+        feed.watchers()
+            .lock()
+            .expect("Failed to Access Global Mutex")
+            .push(stream);
+    }
+}
+
+/// Write Published Lines to the Clients Watching their Feed
+///
+/// Clients that disconnected are dropped from the watchers.
+fn notify(lines: mpsc::Receiver<(Feed, String)>) {
+    for (feed, line) in lines {
+        feed.watchers()
+            .lock()
+            .expect("Failed to Access Global Mutex")
+            .retain_mut(|stream| writeln!(stream, "{line}").is_ok());
+    }
+}
+
+/// Queue the Line to be Written to the Clients Watching the Feed
+#[inline]
+fn send_feed(feed: Feed, line: String) {
+    let _ = PUBLISHER
+        .lock()
+        .expect("Failed to Access Global Mutex")
+        .send((feed, line));
+}
+
+/// Describe the Event as a Single JSON Object
+///
+/// Executed actions are only named, so commands filled w/ a masked
+/// query never reach the socket.
+fn event_json(event: &Event) -> serde_json::Value {
+    match event {
+        Event::QueryChanged(query) => serde_json::json!({
+            "event": "query_changed",
+            "query": query,
+        }),
+        Event::EntriesUpdated(entries) => serde_json::json!({
+            "event": "entries_updated",
+            "entries": entries,
+        }),
+        Event::SelectionMoved { pos, subpos } => serde_json::json!({
+            "event": "selection_moved",
+            "pos": pos,
+            "subpos": subpos,
+        }),
+        Event::ActionExecuted(action) => serde_json::json!({
+            "event": "action_executed",
+            "action": action.name,
+        }),
+    }
+}
+
+/// Forward Events from the Event Bus to the Clients Watching them
+fn publish_event(event: &Event) {
+    let watched = !EVENT_WATCHERS
+        .lock()
+        .expect("Failed to Access Global Mutex")
+        .is_empty();
+    if watched {
+        send_feed(Feed::Events, event_json(event).to_string());
+    }
+}
+
+/// Publish Status to Watching Clients when it Changed
+///
+/// Lines are written by a dedicated thread, so slow clients never
+/// delay the render calling this.
+pub fn publish(status: Status) {
+    let mut last = STATUS.lock().expect("Failed to Access Global Mutex");
+    if *last == status {
+        return;
+    }
+    let line = serde_json::to_string(&status).expect("Failed to Serialize Status");
+    *last = status;
+    send_feed(Feed::Status, line);
+}
+
+/// Queue Per-Source Actions as Control Commands instead of Executing them
+///
+/// Returns the remaining actions that still need to be executed.
+pub fn queue_sources(actions: Vec<Action>) -> Vec<Action> {
+    let mut pending = PENDING.lock().expect("Failed to Access Global Mutex");
+    actions
+        .into_iter()
+        .filter(|action| match &action.exec {
+            Method::RefreshSource(name) => {
+                pending.push_back(Control::RefreshSource(name.to_owned()));
+                false
+            }
+            Method::DisableSource(name) => {
+                pending.push_back(Control::DisableSource(name.to_owned()));
+                false
+            }
+            _ => true,
+        })
+        .collect()
+}
+
+/// Queue a Control Command Issued by the Menu Itself
+///
+/// Replaced entries supersede the same kind of replacement still waiting
+/// to be applied, so bursts of updates are rendered only once.
+pub fn queue(control: Control) {
+    let mut pending = PENDING.lock().expect("Failed to Access Global Mutex");
+    if matches!(control, Control::Streamed(..) | Control::Loaded(..)) {
+        let kind = std::mem::discriminant(&control);
+        pending.retain(|c| std::mem::discriminant(c) != kind);
+    }
+    pending.push_back(control);
+}
+
+/// Retrieve Commands Received since the Last Call
+pub fn take() -> Vec<Control> {
+    PENDING
+        .lock()
+        .expect("Failed to Access Global Mutex")
+        .drain(..)
+        .collect()
+}
+
+/// Keep Entry-Sources Available to Reload Entries on Refresh
+pub fn set_sources(cli: Args) {
+    if SOURCES.set(Mutex::new(cli)).is_err() {
+        log::warn!("entry sources already registered");
+    }
+}
+
+/// Reload Entries from the Registered Entry-Sources
+///
+/// Sources other than the given one are served from their caches.
+pub fn reload(source: Option<&str>) -> Option<(Vec<Entry>, Vec<Normalized>, Vec<Scope>)> {
+    let mut cli = SOURCES.get()?.lock().ok()?;
+    if !cli.can_reload() {
+        log::warn!("entries read from stdin or a fifo cannot be refreshed");
+        return None;
+    }
+    match cli.reload_entries(source) {
+        Ok((entries, normalized, scopes)) => {
+            emit(Event::EntriesUpdated(entries.len()));
+            Some((entries, normalized, scopes))
+        }
+        Err(err) => {
+            log::error!("failed to refresh entries: {err:?}");
+            None
+        }
+    }
+}
+
+/// Load the Deferred Plugins in the Background after the Menu Opened
+///
+/// The entries loaded so far are queued after every plugin, and the
+/// entry-sources stay locked until all of them are loaded so refreshes
+/// never race the deferred plugins.
+pub fn load_deferred(
+    deferred: Vec<String>,
+    scopes: Vec<Scope>,
+    mut entries: Vec<Entry>,
+    mut normalized: Vec<Normalized>,
+) {
+    std::thread::spawn(move || {
+        let Some(mut cli) = SOURCES.get().and_then(|s| s.lock().ok()) else {
+            return;
+        };
+        let mut config = match cli.get_config() {
+            Ok(config) => config,
+            Err(err) => {
+                log::error!("failed to load deferred plugins: {err:?}");
+                return;
+            }
+        };
+        config.search.scopes = scopes;
+        for name in deferred {
+            log::info!("loading deferred plugin: {name:?}");
+            let start = entries.len();
+            let loaded = cli.load_deferred(&mut config, &name, &mut entries, &mut normalized);
+            if let Err(err) = loaded {
+                log::error!("deferred plugin {name:?} failed: {err:?}");
+                continue;
+            }
+            if config.use_icons {
+                crate::image::validate(&entries[start..]);
+            }
+            emit(Event::EntriesUpdated(entries.len()));
+            let scopes = config.search.scopes.clone();
+            queue(Control::Loaded(entries.clone(), normalized.clone(), scopes));
+        }
+    });
+}
+
+/// Send Control Command to the Running Instance
+pub fn send(command: &str) -> Result<()> {
+    let path = socket_path().ok_or(RMenuError::NotRunning)?;
+    let mut stream = UnixStream::connect(&path).map_err(|_| RMenuError::NotRunning)?;
+    writeln!(stream, "{command}")?;
+    if Feed::parse(command).is_some() {
+        for line in BufReader::new(stream).lines() {
+            println!("{}", line?);
+        }
+    }
+    Ok(())
+}
+
+/// Serve Commands and Publish Events on the Claimed Socket
+fn listen(listener: UnixListener) {
+    std::thread::spawn(move || serve(listener));
+    subscribe(publish_event);
+    crate::systemd::notify_ready();
+}
+
+/// Enforce Configured Single-Instance Behavior
+///
+/// Returns false when the existing instance should be used instead.
+/// The claimed socket also accepts control commands while the menu is
+/// open, even when multiple instances are allowed.
+pub fn acquire(mode: &InstanceMode) -> Result<bool> {
+    // sockets passed by systemd socket activation are owned by systemd
+    if let Some(listener) = crate::systemd::take_listener(FD_NAME) {
+        listen(listener);
+        return Ok(true);
+    }
+    let Some(path) = socket_path() else {
+        log::warn!("runtime directory unavailable, skipping instance check");
+        return Ok(true);
+    };
+    // check for existing instance
+    if let Ok(mut stream) = UnixStream::connect(&path) {
+        match mode {
+            InstanceMode::Allow => {
+                log::debug!("control socket owned by existing instance");
+                return Ok(true);
+            }
+            InstanceMode::Error => return Err(RMenuError::AlreadyRunning),
+            InstanceMode::Focus => {
+                writeln!(stream, "focus")?;
+                log::info!("focusing existing instance");
+                return Ok(false);
+            }
+            InstanceMode::Replace => {
+                writeln!(stream, "quit")?;
+                drop(stream);
+                for _ in 0..20 {
+                    if UnixStream::connect(&path).is_err() {
+                        break;
+                    }
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+            }
+        }
+    }
+    // claim socket (removing stale sockets) and listen for commands
+    let _ = fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+    crate::signals::remove_on_exit(path);
+    listen(listener);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_control_commands() {
+        let cmd = Control::parse("set-query fire fox");
+        assert_eq!(cmd, Some(Control::SetQuery("fire fox".to_owned())));
+        assert_eq!(
+            Control::parse("select-index 3"),
+            Some(Control::SelectIndex(3))
+        );
+        assert_eq!(Control::parse("select-index x"), None);
+        assert_eq!(Control::parse("close"), Some(Control::Close));
+        assert_eq!(Control::parse("refresh"), Some(Control::Refresh));
+        assert_eq!(Control::parse("toggle-top"), Some(Control::ToggleTop));
+        assert_eq!(Control::parse("toggle-sticky"), Some(Control::ToggleSticky));
+        assert_eq!(Control::parse("bogus"), None);
+    }
+
+    #[test]
+    fn test_event_lines() {
+        let moved = event_json(&Event::SelectionMoved { pos: 2, subpos: 0 });
+        assert_eq!(
+            moved.to_string(),
+            r#"{"event":"selection_moved","pos":2,"subpos":0}"#
+        );
+        let action = Action::exec("xdg-open {uri}");
+        let executed = event_json(&Event::ActionExecuted(action));
+        assert_eq!(executed["action"], "main");
+        assert!(executed.get("exec").is_none());
+    }
+}
@@ -0,0 +1,30 @@
+//! RMenu Diagnostic Logging (Plain or Structured JSON)
+use std::io::Write;
+
+use env_logger::{Builder, Env, WriteStyle};
+
+/// Initialize Logger on Stderr w/ Requested Verbosity and Format
+///
+/// Colors are disabled when `NO_COLOR` is set and JSON output is
+/// written one object per line so wrapper scripts can parse it.
+pub fn init(json: bool, quiet: bool) {
+    // export level so plugins inherit the same verbosity
+    if std::env::var("RUST_LOG").is_err() {
+        std::env::set_var("RUST_LOG", if quiet { "error" } else { "info" });
+    }
+    let mut builder = Builder::from_env(Env::default());
+    if json || std::env::var_os("NO_COLOR").is_some() {
+        builder.write_style(WriteStyle::Never);
+    }
+    if json {
+        builder.format(|buf, record| {
+            let line = serde_json::json!({
+                "level": record.level().as_str(),
+                "module": record.module_path().unwrap_or_default(),
+                "message": record.args().to_string(),
+            });
+            writeln!(buf, "{line}")
+        });
+    }
+    builder.init();
+}
@@ -1,13 +1,45 @@
+mod blacklist;
+mod bootstrap;
 mod cache;
 mod cli;
-mod config;
+mod clipboard;
+mod crash;
+mod css;
+mod daemon;
 mod exec;
+mod font;
+mod frontend;
+mod geometry;
 mod gui;
+mod history;
+mod http;
+mod icons;
 mod image;
-mod search;
+mod instance;
+mod logging;
+mod manifest;
+mod migrate;
+#[cfg(feature = "native")]
+mod native;
+mod paths;
+mod provenance;
+mod service;
+mod signals;
 mod state;
+mod stream;
+mod systemd;
+#[cfg(any(test, feature = "testing"))]
+#[allow(dead_code)]
+mod testing;
+mod themes;
+#[cfg(feature = "tui")]
+mod tui;
+
+use std::io::ErrorKind;
+use std::sync::Arc;
 
 use clap::Parser;
+use rmenu_core::{config, events, search};
 use rmenu_plugin::{self_exe, Entry};
 
 static DEFAULT_THEME: &'static str = "style.css";
@@ -21,7 +53,8 @@ pub struct App {
     css: String,
     name: String,
     theme: String,
-    entries: Vec<Entry>,
+    entries: Arc<[Entry]>,
+    normalized: Arc<[search::Normalized]>,
     config: config::Config,
 }
 
@@ -35,36 +68,121 @@ fn main() -> cli::Result<()> {
     let exe = self_exe();
     std::env::set_var("RMENU", exe);
 
-    // enable log and set default level
-    if std::env::var("RUST_LOG").is_err() {
-        std::env::set_var("RUST_LOG", "info");
+    // parse cli and enable log w/ requested format
+    let cli = cli::Args::parse();
+    let json = cli.json_logs;
+    logging::init(json, cli.quiet);
+
+    // report fatal errors as structured diagnostics when requested
+    match run(cli) {
+        Err(err) if json => {
+            log::error!("{err}: {err:?}");
+            std::process::exit(1);
+        }
+        result => result,
+    }
+}
+
+fn run(mut cli: cli::Args) -> cli::Result<()> {
+    if let Some(dir) = cli.config_dir.as_ref() {
+        paths::set_config_dir(dir);
     }
-    env_logger::init();
+    crash::install();
+    signals::install()?;
+    // claim the private runtime directory before placing any sockets
+    let runtime = paths::runtime_dir();
+    events::subscribe(|event| log::debug!("event: {event:?}"));
 
-    // parse cli and retrieve values for app
-    let mut cli = cli::Args::parse();
+    // retrieve values for app
+    match cli.command {
+        Some(cli::Command::Init { force }) => return bootstrap::init(force),
+        Some(cli::Command::Completions { shell }) => return cli.completions(shell),
+        Some(cli::Command::Manpage) => return cli.manpage(),
+        Some(cli::Command::Control { ref command }) => return instance::send(&command.join(" ")),
+        Some(cli::Command::Cache { ref command }) => return cli.cache(command),
+        Some(cli::Command::Daemon) => return daemon::run(&cli.get_config()?),
+        Some(cli::Command::Send {
+            ref channel,
+            replace,
+        }) => return daemon::send(channel, replace),
+        Some(cli::Command::MigrateConfig { dry_run }) => return cli.migrate_config(dry_run),
+        None => {}
+    }
+    if cli.runtime_dir {
+        let err = std::io::Error::new(ErrorKind::NotFound, "XDG_RUNTIME_DIR unavailable");
+        println!("{}", runtime.ok_or(err)?.display());
+        return Ok(());
+    }
+    if cli.help_config {
+        print!("{}", provenance::help_config());
+        return Ok(());
+    }
+    if cli.list_themes {
+        print!("{}", cli.themes());
+        return Ok(());
+    }
+    if cli.list_plugins {
+        print!("{}", cli.plugin_report()?);
+        return Ok(());
+    }
     let mut config = cli.get_config()?;
-    let entries = cli.get_entries(&mut config)?;
+
+    // print effective configuration w/ sources when requested
+    if cli.show_config {
+        let mut tracker = provenance::Provenance::new();
+        tracker.record("config file", &config);
+        if cli.has_sources() {
+            cli.get_entries(&mut config)?;
+            tracker.record("plugin options", &config);
+        }
+        config = cli.update_config(config);
+        tracker.record("cli", &config);
+        print!("{}", tracker.render(&config)?);
+        return Ok(());
+    }
+
+    // enforce single-instance settings before loading entries
+    if !instance::acquire(&cli.instance_mode(&config))? {
+        return Ok(());
+    }
+    cli.prioritize(&config);
+    let (entries, normalized) = cli.get_entries(&mut config)?;
+    events::emit(events::Event::EntriesUpdated(entries.len()));
+    let deferred = cli.take_deferred();
+    let scopes = config.search.scopes.clone();
 
     // update config based on cli-settings and entries
     config = cli.update_config(config);
+    // entries of deferred plugins are unknown until the menu opened
+    let pending = !deferred.is_empty();
     config.use_icons = config.use_icons
-        && entries
-            .iter()
-            .any(|e| e.icon.is_some() || e.icon_alt.is_some());
-    config.use_comments = config.use_comments && entries.iter().any(|e| e.comment.is_some());
+        && (pending
+            || entries
+                .iter()
+                .any(|e| e.icon.is_some() || e.icon_alt.is_some()));
+    config.use_comments =
+        config.use_comments && (pending || entries.iter().any(|e| e.comment.is_some()));
+    if config.use_icons {
+        icons::configure(&config);
+        image::validate(&entries);
+    }
 
-    let theme = cli.get_theme();
-    let css = cli.get_css(&config);
+    let theme = css::sanitize("theme", cli.get_theme(&config));
+    let css = css::sanitize("custom", cli.get_css(&config));
+    crash::set_context(cli.plugins(), &config);
+    instance::set_sources(cli);
+    if pending {
+        instance::load_deferred(deferred, scopes, entries.clone(), normalized.clone());
+    }
 
-    // genrate app context and run gui
-    gui::run(App {
+    // genrate app context and run the configured frontend
+    let frontend = frontend::select(&config.frontend)?;
+    frontend.run(App {
         name: "rmenu".to_owned(),
         css,
         theme,
-        entries,
+        entries: entries.into(),
+        normalized: normalized.into(),
         config,
-    });
-
-    Ok(())
+    })
 }
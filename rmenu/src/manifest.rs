@@ -0,0 +1,76 @@
+//! Plugin Auto-Registration from `plugins/*/plugin.toml` Manifests
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::config::{CacheSetting, PluginConfig};
+
+static MANIFEST: &'static str = "plugin.toml";
+
+/// Plugin Registration Shipped Alongside the Plugin
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    name: Option<String>,
+    exec: Vec<String>,
+    cache: Option<String>,
+    placeholder: Option<String>,
+    prefix: Option<String>,
+}
+
+/// Parse the Manifest within a Plugin Folder
+///
+/// The executable is resolved relative to the folder when it exists
+/// there, and the plugin is named after the folder unless specified.
+fn load(dir: &Path) -> Result<(String, PluginConfig), String> {
+    let content = std::fs::read_to_string(dir.join(MANIFEST)).map_err(|e| e.to_string())?;
+    let manifest: Manifest = toml::from_str(&content).map_err(|e| e.to_string())?;
+    let mut exec = manifest.exec;
+    match exec.first_mut() {
+        Some(main) if dir.join(main.as_str()).is_file() => {
+            *main = dir.join(main.as_str()).to_string_lossy().to_string()
+        }
+        Some(_) => {}
+        None => return Err("missing plugin exec".to_owned()),
+    }
+    let cache = match manifest.cache {
+        Some(cache) => CacheSetting::from_str(&cache)?,
+        None => Default::default(),
+    };
+    let name = manifest.name.unwrap_or_else(|| {
+        dir.file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    });
+    let plugin = PluginConfig {
+        exec,
+        cache,
+        placeholder: manifest.placeholder,
+        prefix: manifest.prefix,
+        ..Default::default()
+    };
+    Ok((name, plugin))
+}
+
+/// Discover Plugins Registered by Manifests in the Plugin Directory
+pub fn discover() -> BTreeMap<String, PluginConfig> {
+    let mut plugins = BTreeMap::new();
+    let Ok(dirs) = std::fs::read_dir(crate::paths::config_dir().join("plugins")) else {
+        return plugins;
+    };
+    for dir in dirs.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if !dir.join(MANIFEST).is_file() {
+            continue;
+        }
+        match load(&dir) {
+            Ok((name, plugin)) => {
+                log::debug!("registered plugin {name:?} from {dir:?}");
+                plugins.insert(name, plugin);
+            }
+            Err(err) => log::error!("invalid plugin manifest in {dir:?}: {err}"),
+        }
+    }
+    plugins
+}
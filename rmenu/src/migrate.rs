@@ -0,0 +1,140 @@
+//! Config Migration Rewriting Deprecated Keys to the Current Schema
+use std::fs;
+use std::path::Path;
+
+use serde_yaml::{Mapping, Value};
+
+use crate::cli::Result;
+
+/// Deprecated Key Paths w/ their Current Location
+static RENAMED: &[(&str, &str)] = &[
+    ("search.regex", "search.use_regex"),
+    ("search.mode", "search.matcher"),
+    ("search_regex", "search.use_regex"),
+    ("search_restrict", "search.restrict"),
+    ("ignore_case", "search.ignore_case"),
+    ("placeholder", "search.placeholder"),
+];
+
+/// Retrieve the Current Location of a Deprecated Key
+pub fn renamed(path: &str) -> Option<&'static str> {
+    RENAMED
+        .iter()
+        .find(|(old, _)| *old == path)
+        .map(|(_, new)| *new)
+}
+
+/// Find the Value at the Dotted Path
+fn lookup<'a>(config: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    path.split('.')
+        .try_fold(config, |value, key| value.get_mut(key))
+}
+
+/// Remove the Value at the Dotted Path (if Present)
+fn take(config: &mut Value, path: &str) -> Option<Value> {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (lookup(config, parent)?, key),
+        None => (config, path),
+    };
+    parent.as_mapping_mut()?.remove(key)
+}
+
+/// Insert the Value at the Dotted Path unless it is Already Set
+fn place(config: &mut Value, path: &str, value: Value) -> bool {
+    let mut current = config;
+    let mut keys = path.split('.').peekable();
+    while let Some(key) = keys.next() {
+        let Some(map) = current.as_mapping_mut() else {
+            return false;
+        };
+        if keys.peek().is_none() {
+            if map.contains_key(key) {
+                return false;
+            }
+            map.insert(key.into(), value);
+            return true;
+        }
+        let next = map.entry(key.into()).or_insert(Value::Null);
+        if next.is_null() {
+            *next = Value::Mapping(Mapping::new());
+        }
+        current = next;
+    }
+    false
+}
+
+/// Move Deprecated Keys to their Current Location and Describe each Change
+pub fn migrate(config: &mut Value) -> Vec<String> {
+    let mut changes = vec![];
+    for (old, new) in RENAMED {
+        let Some(value) = take(config, old) else {
+            continue;
+        };
+        let rendered = serde_yaml::to_string(&value).unwrap_or_default();
+        match place(config, new, value) {
+            true => changes.push(format!("- {old}\n+ {new}: {}", rendered.trim())),
+            false => changes.push(format!("- {old} (dropped, {new} is already set)")),
+        }
+    }
+    changes
+}
+
+/// Rewrite the Config File after Backing up the Original
+pub fn run(path: &Path, dry_run: bool) -> Result<()> {
+    let content = fs::read_to_string(path)?;
+    let mut config: Value = serde_yaml::from_str(&content)?;
+    let changes = migrate(&mut config);
+    if changes.is_empty() {
+        println!("{} is up to date", path.display());
+        return Ok(());
+    }
+    for change in changes.iter() {
+        println!("{change}");
+    }
+    let comments = content
+        .lines()
+        .filter(|l| l.trim_start().starts_with('#'))
+        .count();
+    if comments > 0 {
+        eprintln!(
+            "warning: the {comments} comment lines of {} are not kept in the rewritten file",
+            path.display()
+        );
+    }
+    if dry_run {
+        return Ok(());
+    }
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    fs::copy(path, &backup)?;
+    fs::write(path, serde_yaml::to_string(&config)?)?;
+    println!(
+        "migrated {} changes in {} (original kept at {})",
+        changes.len(),
+        path.display(),
+        Path::new(&backup).display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_migrate_config() {
+        let mut value: serde_yaml::Value = serde_yaml::from_str(
+            "ignore_case: false\nsearch_regex: false\nsearch:\n  use_regex: true\n  mode: fzf\n",
+        )
+        .unwrap();
+        let changes = migrate(&mut value);
+        assert_eq!(changes.len(), 3);
+        let config: Config = serde_yaml::from_value(value.clone()).unwrap();
+        assert!(!config.search.ignore_case);
+        assert!(config.search.use_regex);
+        assert_eq!(config.search.matcher.as_deref(), Some("fzf"));
+        assert!(value.get("search_regex").is_none());
+        assert!(migrate(&mut value).is_empty());
+    }
+}
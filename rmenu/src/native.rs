@@ -0,0 +1,269 @@
+//! RMenu Lightweight Native Frontend using egui
+use std::str::FromStr;
+use std::time::Duration;
+
+use dioxus_html::input_data::keyboard_types::{Code, Modifiers};
+use eframe::egui;
+
+use crate::cli::{RMenuError, Result};
+use crate::frontend::{Frontend, Session};
+use crate::App;
+
+/// Native (egui) Frontend for Users who find the Webview too Heavy
+pub struct Native;
+
+impl Frontend for Native {
+    fn run(&self, app: App) -> Result<()> {
+        let window = &app.config.window;
+        let options = eframe::NativeOptions {
+            initial_window_size: Some(egui::vec2(
+                window.size.width as f32,
+                window.size.height as f32,
+            )),
+            initial_window_pos: Some(egui::pos2(
+                window.position.x as f32,
+                window.position.y as f32,
+            )),
+            decorated: window.decorate,
+            transparent: window.transparent,
+            always_on_top: window.always_top,
+            fullscreen: window.fullscreen.unwrap_or(false),
+            ..Default::default()
+        };
+        let title = crate::frontend::initial_title(&app);
+        let session = Session::new(app);
+        eframe::run_native(
+            &title,
+            options,
+            Box::new(|_| Box::new(NativeApp { session })),
+        )
+        .map_err(|e| RMenuError::FrontendError(e.to_string()))
+    }
+}
+
+/// Convert egui Modifiers into Keybind Modifiers
+fn convert_mods(mods: &egui::Modifiers) -> Modifiers {
+    let mut out = Modifiers::empty();
+    if mods.alt {
+        out |= Modifiers::ALT;
+    }
+    if mods.ctrl {
+        out |= Modifiers::CONTROL;
+    }
+    if mods.shift {
+        out |= Modifiers::SHIFT;
+    }
+    if mods.mac_cmd {
+        out |= Modifiers::META;
+    }
+    out
+}
+
+/// Convert egui Key into Keybind Code (e.g. `A` -> `KeyA`)
+fn convert_key(key: &egui::Key) -> Option<Code> {
+    let name = format!("{key:?}");
+    let name = match name.as_str() {
+        n if n.len() == 1 => format!("Key{n}"),
+        n if n.starts_with("Num") => format!("Digit{}", &n[3..]),
+        n => n.to_owned(),
+    };
+    Code::from_str(&name).ok()
+}
+
+/// Rendered Result Row Detached from the Session Borrow
+struct Row {
+    name: String,
+    tooltip: Option<String>,
+    marked: bool,
+    comment: Option<String>,
+    actions: Vec<String>,
+}
+
+struct NativeApp {
+    session: Session,
+}
+
+impl NativeApp {
+    /// Apply Keybinds for every Key Pressed since the Last Frame
+    fn handle_keys(&mut self, ctx: &egui::Context) {
+        let presses: Vec<(Modifiers, Code)> = ctx.input(|i| {
+            i.events
+                .iter()
+                .filter_map(|e| match e {
+                    egui::Event::Key {
+                        key,
+                        pressed: true,
+                        modifiers,
+                        ..
+                    } => Some((convert_mods(modifiers), convert_key(key)?)),
+                    _ => None,
+                })
+                .collect()
+        });
+        let mut jumped = false;
+        for (mods, key) in presses {
+            jumped |= self.session.letter_event(&mods, &key).is_some();
+            if let Some(outcome) = self.session.press(&mods, &key) {
+                self.session.finish(outcome);
+            }
+        }
+        // keep jump letters out of the search input
+        if jumped {
+            ctx.input_mut(|i| i.events.retain(|e| !matches!(e, egui::Event::Text(_))));
+        }
+    }
+
+    /// Collect Row Information for the Current Results
+    fn rows(&mut self) -> Vec<Row> {
+        let use_tooltips = self.session.config().use_tooltips;
+        let display = self.session.config().display.clone();
+        self.session
+            .marked_results()
+            .into_iter()
+            .map(|(e, marked)| Row {
+                name: display.name(&e.name),
+                tooltip: use_tooltips.then(|| crate::frontend::tooltip(e)),
+                marked,
+                comment: e.comment.as_ref().map(|c| display.comment(c)),
+                actions: e.actions.iter().skip(1).map(|a| a.name.clone()).collect(),
+            })
+            .collect()
+    }
+}
+
+impl eframe::App for NativeApp {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.handle_keys(ctx);
+        if let Some(outcome) = self.session.poll_controls() {
+            self.session.finish(outcome);
+        }
+        // keep polling for remote-control commands while idle
+        ctx.request_repaint_after(Duration::from_millis(100));
+        let placeholder = self
+            .session
+            .config()
+            .search
+            .placeholder
+            .clone()
+            .unwrap_or_default();
+        let use_comments = self.session.config().use_comments;
+        let scroll_margin = self.session.config().scroll_margin as f32;
+        let password = self.session.config().search.password;
+        let disable_search = self.session.config().search.disable;
+        let prompt = crate::frontend::prompt(self.session.config());
+        let two_line = self.session.config().display.two_line;
+        // show shortcut hints while the hint modifier is held
+        let keybinds = &self.session.config().keybinds;
+        let hinting = match keybinds.hint_mods() {
+            Some(hint) => ctx.input(|i| convert_mods(&i.modifiers).contains(hint)),
+            None => false,
+        };
+        let quick_hints: Vec<Option<String>> = (0..9)
+            .map(|i| keybinds.quick_hint(i).filter(|_| hinting))
+            .collect();
+        let action_hints: Vec<Option<String>> = (1..10)
+            .map(|i| keybinds.action_hint(i).filter(|_| hinting))
+            .collect();
+        let crumbs = self.session.breadcrumbs().join(" › ");
+        egui::CentralPanel::default().show(ctx, |ui| {
+            // render nested menu titles, search and keep it focused
+            if !crumbs.is_empty() {
+                ui.weak(crumbs);
+            }
+            let mut search = self.session.edit_buffer();
+            if !disable_search {
+                ui.horizontal(|ui| {
+                    if !prompt.is_empty() {
+                        ui.label(prompt);
+                    }
+                    let input = ui.add(
+                        egui::TextEdit::singleline(&mut *search)
+                            .password(password)
+                            .hint_text(placeholder)
+                            .lock_focus(true)
+                            .desired_width(f32::INFINITY),
+                    );
+                    input.request_focus();
+                    if input.changed() {
+                        self.session.set_search(std::mem::take(&mut *search));
+                    }
+                });
+            }
+            // render results and sub-actions of the selected entry
+            let row_height =
+                ui.text_style_height(&egui::TextStyle::Body) + ui.spacing().item_spacing.y;
+            self.session
+                .set_viewport((ui.available_height() / row_height) as usize);
+            let rows = self.rows();
+            crate::instance::publish(self.session.status());
+            if let Some(title) = self.session.changed_title() {
+                frame.set_window_title(&title);
+            }
+            let (pos, subpos) = self.session.position();
+            let missing = self.session.missing();
+            let mut top = None;
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                if missing > 0 {
+                    let min = self.session.config().search.min_length.unwrap_or_default();
+                    ui.weak(format!("type at least {min} characters"));
+                }
+                for (i, row) in rows.iter().enumerate() {
+                    let mark = match row.marked {
+                        true => "✓ ",
+                        false => "",
+                    };
+                    let mut text = match (use_comments, row.comment.as_ref()) {
+                        (true, Some(comment)) if two_line => {
+                            format!("{mark}{}\n{comment}", row.name)
+                        }
+                        (true, Some(comment)) => format!("{mark}{}  —  {comment}", row.name),
+                        _ => format!("{mark}{}", row.name),
+                    };
+                    let slot = self.session.quick_slot(i);
+                    if let Some(Some(hint)) = slot.and_then(|slot| quick_hints.get(slot)) {
+                        text = format!("[{hint}] {text}");
+                    }
+                    let mut label = ui.selectable_label(i == pos && subpos == 0, text);
+                    if top.is_none() && label.rect.bottom() > ui.clip_rect().top() {
+                        top = Some(i);
+                    }
+                    if let Some(tooltip) = row.tooltip.as_ref() {
+                        label = label.on_hover_text(tooltip);
+                    }
+                    if i == pos {
+                        let margin = scroll_margin * label.rect.height();
+                        ui.scroll_to_rect(label.rect.expand2(egui::vec2(0.0, margin)), None);
+                    }
+                    if label.clicked() {
+                        self.session.set_position(i, 0);
+                    }
+                    if label.double_clicked() {
+                        self.session.set_position(i, 0);
+                        if let Some(outcome) = self.session.handle(&crate::state::KeyEvent::Exec) {
+                            self.session.finish(outcome);
+                        }
+                    }
+                    if i != pos || subpos == 0 {
+                        continue;
+                    }
+                    for (idx, action) in row.actions.iter().enumerate() {
+                        let text = match action_hints.get(idx) {
+                            Some(Some(hint)) => format!("{action}  [{hint}]"),
+                            _ => action.to_owned(),
+                        };
+                        let label =
+                            ui.indent((i, idx), |ui| ui.selectable_label(idx + 1 == subpos, text));
+                        if label.inner.clicked() {
+                            self.session.set_position(i, idx + 1);
+                        }
+                    }
+                }
+                let hidden = self.session.hidden();
+                if hidden > 0 {
+                    ui.weak(format!("{hidden} more results hidden — refine your search"));
+                }
+            });
+            self.session.set_top(top.unwrap_or_default());
+        });
+    }
+}
@@ -0,0 +1,127 @@
+//! Shared Locations of Configuration, Cache and Runtime Files
+use std::fs::{self, DirBuilder};
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::OnceCell;
+use sha2::{Digest, Sha256};
+
+use crate::XDG_PREFIX;
+
+static CONFIG_DIR: OnceCell<PathBuf> = OnceCell::new();
+static RUNTIME_DIR: OnceCell<Option<PathBuf>> = OnceCell::new();
+
+#[inline]
+fn xdg_dirs() -> xdg::BaseDirectories {
+    xdg::BaseDirectories::with_prefix(XDG_PREFIX).expect("Failed to read xdg base dirs")
+}
+
+/// Override the Configuration Directory for the Rest of the Process
+///
+/// Overridden directories replace every xdg config path, so read-only
+/// setups (e.g. the nix store) never mix in files from `~/.config`.
+pub fn set_config_dir(dir: &Path) {
+    let dir = PathBuf::from(shellexpand::tilde(&dir.to_string_lossy()).to_string());
+    log::info!("using config directory: {dir:?}");
+    let _ = CONFIG_DIR.set(dir);
+}
+
+/// Retrieve the Configuration Directory (Override or XDG Config Home)
+pub fn config_dir() -> PathBuf {
+    match CONFIG_DIR.get() {
+        Some(dir) => dir.clone(),
+        None => xdg_dirs().get_config_home(),
+    }
+}
+
+/// Find a Specifically Named File across the Config Paths
+pub fn find_config_file(name: &str) -> Option<PathBuf> {
+    match CONFIG_DIR.get() {
+        Some(dir) => Some(dir.join(name)).filter(|p| p.exists()),
+        None => xdg_dirs().find_config_file(name),
+    }
+}
+
+/// List Files within the Named Sub-Directory across the Config Paths
+pub fn list_config_files(name: &str) -> Vec<PathBuf> {
+    let Some(dir) = CONFIG_DIR.get() else {
+        return xdg_dirs().list_config_files(name);
+    };
+    std::fs::read_dir(dir.join(name))
+        .map(|dir| dir.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+/// Retrieve the Plugin Cache Directory
+///
+/// Overridden config directories get their own cache sub-directory, so
+/// results from one set of plugins are never served for another.
+pub fn cache_dir() -> PathBuf {
+    scoped(xdg_dirs().get_cache_home())
+}
+
+/// Retrieve the Directory of Persisted User Data (e.g. Blacklists)
+pub fn data_dir() -> PathBuf {
+    scoped(xdg_dirs().get_data_home())
+}
+
+/// Retrieve the Private Runtime Directory for Sockets, Locks and FIFOs
+///
+/// The directory (`$XDG_RUNTIME_DIR/rmenu`) is created w/ `0700` permissions
+/// on first use, so confinement policies only need to allow one location.
+pub fn runtime_dir() -> Option<PathBuf> {
+    RUNTIME_DIR
+        .get_or_init(|| {
+            let dir = xdg_dirs().get_runtime_directory().ok()?.join(XDG_PREFIX);
+            match create_private(&dir) {
+                Ok(()) => Some(dir),
+                Err(err) => {
+                    log::warn!("failed to create runtime directory {dir:?}: {err}");
+                    None
+                }
+            }
+        })
+        .clone()
+}
+
+/// Retrieve the Path of a Named File within the Runtime Directory
+pub fn runtime_file(name: &str) -> Option<PathBuf> {
+    runtime_dir().map(|dir| dir.join(name))
+}
+
+/// Create a Directory (if Missing) Accessible Only by the Current User
+fn create_private(dir: &Path) -> io::Result<()> {
+    DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+}
+
+/// Separate the Directory by the Overridden Config Directory (if Any)
+fn scoped(home: PathBuf) -> PathBuf {
+    match CONFIG_DIR.get() {
+        Some(dir) => {
+            // a stable digest keeps the same directory across rust releases
+            let digest = Sha256::digest(dir.as_os_str().as_bytes());
+            let hex: String = digest[..8].iter().map(|b| format!("{b:02x}")).collect();
+            home.join(format!("config-{hex}"))
+        }
+        None => home,
+    }
+}
+
+/// Expand a Plugin Executable Path
+///
+/// With an overridden config directory, relative paths like
+/// `plugins/powermenu.sh` are resolved against it, while bare names are
+/// still searched in `$PATH`.
+pub fn expand_exec(exec: &str) -> String {
+    let exec = shellexpand::tilde(exec).to_string();
+    let Some(dir) = CONFIG_DIR.get() else {
+        return exec;
+    };
+    match exec.contains('/') && !exec.starts_with('/') && !exec.starts_with('.') {
+        true => dir.join(exec).to_string_lossy().to_string(),
+        false => exec,
+    }
+}
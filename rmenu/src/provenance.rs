@@ -0,0 +1,370 @@
+//! RMenu Configuration Introspection (Provenance and Key Reference)
+use std::cell::Cell;
+use std::collections::BTreeMap;
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, Deserializer, MapAccess, Visitor};
+use serde::Serialize;
+use serde_yaml::Value;
+
+use crate::config::{Config, PluginConfig};
+
+/// Flatten YAML Value into Dotted Leaf Paths
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Mapping(map) => {
+            for (key, value) in map.iter() {
+                let key = match key {
+                    Value::String(s) => s.to_owned(),
+                    other => serde_yaml::to_string(other).unwrap_or_default(),
+                };
+                let path = match prefix.is_empty() {
+                    true => key.trim().to_owned(),
+                    false => format!("{prefix}.{}", key.trim()),
+                };
+                flatten(&path, value, out);
+            }
+        }
+        _ => {
+            out.insert(prefix.to_owned(), value.clone());
+        }
+    }
+}
+
+/// Describe YAML Value Type for Documentation
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "optional",
+        Value::Bool(_) => "bool",
+        Value::Number(n) if n.is_f64() => "float",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Sequence(_) => "list",
+        Value::Mapping(_) => "map",
+        Value::Tagged(_) => "tagged",
+    }
+}
+
+/// Deserializer Recording the Type Requested by the Key under Probe
+struct Probe<'a>(&'a Cell<Option<&'static str>>);
+
+impl Probe<'_> {
+    fn found<T>(self, kind: &'static str) -> Result<T, serde_yaml::Error> {
+        self.0.set(Some(kind));
+        Err(de::Error::custom("type probed"))
+    }
+}
+
+macro_rules! probe_hints {
+    ($($method:ident => $kind:literal),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+                self.found($kind)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Probe<'_> {
+    type Error = serde_yaml::Error;
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.found("value")
+    }
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.found("list")
+    }
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.found("list")
+    }
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.found("map")
+    }
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.found("enum")
+    }
+    probe_hints! {
+        deserialize_any => "value",
+        deserialize_bool => "bool",
+        deserialize_i8 => "integer",
+        deserialize_i16 => "integer",
+        deserialize_i32 => "integer",
+        deserialize_i64 => "integer",
+        deserialize_u8 => "integer",
+        deserialize_u16 => "integer",
+        deserialize_u32 => "integer",
+        deserialize_u64 => "integer",
+        deserialize_f32 => "float",
+        deserialize_f64 => "float",
+        deserialize_char => "string",
+        deserialize_str => "string",
+        deserialize_string => "string",
+        deserialize_identifier => "string",
+        deserialize_bytes => "list",
+        deserialize_byte_buf => "list",
+        deserialize_seq => "list",
+        deserialize_map => "map",
+        deserialize_unit => "value",
+        deserialize_ignored_any => "value",
+    }
+}
+
+/// Deserializer Replaying the Default Config and Probing the Key at the Path
+struct OnPath<'de, 'a> {
+    value: &'de Value,
+    path: &'a [&'a str],
+    probe: &'a Cell<Option<&'static str>>,
+}
+
+impl<'de> Deserializer<'de> for OnPath<'de, '_> {
+    type Error = serde_yaml::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Mapping(map) => visitor.visit_map(Fields {
+                entries: map.iter(),
+                pending: None,
+                path: self.path,
+                probe: self.probe,
+            }),
+            other => other.deserialize_any(visitor),
+        }
+    }
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Fields of a Mapping along the Probed Path
+struct Fields<'de, 'a> {
+    entries: serde_yaml::mapping::Iter<'de>,
+    pending: Option<(&'de Value, &'de Value)>,
+    path: &'a [&'a str],
+    probe: &'a Cell<Option<&'static str>>,
+}
+
+impl<'de> MapAccess<'de> for Fields<'de, '_> {
+    type Error = serde_yaml::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        self.pending = Some((key, value));
+        seed.deserialize(key).map(Some)
+    }
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (key, value) = self
+            .pending
+            .take()
+            .ok_or_else(|| <Self::Error as de::Error>::custom("value requested before key"))?;
+        match self.path.split_first() {
+            Some((first, rest)) if key.as_str() == Some(*first) => match rest.is_empty() {
+                true => seed.deserialize(Probe(self.probe)),
+                false => seed.deserialize(OnPath {
+                    value,
+                    path: rest,
+                    probe: self.probe,
+                }),
+            },
+            _ => seed.deserialize(value),
+        }
+    }
+}
+
+/// Determine the Type of an Optional Key from the Type it Deserializes
+///
+/// The default config is replayed into the config struct, and the
+/// deserialize hint requested for the key names its type.
+fn probe<T: Default + Serialize + DeserializeOwned>(path: &str) -> Option<&'static str> {
+    let defaults = serde_yaml::to_value(T::default()).ok()?;
+    let path: Vec<&str> = path.split('.').collect();
+    let probe = Cell::new(None);
+    let _ = T::deserialize(OnPath {
+        value: &defaults,
+        path: &path,
+        probe: &probe,
+    });
+    probe.get()
+}
+
+/// Describe the Type of the Key, Probing the Type of Unset Optional Keys
+fn key_type(key: &str, value: &Value) -> String {
+    if !value.is_null() {
+        return type_name(value).to_owned();
+    }
+    let kind = match key.strip_prefix("plugins.<name>.") {
+        Some(key) => probe::<PluginConfig>(key),
+        None => probe::<Config>(key),
+    };
+    format!("optional {}", kind.unwrap_or("value"))
+}
+
+/// Render YAML Value as Single-Line Default Description
+fn render_value(value: &Value) -> String {
+    match value {
+        Value::Null => "none".to_owned(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => format!("{s:?}"),
+        Value::Sequence(seq) => {
+            let items: Vec<String> = seq.iter().map(render_value).collect();
+            format!("[{}]", items.join(", "))
+        }
+        other => serde_yaml::to_string(other)
+            .map(|s| s.trim().to_owned())
+            .unwrap_or_default(),
+    }
+}
+
+/// Generate Reference of every Configuration Key w/ Type and Default
+pub fn help_config() -> String {
+    let mut keys = leaves(&Config::default());
+    let value = serde_yaml::to_value(PluginConfig::default()).expect("Failed to Serialize Plugin");
+    flatten("plugins.<name>", &value, &mut keys);
+    let kinds: Vec<String> = keys.iter().map(|(k, v)| key_type(k, v)).collect();
+    let width = keys.keys().map(|k| k.len()).max().unwrap_or_default();
+    let kind_width = kinds.iter().map(|k| k.len()).max().unwrap_or_default();
+    keys.iter()
+        .zip(kinds.iter())
+        .map(|((key, value), kind)| {
+            let default = render_value(value);
+            format!("{key:width$}  {kind:kind_width$}  default: {default}\n")
+        })
+        .collect()
+}
+
+#[inline]
+fn leaves(config: &Config) -> BTreeMap<String, Value> {
+    let mut out = BTreeMap::new();
+    let value = serde_yaml::to_value(config).expect("Failed to Serialize Config");
+    flatten("", &value, &mut out);
+    out
+}
+
+/// Track which Configuration Source last Modified each Setting
+pub struct Provenance {
+    last: BTreeMap<String, Value>,
+    sources: BTreeMap<String, String>,
+}
+
+impl Provenance {
+    /// Spawn new Tracker using the Default Configuration as a Baseline
+    pub fn new() -> Self {
+        Self {
+            last: leaves(&Config::default()),
+            sources: BTreeMap::new(),
+        }
+    }
+
+    /// Record all Settings Changed by the Specified Source
+    pub fn record(&mut self, source: &str, config: &Config) {
+        let current = leaves(config);
+        for (path, value) in current.iter() {
+            if self.last.get(path) != Some(value) {
+                self.sources.insert(path.to_owned(), source.to_owned());
+            }
+        }
+        self.last = current;
+    }
+
+    /// Render Configuration as YAML w/ Provenance Comments
+    pub fn render(&self, config: &Config) -> Result<String, serde_yaml::Error> {
+        let yaml = serde_yaml::to_string(config)?;
+        let mut stack: Vec<(usize, String)> = vec![];
+        let mut lines = vec![];
+        for line in yaml.lines() {
+            let trimmed = line.trim_start();
+            let indent = line.len() - trimmed.len();
+            let key = match trimmed.starts_with("- ") {
+                true => None,
+                false => trimmed.split_once(':').map(|(k, _)| k.trim().to_owned()),
+            };
+            let Some(key) = key else {
+                lines.push(line.to_owned());
+                continue;
+            };
+            while stack.last().map(|(i, _)| *i >= indent).unwrap_or(false) {
+                stack.pop();
+            }
+            stack.push((indent, key));
+            let path = stack
+                .iter()
+                .map(|(_, k)| k.as_str())
+                .collect::<Vec<&str>>()
+                .join(".");
+            match self.sources.get(&path) {
+                Some(source) => lines.push(format!("{line}  # {source}")),
+                None => lines.push(line.to_owned()),
+            }
+        }
+        Ok(lines.join("\n") + "\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optional_key_types() {
+        assert_eq!(key_type("page_load", &Value::Null), "optional float");
+        assert_eq!(key_type("page_size", &Value::Null), "optional integer");
+        assert_eq!(key_type("window.dark_mode", &Value::Null), "optional bool");
+        assert_eq!(
+            key_type("search.placeholder", &Value::Null),
+            "optional string"
+        );
+        assert_eq!(
+            key_type("plugins.<name>.http", &Value::Null),
+            "optional map"
+        );
+        assert_eq!(key_type("jump_dist", &Value::from(5)), "integer");
+    }
+}
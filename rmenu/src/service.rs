@@ -0,0 +1,211 @@
+//! Long-Running Service Plugins Supervised by the Daemon
+//!
+//! Service plugins keep running under `rmenu daemon` and publish their
+//! entries into the daemon channel of their name, where every batch of
+//! messages ended by an empty line replaces the previous one. Exited
+//! services are restarted w/ exponential backoff, and receive a `shutdown`
+//! line on stdin when the daemon stops.
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use rmenu_plugin::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::{plugin_args, RMenuError, Result};
+use crate::config::Config;
+
+static SERVICES: Lazy<Mutex<BTreeMap<String, Service>>> = Lazy::new(|| Mutex::new(BTreeMap::new()));
+static STOPPING: AtomicBool = AtomicBool::new(false);
+
+/// Delay before the First Restart, Doubled after every Quick Exit
+static MIN_BACKOFF: Duration = Duration::from_secs(1);
+static MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Runtime after which an Exit no longer Counts as a Crash Loop
+static STABLE: Duration = Duration::from_secs(30);
+/// Time Services are Given to Exit after the Shutdown Message
+static GRACE: Duration = Duration::from_secs(2);
+
+/// Supervision State of a Service Reported by `--list-plugins`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Status {
+    pub pid: Option<u32>,
+    pub restarts: u32,
+    pub last_exit: Option<String>,
+    /// Seconds until the Next Restart while Backing Off
+    pub retry_in: Option<u64>,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.pid, self.retry_in) {
+            (Some(pid), _) => write!(f, "running (pid {pid}")?,
+            (None, Some(secs)) => write!(f, "restarting in {secs}s (")?,
+            (None, None) => write!(f, "stopped (")?,
+        }
+        if self.pid.is_none() {
+            let exit = self.last_exit.as_deref().unwrap_or("never started");
+            write!(f, "{exit}")?;
+        }
+        write!(f, ", {} restarts)", self.restarts)
+    }
+}
+
+/// Running Service w/ the Pipe used to Request its Shutdown
+#[derive(Default)]
+struct Service {
+    status: Status,
+    stdin: Option<ChildStdin>,
+}
+
+/// Update the Supervision State of the Service
+fn update(name: &str, f: impl FnOnce(&mut Service)) {
+    let mut services = SERVICES.lock().expect("Failed to Access Global Mutex");
+    f(services.entry(name.to_owned()).or_default());
+}
+
+/// Retrieve the Supervision State of the Service (if Started)
+pub fn status(name: &str) -> Option<Status> {
+    let services = SERVICES.lock().expect("Failed to Access Global Mutex");
+    services.get(name).map(|s| s.status.clone())
+}
+
+/// Run the Service once, Publishing its Batches until it Exits
+fn run(name: &str, exec: &[String]) -> Result<String> {
+    let args = plugin_args(exec);
+    let main = args
+        .get(0)
+        .ok_or_else(|| RMenuError::InvalidPlugin(name.to_owned()))?;
+    let mut child = Command::new(main)
+        .args(&args[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let pid = child.id();
+    crate::signals::track_child(pid);
+    log::info!("started service {name:?} (pid {pid})");
+    let stdin = child.stdin.take();
+    update(name, |s| {
+        s.status.pid = Some(pid);
+        s.status.retry_in = None;
+        s.stdin = stdin;
+    });
+    let stdout = child.stdout.take().expect("Failed to Read Service Stdout");
+    let mut batch: Vec<Message> = vec![];
+    for line in BufReader::new(stdout).lines().filter_map(|l| l.ok()) {
+        if !line.trim().is_empty() {
+            match serde_json::from_str(&line) {
+                Ok(msg) => batch.push(msg),
+                Err(err) => log::warn!("invalid message from service {name:?}: {err}"),
+            }
+            continue;
+        }
+        crate::daemon::publish(name, std::mem::take(&mut batch));
+    }
+    let status = child.wait()?;
+    crate::signals::untrack_child(pid);
+    update(name, |s| {
+        s.status.pid = None;
+        s.stdin = None;
+    });
+    Ok(status.to_string())
+}
+
+/// Keep the Service Running, Restarting it w/ Backoff whenever it Exits
+fn supervise(name: String, exec: Vec<String>) {
+    let mut backoff = MIN_BACKOFF;
+    while !STOPPING.load(Ordering::SeqCst) {
+        let started = Instant::now();
+        let exit = match run(&name, &exec) {
+            Ok(exit) => exit,
+            Err(err) => format!("failed to start: {err}"),
+        };
+        if STOPPING.load(Ordering::SeqCst) {
+            break;
+        }
+        if started.elapsed() >= STABLE {
+            backoff = MIN_BACKOFF;
+        }
+        log::warn!("service {name:?} {exit}, restarting in {backoff:?}");
+        update(&name, |s| {
+            s.status.last_exit = Some(exit);
+            s.status.retry_in = Some(backoff.as_secs());
+            s.status.restarts += 1;
+        });
+        std::thread::sleep(backoff);
+        backoff = std::cmp::min(backoff * 2, MAX_BACKOFF);
+    }
+}
+
+/// Start Supervising every Service Plugin of the Configuration
+pub fn start(config: &Config) {
+    STOPPING.store(false, Ordering::SeqCst);
+    for (name, plugin) in config.plugins.iter().filter(|(_, p)| p.service) {
+        update(name, |_| {});
+        let (name, exec) = (name.to_owned(), plugin.exec.clone());
+        std::thread::spawn(move || supervise(name, exec));
+    }
+}
+
+/// Ask every Running Service to Exit and Wait for them to Comply
+///
+/// Services still running after the grace period are left to be
+/// terminated along w/ the other tracked plugin processes.
+pub fn shutdown() {
+    STOPPING.store(true, Ordering::SeqCst);
+    let mut running: Vec<String> = vec![];
+    if let Ok(mut services) = SERVICES.lock() {
+        for (name, service) in services.iter_mut() {
+            if let Some(mut stdin) = service.stdin.take() {
+                log::info!("stopping service {name:?}");
+                let _ = writeln!(stdin, "shutdown");
+                running.push(name.to_owned());
+            }
+        }
+    }
+    let deadline = Instant::now() + GRACE;
+    while Instant::now() < deadline {
+        if running
+            .iter()
+            .all(|name| status(name).and_then(|s| s.pid).is_none())
+        {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixture_plugin;
+
+    #[test]
+    fn test_service_supervision() {
+        use std::time::{Duration, Instant};
+        let mut config = Config::default();
+        let mut plugin = fixture_plugin("service.sh");
+        plugin.service = true;
+        config.plugins.insert("test-service".to_owned(), plugin);
+        start(&config);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut messages = vec![];
+        while messages.is_empty() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+            messages = crate::daemon::messages("test-service");
+        }
+        assert_eq!(messages.len(), 1);
+        let before = status("test-service").unwrap();
+        assert!(before.pid.is_some());
+        assert_eq!(before.restarts, 0);
+        shutdown();
+        let after = status("test-service").unwrap();
+        assert_eq!(after.pid, None);
+        assert_eq!(after.restarts, 0);
+    }
+}
@@ -0,0 +1,61 @@
+//! RMenu Signal Handling and Clean Teardown
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+use signal_hook::iterator::Signals;
+
+static CHILDREN: Lazy<Mutex<Vec<u32>>> = Lazy::new(|| Mutex::new(vec![]));
+static RUNTIME_FILES: Lazy<Mutex<Vec<PathBuf>>> = Lazy::new(|| Mutex::new(vec![]));
+
+/// Track Running Plugin Process to Terminate on Teardown
+pub fn track_child(pid: u32) {
+    if let Ok(mut children) = CHILDREN.lock() {
+        children.push(pid);
+    }
+}
+
+/// Stop Tracking Plugin Process after it Exits
+pub fn untrack_child(pid: u32) {
+    if let Ok(mut children) = CHILDREN.lock() {
+        children.retain(|p| *p != pid);
+    }
+}
+
+/// Register Runtime File (Socket/Lock) to Remove on Teardown
+pub fn remove_on_exit(path: PathBuf) {
+    if let Ok(mut files) = RUNTIME_FILES.lock() {
+        files.push(path);
+    }
+}
+
+/// Stop Services, Terminate Plugin Processes and Remove Runtime Files
+pub fn teardown() {
+    crate::service::shutdown();
+    if let Ok(mut children) = CHILDREN.lock() {
+        for pid in children.drain(..) {
+            log::debug!("terminating plugin process {pid}");
+            unsafe { libc::kill(pid as libc::pid_t, SIGTERM) };
+        }
+    }
+    if let Ok(mut files) = RUNTIME_FILES.lock() {
+        for path in files.drain(..) {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Install Signal Handlers that Teardown and Exit w/ `128 + signal`
+pub fn install() -> std::io::Result<()> {
+    let mut signals = Signals::new([SIGINT, SIGTERM, SIGHUP])?;
+    std::thread::spawn(move || {
+        if let Some(signal) = signals.forever().next() {
+            log::info!("received signal {signal}, shutting down");
+            teardown();
+            std::process::exit(128 + signal);
+        }
+    });
+    Ok(())
+}
@@ -1,133 +1,114 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Instant;
+
 use dioxus::prelude::{use_eval, use_ref, Scope, UseRef};
-use regex::Regex;
+use dioxus_html::input_data::keyboard_types::{Code, Modifiers};
 use rmenu_plugin::Entry;
+use zeroize::Zeroizing;
+
+pub use rmenu_core::state::{char_code, key_event, InnerState, KeyEvent};
 
 use crate::config::Config;
-use crate::exec::execute;
-use crate::search::new_searchfn;
+use crate::exec::{bind_actions, echo_secret, execute, execute_many, Menu};
+use crate::instance::{queue_sources, Control, Status};
+use crate::search::Normalized;
 use crate::App;
 
 #[inline]
 fn scroll<T>(cx: Scope<T>, pos: usize) {
+    scroll_margin(cx, pos, pos)
+}
+
+/// Scroll the Row at the Margin into View, then the Highlighted Row
+#[inline]
+fn scroll_margin<T>(cx: Scope<T>, pos: usize, margin: usize) {
     let eval = use_eval(cx);
-    let js = format!("document.getElementById(`result-{pos}`).scrollIntoView(false)");
+    let js = format!(
+        "for (const i of [{margin}, {pos}]) \
+        document.getElementById(`result-${{i}}`)?.scrollIntoView({{block: `nearest`}})"
+    );
     let _ = eval(&js);
 }
 
-#[derive(Debug, PartialEq, Clone)]
-pub enum KeyEvent {
-    Exec,
-    Exit,
-    MovePrev,
-    MoveNext,
-    OpenMenu,
-    CloseMenu,
-    JumpNext,
-    JumpPrev,
+/// Scroll the Row to the Top once the Next Render Materialized it
+#[inline]
+fn scroll_later<T>(cx: Scope<T>, pos: usize) {
+    let eval = use_eval(cx);
+    let js = format!(
+        "setTimeout(() => document.getElementById(`result-{pos}`)\
+        ?.scrollIntoView({{block: `start`}}), 50)"
+    );
+    let _ = eval(&js);
 }
 
-pub struct InnerState {
-    pos: usize,
-    subpos: usize,
-    page: usize,
-    search: String,
-    event: Option<KeyEvent>,
-    search_regex: Option<Regex>,
+/// Revert Rejected Input and Flash Visual Feedback
+#[inline]
+fn reject_input<T>(cx: Scope<T>, search: &str) {
+    let eval = use_eval(cx);
+    let value = serde_json::to_string(search).unwrap_or_default();
+    let js = format!(
+        "const e = document.getElementById(`search`); e.value = {value}; \
+        e.classList.add(`rejected`); setTimeout(() => e.classList.remove(`rejected`), 300);"
+    );
+    let _ = eval(&js);
 }
 
-impl InnerState {
-    /// Move X Primary Results Upwards
-    pub fn move_up(&mut self, x: usize) {
-        self.subpos = 0;
-        self.pos = std::cmp::max(self.pos, x) - x;
-    }
-
-    /// Move X Primary Results Downwards
-    pub fn move_down(&mut self, x: usize, max: usize) {
-        self.subpos = 0;
-        self.pos = std::cmp::min(self.pos + x, max - 1)
-    }
-
-    /// Jump a spefified number of results upwards
-    #[inline]
-    pub fn jump_up(&mut self, jump: usize) {
-        self.move_up(jump)
-    }
-
-    /// Jump a specified number of results downwards
-    pub fn jump_down(&mut self, jump: usize, results: &Vec<&Entry>) {
-        let max = std::cmp::max(results.len(), 1);
-        self.move_down(jump, max);
-    }
-
-    /// Move Up Once With Context of SubMenu
-    pub fn move_prev(&mut self) {
-        if self.subpos > 0 {
-            self.subpos -= 1;
-            return;
-        }
-        self.move_up(1);
-    }
-
-    /// Move Down Once With Context of SubMenu
-    pub fn move_next(&mut self, results: &Vec<&Entry>) {
-        if let Some(result) = results.get(self.pos) {
-            if self.subpos > 0 && self.subpos < result.actions.len() - 1 {
-                self.subpos += 1;
-                return;
-            }
-        }
-        self.jump_down(1, results)
-    }
+/// Nested Menu Change to Apply before the Next Render
+pub enum Nested {
+    Enter(String, Arc<[Entry]>, Arc<[Normalized]>),
+    /// Nested Menu still to be Loaded w/ its Title
+    Open(String, Menu),
+    Submit(String, Vec<String>),
+    Back,
 }
 
 #[derive(PartialEq)]
 pub struct AppState<'a> {
     state: &'a UseRef<InnerState>,
     app: &'a App,
-    results: Vec<&'a Entry>,
+    results: Rc<[&'a Entry]>,
 }
 
 impl<'a> AppState<'a> {
     /// Spawn new Application State Tracker
     pub fn new<T>(cx: Scope<'a, T>, app: &'a App) -> Self {
         Self {
-            state: use_ref(cx, || InnerState {
-                pos: 0,
-                subpos: 0,
-                page: 0,
-                search: "".to_string(),
-                event: None,
-                search_regex: app.config.search.restrict.clone().and_then(|mut r| {
-                    if !r.starts_with('^') {
-                        r = format!("^{r}")
-                    };
-                    if !r.ends_with('$') {
-                        r = format!("{r}$")
-                    };
-                    match Regex::new(&r) {
-                        Ok(regex) => Some(regex),
-                        Err(err) => {
-                            log::error!("Invalid Regex Expression: {:?}", err);
-                            None
-                        }
-                    }
-                }),
-            }),
+            state: use_ref(cx, || InnerState::new(&app.config)),
             app,
-            results: vec![],
+            results: Rc::new([]),
         }
     }
 
-    /// Create Partial Copy of Self (Not Including Results)
+    /// Retrieve the Page of the Selection and the Number of Pages
+    #[inline]
+    pub fn pages(&self) -> (usize, usize) {
+        self.state
+            .with(|s| s.pages(&self.app.config, &self.results))
+    }
+
+    /// Render the Next Page of Results w/o Moving the Selection
+    #[inline]
+    pub fn load_page(&self) {
+        self.state
+            .with_mut(|s| s.load_page(&self.app.config, &self.results))
+    }
+
+    /// Create Partial Copy of Self (Sharing the Current Results)
     pub fn partial_copy(&self) -> Self {
         Self {
             state: self.state,
             app: self.app,
-            results: vec![],
+            results: Rc::clone(&self.results),
         }
     }
 
+    /// Retrieve Entry at the Specified Index of the Current Results
+    #[inline]
+    pub fn result(&self, index: usize) -> Option<&'a Entry> {
+        self.results.get(index).copied()
+    }
+
     /// Retrieve Configuration
     #[inline]
     pub fn config(&self) -> &Config {
@@ -137,178 +118,356 @@ impl<'a> AppState<'a> {
     /// Retrieve Current Position State
     #[inline]
     pub fn position(&self) -> (usize, usize) {
-        self.state.with(|s| (s.pos, s.subpos))
+        self.state.with(|s| s.position())
     }
 
     /// Retrieve Current Search String
     #[inline]
     pub fn search(&self) -> String {
-        self.state.with(|s| s.search.clone())
+        self.state.with(|s| s.search().to_owned())
+    }
+
+    /// Retrieve the Search Bound to Action Placeholders (Empty when Masked)
+    #[inline]
+    pub fn query(&self) -> String {
+        self.state.with(|s| s.query().to_owned())
+    }
+
+    /// Retrieve the Number of Characters in the Search w/o Copying it
+    #[inline]
+    pub fn search_length(&self) -> usize {
+        self.state.with(|s| s.search().chars().count())
     }
 
-    /// Execute the Current Action
+    /// Execute the Marked Actions or the Current Action
     pub fn execute(&self) {
-        let (pos, subpos) = self.position();
-        log::debug!("execute {pos} {subpos}");
-        let Some(result) = self.results.get(pos) else {
+        let config = &self.app.config;
+        if let Some(secret) = self.state.with(|s| s.secret(config, &self.results)) {
+            self.wipe();
+            return echo_secret(secret);
+        }
+        let selected = self.state.with(|s| s.actions(&self.results));
+        crate::history::record(&selected, config);
+        let actions = queue_sources(bind_actions(selected, &self.query()));
+        if actions.is_empty() {
+            return;
+        }
+        execute_many(&actions, config);
+    }
+
+    /// Hide the Highlighted Entry (and Blacklist it when Configured)
+    pub fn hide_entry(&self) {
+        let entry = self.state.with_mut(|s| s.hide_entry(&self.results));
+        if let Some(entry) = entry.filter(|_| self.app.config.persist_hidden) {
+            crate::blacklist::persist(entry);
+        }
+    }
+
+    /// Overwrite the Search and its Copies before Closing
+    #[inline]
+    pub fn wipe(&self) {
+        self.state.with_mut(|s| s.wipe())
+    }
+
+    /// Execute the Specified Action of the Current Entry
+    pub fn execute_alt(&self, index: usize) {
+        let Some((entry, action)) = self.state.with(|s| s.alt_action(&self.results, index)) else {
             return;
         };
-        log::debug!("result: {result:?}");
-        let Some(action) = result.actions.get(subpos) else {
+        let selected = vec![(entry.clone(), action.clone())];
+        crate::history::record(&selected, &self.app.config);
+        let actions = bind_actions(selected, &self.query());
+        if let [action] = queue_sources(actions).as_slice() {
+            execute(action, &self.app.config);
+        }
+    }
+
+    /// Select and Execute the Result at the Given Index
+    pub fn execute_quick(&self, index: usize) {
+        let Some((entry, action)) = self
+            .state
+            .with_mut(|s| s.quick_action(&self.results, index))
+        else {
             return;
         };
-        log::debug!("action: {action:?}");
-        execute(action, self.app.config.terminal.clone());
+        let selected = vec![(entry.clone(), action.clone())];
+        crate::history::record(&selected, &self.app.config);
+        let actions = bind_actions(selected, &self.query());
+        if let [action] = queue_sources(actions).as_slice() {
+            execute(action, &self.app.config);
+        }
+    }
+
+    /// Check if Shortcut Hints are Shown
+    #[inline]
+    pub fn hinting(&self) -> bool {
+        self.state.with(|s| s.hinting())
+    }
+
+    /// Show or Hide Shortcut Hints (Only Re-Rendering on Change)
+    pub fn set_hinting(&self, hinting: bool) {
+        if self.hinting() != hinting {
+            self.state.with_mut(|s| s.set_hinting(hinting));
+        }
+    }
+
+    /// Record the First Visible Result Row (Only Re-Rendering on Change)
+    pub fn set_top(&self, top: usize) {
+        if self.state.with(|s| s.top()) != top {
+            self.state.with_mut(|s| s.set_top(top));
+        }
+    }
+
+    /// Retrieve the Quick-Exec Slot of the Result at the Given Index (if Any)
+    #[inline]
+    pub fn quick_slot(&self, index: usize) -> Option<usize> {
+        self.state.with(|s| s.quick_slot(index))
     }
 
     /// Set Current Key/Action for Later Evaluation
     #[inline]
     pub fn set_event(&self, event: KeyEvent) {
-        self.state.with_mut(|s| s.event = Some(event));
+        self.state.with_mut(|s| s.set_event(Some(event)));
+    }
+
+    /// Retrieve the Submitted Query and the Plugin Command Answering it
+    fn submit(&self) -> Option<(String, Vec<String>)> {
+        let config = &self.app.config;
+        let (plugin, query) = self.state.with(|s| s.submit(config, &self.results))?;
+        let args = crate::exec::submit_args(config, &plugin, &query)?;
+        Some((query, args))
+    }
+
+    /// Resolve the Nested Menu of the Current Action (if Any)
+    ///
+    /// The menu is only loaded by the caller, so its command never
+    /// blocks the GUI thread.
+    fn submenu(&self) -> Option<Option<Nested>> {
+        let (title, args) = self.state.with(|s| s.submenu(&self.results))?;
+        let menu = Menu::new(&self.app.config, &args);
+        Some(menu.map(|menu| Nested::Open(title, menu)))
+    }
+
+    /// Remember the Current Menu and Start Fresh in a Nested Menu
+    #[inline]
+    pub fn enter(&self, title: String) {
+        self.state.with_mut(|s| s.enter(title))
+    }
+
+    /// Return to the Parent Menu w/ its Search and Position
+    #[inline]
+    pub fn back(&self) {
+        self.state.with_mut(|s| s.back());
+    }
+
+    /// Retrieve Titles of the Nested Menus Entered so Far
+    pub fn breadcrumbs(&self) -> Vec<String> {
+        self.state
+            .with(|s| s.breadcrumbs().into_iter().map(|s| s.to_owned()).collect())
+    }
+
+    /// Check if the Keypress Returns to the Parent Menu
+    #[inline]
+    pub fn is_back(&self, key: &Code) -> bool {
+        self.state.with(|s| s.is_back(key))
+    }
+
+    /// Collect Live Search Counters for the Current State
+    #[inline]
+    pub fn status(&self) -> Status {
+        self.state
+            .with(|s| crate::frontend::status(s, &self.app.config))
+    }
+
+    /// Check if the Next Letter Pressed Jumps to a Matching Entry
+    #[inline]
+    pub fn jumping(&self) -> bool {
+        self.state.with(|s| s.jumping())
+    }
+
+    /// Translate a Keypress into a Jump while Waiting for a Letter
+    #[inline]
+    pub fn letter_event(&self, mods: &Modifiers, key: &Code) -> Option<KeyEvent> {
+        self.state
+            .with(|s| s.letter_event(&self.app.config, mods, key))
     }
 
     /// React to Previously Activated KeyEvents
-    pub fn handle_events(&self, cx: Scope<'a, App>) {
-        match self.state.with(|s| s.event.clone()) {
+    ///
+    /// Returns the nested menu to enter or leave, which the caller swaps
+    /// in before generating the next results.
+    pub fn handle_events(&self, cx: Scope<'a, App>) -> Option<Nested> {
+        let mut nested = None;
+        match self.state.with(|s| s.event()) {
             None => {}
             Some(event) => {
                 match event {
-                    KeyEvent::Exit => std::process::exit(0),
-                    KeyEvent::Exec => self.execute(),
-                    KeyEvent::OpenMenu => self.open_menu(),
-                    KeyEvent::CloseMenu => self.close_menu(),
-                    KeyEvent::MovePrev => {
-                        self.move_prev();
-                        let pos = self.position().0;
-                        scroll(cx, if pos <= 3 { pos } else { pos + 3 })
+                    KeyEvent::Exit => {
+                        self.wipe();
+                        crate::signals::teardown();
+                        std::process::exit(0)
                     }
-                    KeyEvent::MoveNext => {
-                        self.move_next();
-                        scroll(cx, self.position().0 + 3)
+                    KeyEvent::Exec if !self.state.with(|s| s.is_submittable()) => {
+                        if self.app.config.search.restrict_input {
+                            reject_input(cx, &self.search())
+                        }
                     }
-                    KeyEvent::JumpPrev => {
-                        self.jump_prev();
-                        let pos = self.position().0;
-                        scroll(cx, if pos <= 3 { pos } else { pos + 3 })
+                    KeyEvent::Exec => match self.submit() {
+                        Some((query, args)) => nested = Some(Nested::Submit(query, args)),
+                        None => match self.submenu() {
+                            Some(menu) => nested = menu,
+                            None => self.execute(),
+                        },
+                    },
+                    KeyEvent::Back => nested = Some(Nested::Back),
+                    KeyEvent::ExecAlt(index) => self.execute_alt(index),
+                    KeyEvent::QuickExec(index) => self.execute_quick(index),
+                    KeyEvent::Paste => self.paste(cx),
+                    KeyEvent::HideEntry => self.hide_entry(),
+                    KeyEvent::ToggleTop => crate::instance::queue(Control::ToggleTop),
+                    KeyEvent::ToggleSticky => crate::instance::queue(Control::ToggleSticky),
+                    _ => self
+                        .state
+                        .with_mut(|s| s.navigate(&event, &self.app.config, &self.results)),
+                };
+                let margin = self.app.config.scroll_margin;
+                let pos = self.position().0;
+                match event {
+                    KeyEvent::MovePrev | KeyEvent::JumpPrev => {
+                        scroll_margin(cx, pos, pos.saturating_sub(margin))
                     }
-                    KeyEvent::JumpNext => {
-                        self.jump_next();
-                        scroll(cx, self.position().0 + 3)
+                    KeyEvent::MoveNext | KeyEvent::JumpNext => {
+                        let last = self.results.len().saturating_sub(1);
+                        scroll_margin(cx, pos, std::cmp::min(pos + margin, last))
                     }
+                    KeyEvent::NextPage | KeyEvent::PrevPage => scroll_later(cx, pos),
+                    _ => {}
                 };
-                self.state.with_mut(|s| s.event = None);
+                self.state.with_mut(|s| s.set_event(None));
             }
         }
+        nested
     }
 
-    /// Generate and return Results PTR
-    pub fn results(&mut self, entries: &'a Vec<Entry>) -> Vec<&'a Entry> {
-        let ratio = self.app.config.page_load;
-        let page_size = self.app.config.page_size;
-        let (pos, page, search) = self.state.with(|s| (s.pos, s.page, s.search.clone()));
-        // determine current page based on position and configuration
-        let next = (pos % page_size) as f64 / page_size as f64 > ratio;
-        let pos_page = (pos + 1) / page_size + 1 + next as usize;
-        let new_page = std::cmp::max(pos_page, page);
-        let index = page_size * new_page;
-        // update page counter if higher than before
-        if new_page > page {
-            self.state.with_mut(|s| s.page = new_page);
-        }
-        // render results and stop at page-limit
-        let sfn = new_searchfn(&self.app.config, &search);
-        self.results = entries.iter().filter(|e| sfn(e)).take(index).collect();
-        self.results.clone()
+    /// Generate and return Shared Results PTR
+    ///
+    /// The page size is tuned to the visible rows afterwards, without
+    /// triggering another render.
+    pub fn results(
+        &mut self,
+        entries: &'a [Entry],
+        normalized: &[Normalized],
+        rows: usize,
+    ) -> Rc<[&'a Entry]> {
+        let config = &self.app.config;
+        let started = Instant::now();
+        self.results = self
+            .state
+            .with_mut(|s| s.results(config, entries, normalized))
+            .into();
+        let elapsed = started.elapsed();
+        let rendered = self.results.len();
+        self.state
+            .write_silent()
+            .tune(config, rows, rendered, elapsed);
+        Rc::clone(&self.results)
+    }
+
+    /// Check if the Entry is Marked for Execution
+    #[inline]
+    pub fn is_marked(&self, entry: &Entry) -> bool {
+        self.state.with(|s| s.is_marked(entry))
+    }
+
+    /// Retrieve Number of Matching Results Hidden by the Entry Limit
+    #[inline]
+    pub fn hidden(&self) -> usize {
+        self.state.with(|s| s.hidden())
+    }
+
+    /// Retrieve Number of Characters Missing to Meet the Minimum Length
+    #[inline]
+    pub fn missing(&self) -> usize {
+        self.state.with(|s| s.missing(&self.app.config))
     }
 
     /// Update Search and Reset Position
     pub fn set_search(&self, cx: Scope<'_, App>, search: String) {
-        // confirm search meets required criteria
-        if let Some(min) = self.app.config.search.min_length.as_ref() {
-            if search.len() < *min {
-                return;
-            }
-        }
-        if let Some(min) = self.app.config.search.min_length.as_ref() {
-            if search.len() < *min {
-                return;
-            }
-        }
-        let is_match = self.state.with(|s| {
-            s.search_regex
-                .as_ref()
-                .map(|r| r.is_match(&search))
-                .unwrap_or(true)
-        });
-        if !is_match {
+        let config = &self.app.config;
+        let too_long = config
+            .search
+            .max_length
+            .map(|max| search.chars().count() > max)
+            .unwrap_or(false);
+        let restricted =
+            config.search.restrict_input && !self.state.with(|s| s.is_partial(&search));
+        if too_long || restricted {
+            reject_input(cx, &self.search());
             return;
         }
-        // update search w/ new content
-        self.state.with_mut(|s| {
-            s.pos = 0;
-            s.subpos = 0;
-            s.search = search;
-        });
-        scroll(cx, 0);
+        if self.state.with_mut(|s| s.set_search(config, search)) {
+            crate::stream::search(config, &self.search());
+            scroll(cx, 0);
+        }
     }
 
-    /// Manually Set Position/SubPosition (with Click)
-    pub fn set_position(&self, pos: usize, subpos: usize) {
-        self.state.with_mut(|s| {
-            s.pos = pos;
-            s.subpos = subpos;
-        })
-    }
-
-    /// Automatically Increase PageCount When Nearing Bottom
-    // pub fn scroll_down(&self) {
-    //     self.state.with_mut(|s| {
-    //         if self.app.config.page_size * s.page < self.app.entries.len() {
-    //             s.page += 1;
-    //         }
-    //     });
-    // }
-
-    /// Move Position To SubMenu if it Exists
-    pub fn open_menu(&self) {
-        let pos = self.state.with(|s| s.pos);
-        if let Some(result) = self.results.get(pos) {
-            if result.actions.len() > 1 {
-                self.state.with_mut(|s| s.subpos += 1);
-            }
+    /// Append Clipboard Contents to the Current Search
+    pub fn paste(&self, cx: Scope<'_, App>) {
+        if let Some(text) = crate::clipboard::read() {
+            let text = Zeroizing::new(text);
+            let mut search = self.state.with(|s| s.edit_buffer());
+            search.reserve(text.len());
+            search.push_str(&text);
+            self.set_search(cx, std::mem::take(&mut *search));
         }
     }
 
-    // Reset and Close SubMenu Position
+    /// Forget Previous Matches and Selection after Entries were Replaced
     #[inline]
-    pub fn close_menu(&self) {
-        self.state.with_mut(|s| s.subpos = 0);
+    pub fn refresh(&self) {
+        self.state.with_mut(|s| s.refresh())
     }
 
-    /// Move Up Once With Context of SubMenu
+    /// Forget Previous Matches after Entries were Updated in Place
     #[inline]
-    pub fn move_prev(&self) {
-        self.state.with_mut(|s| s.move_prev());
+    pub fn update(&self) {
+        self.state.with_mut(|s| s.update())
     }
 
-    /// Move Down Once With Context of SubMenu
+    /// Replace the Configured Scopes w/ the ones of Reloaded Entries
     #[inline]
-    pub fn move_next(&self) {
-        self.state.with_mut(|s| s.move_next(&self.results))
+    pub fn set_scopes(&self, scopes: Vec<crate::config::Scope>) {
+        self.state.with_mut(|s| s.set_scopes(scopes))
     }
 
-    /// Jump a Configured Distance Up the Results
-    #[inline]
-    pub fn jump_prev(&self) {
-        let distance = self.app.config.jump_dist;
-        self.state.with_mut(|s| s.jump_up(distance))
+    /// Apply Remote-Control Command to the Current State
+    pub fn control(&self, cx: Scope<'_, App>, control: Control) {
+        match control {
+            Control::Focus
+            | Control::Refresh
+            | Control::RefreshSource(_)
+            | Control::Update
+            | Control::Streamed(..)
+            | Control::Loaded(..)
+            | Control::ToggleTop
+            | Control::ToggleSticky => {}
+            Control::DisableSource(name) => self.state.with_mut(|s| s.disable_source(&name)),
+            Control::SetQuery(query) => self.set_search(cx, query),
+            Control::SelectIndex(index) => {
+                let pos = std::cmp::min(index, self.results.len().saturating_sub(1));
+                self.set_position(pos, 0);
+                scroll(cx, pos);
+            }
+            Control::Close => {
+                self.wipe();
+                crate::signals::teardown();
+                std::process::exit(0);
+            }
+        }
     }
 
-    /// Jump a Configured Distance Down the Results
-    #[inline]
-    pub fn jump_next(&self) {
-        let distance = self.app.config.jump_dist;
-        self.state
-            .with_mut(|s| s.jump_down(distance, &self.results))
+    /// Manually Set Position/SubPosition (with Click)
+    pub fn set_position(&self, pos: usize, subpos: usize) {
+        self.state.with_mut(|s| s.set_position(pos, subpos))
     }
 }
@@ -0,0 +1,562 @@
+//! Long-Running Plugins Answering every Search Query as the User Types
+//!
+//! Streaming plugins receive each changed search as one line on stdin and
+//! reply w/ their messages followed by an empty line. Answers replace
+//! the entries of the menu, so streaming plugins are run on their own.
+//! Long answers are shown while they are read, flushed in batches so the
+//! menu is updated at a bounded rate.
+//!
+//! Every query is numbered w/ a request id. While a plugin is busy only
+//! the most recent query is kept waiting for it, so slow plugins never
+//! build up a backlog of searches the user already typed past. Plugins
+//! configured w/ `request_ids` receive the numbered queries as JSON and
+//! may be sent the next one before finishing their answer, which is
+//! discarded once it arrives late.
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use rmenu_plugin::{Batch, Entry, Message, Query, STREAM_ENV};
+
+use crate::cli::{plugin_args, RMenuError, Result};
+use crate::config::{Config, PluginConfig, UpdateConfig};
+use crate::instance::Control;
+use crate::search::Normalized;
+
+static STREAMS: Lazy<Mutex<BTreeMap<String, Stream>>> = Lazy::new(|| Mutex::new(BTreeMap::new()));
+static REQUESTS: AtomicU64 = AtomicU64::new(1);
+
+/// Running Streaming Plugin w/ its Latest Answer
+struct Stream {
+    queries: Arc<Queries>,
+    latest: Vec<Message>,
+}
+
+/// Number the Search as the Most Recent Request
+pub fn request(search: &str) -> Query {
+    Query {
+        request_id: REQUESTS.fetch_add(1, Ordering::SeqCst),
+        search: search.to_owned(),
+    }
+}
+
+/// Single Slot of the Query Waiting for a Busy Plugin
+///
+/// Newer queries replace the waiting one instead of queueing behind it.
+#[derive(Default)]
+pub struct Queries {
+    waiting: Mutex<Option<Query>>,
+    ready: Condvar,
+}
+
+impl Queries {
+    /// Make the Query Wait for the Plugin, Returning the one it Superseded
+    pub fn push(&self, query: Query) -> Option<Query> {
+        let mut waiting = self.waiting.lock().expect("Failed to Access Query Slot");
+        let dropped = waiting.replace(query);
+        self.ready.notify_one();
+        dropped
+    }
+
+    /// Block until a Query is Waiting and Take it
+    pub fn next(&self) -> Query {
+        let mut waiting = self.waiting.lock().expect("Failed to Access Query Slot");
+        loop {
+            if let Some(query) = waiting.take() {
+                return query;
+            }
+            waiting = self
+                .ready
+                .wait(waiting)
+                .expect("Failed to Access Query Slot");
+        }
+    }
+
+    /// Check if a Newer Query is Waiting
+    pub fn superseded(&self) -> bool {
+        self.waiting
+            .lock()
+            .expect("Failed to Access Query Slot")
+            .is_some()
+    }
+}
+
+/// Requests Sent to the Plugin w/o a Complete Answer so far
+pub struct InFlight {
+    sent: Mutex<VecDeque<u64>>,
+    answered: Condvar,
+    /// Most Recent Request Sent, the only one whose Answers are Shown
+    latest: AtomicU64,
+    /// Number of Requests the Plugin may be Sent at once
+    limit: usize,
+}
+
+impl InFlight {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            sent: Mutex::new(VecDeque::new()),
+            answered: Condvar::new(),
+            latest: AtomicU64::new(0),
+            limit: limit.max(1),
+        }
+    }
+
+    /// Block until Another Request may be Sent to the Plugin
+    pub fn wait_room(&self) {
+        let mut sent = self.sent.lock().expect("Failed to Access Request Queue");
+        while sent.len() >= self.limit {
+            sent = self
+                .answered
+                .wait(sent)
+                .expect("Failed to Access Request Queue");
+        }
+    }
+
+    /// Record the Request as Sent and the Most Recent one
+    pub fn sent(&self, request_id: u64) {
+        let mut sent = self.sent.lock().expect("Failed to Access Request Queue");
+        sent.push_back(request_id);
+        self.latest.store(request_id, Ordering::SeqCst);
+    }
+
+    /// Retrieve the Request an Answer Belongs to
+    ///
+    /// Answers w/o a batch header belong to the oldest unanswered request.
+    fn owner(sent: &VecDeque<u64>, header: Option<u64>) -> Option<u64> {
+        header.or_else(|| sent.front().copied())
+    }
+
+    /// Check if the Answer being Read is for the Most Recent Request
+    pub fn current(&self, header: Option<u64>) -> bool {
+        let sent = self.sent.lock().expect("Failed to Access Request Queue");
+        Self::owner(&sent, header) == Some(self.latest.load(Ordering::SeqCst))
+    }
+
+    /// Mark the Request of a Complete Answer as Answered
+    ///
+    /// Plugins answer in order, so older requests still waiting were
+    /// abandoned and are dropped as well. Returns if the answer is for
+    /// the most recent request and is shown.
+    pub fn answered(&self, header: Option<u64>) -> bool {
+        let mut sent = self.sent.lock().expect("Failed to Access Request Queue");
+        let owner = Self::owner(&sent, header);
+        if let Some(id) = owner {
+            sent.retain(|s| *s > id);
+        }
+        self.answered.notify_one();
+        owner == Some(self.latest.load(Ordering::SeqCst))
+    }
+}
+
+/// Coalesce Messages Read in Quick Succession into Bounded Updates
+///
+/// Updates are flushed once the interval passed since the last one, or
+/// early when enough messages were collected in the meantime.
+pub struct Batcher {
+    interval: Duration,
+    size: usize,
+    last: Instant,
+    flushed: usize,
+}
+
+impl Batcher {
+    pub fn new(updates: &UpdateConfig) -> Self {
+        Self {
+            interval: updates.interval(),
+            size: updates.batch_size.max(1),
+            last: Instant::now(),
+            flushed: 0,
+        }
+    }
+
+    /// Check if the Collected Messages are Due to be Flushed
+    pub fn ready(&mut self, collected: usize) -> bool {
+        let pending = collected.saturating_sub(self.flushed);
+        if pending == 0 || (pending < self.size && self.last.elapsed() < self.interval) {
+            return false;
+        }
+        self.last = Instant::now();
+        self.flushed = collected;
+        true
+    }
+}
+
+/// Write the Query as one Line, as JSON if the Plugin Supports Request IDs
+fn send(stdin: &mut ChildStdin, query: &Query, ids: bool) -> Result<()> {
+    match ids {
+        true => writeln!(stdin, "{}", serde_json::to_string(query)?)?,
+        false => writeln!(stdin, "{}", query.search.replace('\n', " "))?,
+    }
+    stdin.flush()?;
+    Ok(())
+}
+
+/// Read the Messages of one Answer up to the Empty Line Ending it
+///
+/// Returns the request id named by the batch header of the answer (if
+/// any). The messages read so far are passed to `partial` whenever a
+/// batch is due.
+fn read_answer(
+    stdout: &mut BufReader<ChildStdout>,
+    mut batcher: Batcher,
+    mut partial: impl FnMut(Option<u64>, &[Message]),
+) -> Result<(Option<u64>, Vec<Message>)> {
+    let mut header = None;
+    let mut messages = vec![];
+    for line in stdout.by_ref().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            return Ok((header, messages));
+        }
+        if let Ok(batch) = serde_json::from_str::<Batch>(&line) {
+            header = Some(batch.request_id);
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(msg) => messages.push(msg),
+            Err(err) => log::warn!("invalid stream message {line:?}: {err}"),
+        }
+        if batcher.ready(messages.len()) {
+            partial(header, &messages);
+        }
+    }
+    Err(RMenuError::CommandError(None))
+}
+
+/// Send a Query and Collect the Messages Answering it
+///
+/// Answers naming another request are skipped.
+fn respond(
+    stdin: &mut ChildStdin,
+    stdout: &mut BufReader<ChildStdout>,
+    query: &Query,
+    ids: bool,
+    updates: &UpdateConfig,
+) -> Result<Vec<Message>> {
+    send(stdin, query, ids)?;
+    loop {
+        let (header, messages) = read_answer(stdout, Batcher::new(updates), |_, _| {})?;
+        if header.is_none() || header == Some(query.request_id) {
+            return Ok(messages);
+        }
+        log::debug!("skipping answer to unknown request {header:?}");
+    }
+}
+
+/// Attribute the Streamed Entries to their Plugin and Normalize them
+fn entries(name: &str, messages: &[Message]) -> (Vec<Entry>, Vec<Normalized>) {
+    let mut entries: Vec<Entry> = messages
+        .iter()
+        .filter_map(|msg| match msg {
+            Message::Entry(entry) => Some(entry.clone()),
+            Message::Options(_) => None,
+        })
+        .collect();
+    for entry in entries.iter_mut() {
+        entry.source = Some(name.to_owned());
+    }
+    let normalized = entries.iter().map(Normalized::new).collect();
+    (entries, normalized)
+}
+
+/// Send the Latest Query whenever the Plugin has Room for Another
+fn write_queries(
+    name: &str,
+    mut stdin: ChildStdin,
+    queries: &Queries,
+    flight: &InFlight,
+    ids: bool,
+) {
+    loop {
+        flight.wait_room();
+        let query = queries.next();
+        log::debug!("stream {name:?} sent request {}", query.request_id);
+        flight.sent(query.request_id);
+        if let Err(err) = send(&mut stdin, &query, ids) {
+            log::error!("failed to query streaming plugin {name:?}: {err:?}");
+            break;
+        }
+    }
+}
+
+/// Show the Answers to the Latest Query until the Plugin Exits
+///
+/// Plugins are sent one query at a time, or two w/ request ids so they
+/// can move on to the newest search early. Partial answers stop being
+/// shown once a newer query exists, and complete answers to anything
+/// but the most recent query are discarded.
+fn serve(
+    name: String,
+    mut child: Child,
+    stdin: ChildStdin,
+    mut stdout: BufReader<ChildStdout>,
+    queries: Arc<Queries>,
+    updates: UpdateConfig,
+    ids: bool,
+) {
+    let flight = Arc::new(InFlight::new(if ids { 2 } else { 1 }));
+    {
+        let (name, queries, flight) = (name.clone(), Arc::clone(&queries), Arc::clone(&flight));
+        std::thread::spawn(move || write_queries(&name, stdin, &queries, &flight, ids));
+    }
+    loop {
+        let partial = |header: Option<u64>, messages: &[Message]| {
+            if !flight.current(header) || queries.superseded() {
+                return;
+            }
+            let (entries, normalized) = entries(&name, messages);
+            crate::instance::queue(Control::Streamed(entries, normalized));
+        };
+        let (header, messages) = match read_answer(&mut stdout, Batcher::new(&updates), partial) {
+            Ok(answer) => answer,
+            Err(err) => {
+                log::error!("streaming plugin {name:?} stopped answering: {err:?}");
+                break;
+            }
+        };
+        if !flight.answered(header) {
+            log::debug!("stream {name:?} discarded late answer to request {header:?}");
+            continue;
+        }
+        let (entries, normalized) = entries(&name, &messages);
+        if let Some(stream) = STREAMS
+            .lock()
+            .expect("Failed to Access Global Mutex")
+            .get_mut(&name)
+        {
+            stream.latest = messages;
+        }
+        crate::instance::queue(Control::Streamed(entries, normalized));
+    }
+    // the query writer stops once the closed plugin fails its next write
+    stop(&name, child);
+}
+
+/// Forget the Streaming Plugin and Stop its Process
+fn stop(name: &str, mut child: Child) {
+    STREAMS
+        .lock()
+        .expect("Failed to Access Global Mutex")
+        .remove(name);
+    let _ = child.kill();
+    let _ = child.wait();
+    crate::signals::untrack_child(child.id());
+}
+
+/// Wait for the First Answer up to the Configured Startup Time
+///
+/// Returns nothing when the plugin was too slow to answer in time.
+fn wait_answer(
+    name: &str,
+    answer: mpsc::Receiver<Result<Vec<Message>>>,
+    updates: &UpdateConfig,
+) -> Option<Result<Vec<Message>>> {
+    match answer.recv_timeout(updates.startup()) {
+        Ok(answer) => Some(answer),
+        Err(RecvTimeoutError::Timeout) => {
+            log::warn!("streaming plugin {name:?} too slow, opening w/o its entries");
+            None
+        }
+        Err(RecvTimeoutError::Disconnected) => Some(Err(RMenuError::CommandError(None))),
+    }
+}
+
+/// Start the Streaming Plugin and Retrieve its Answer to the Empty Search
+///
+/// Plugins keep running to answer later searches only when `live`,
+/// and already running plugins return their latest answer instead.
+/// The menu waits for the first answer up to `updates.startup` and opens
+/// w/o the plugin's entries otherwise, showing them once they arrive.
+pub fn open(
+    name: &str,
+    plugin: &PluginConfig,
+    live: bool,
+    updates: &UpdateConfig,
+) -> Result<Vec<Message>> {
+    if let Some(stream) = STREAMS
+        .lock()
+        .expect("Failed to Access Global Mutex")
+        .get(name)
+    {
+        return Ok(stream.latest.clone());
+    }
+    let args = plugin_args(&plugin.exec);
+    let main = args
+        .get(0)
+        .ok_or_else(|| RMenuError::InvalidPlugin(name.to_owned()))?;
+    log::info!("starting streaming plugin: {name:?}");
+    let mut child = Command::new(main)
+        .args(&args[1..])
+        .env(STREAM_ENV, "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    crate::signals::track_child(child.id());
+    let mut stdin = child.stdin.take().expect("Failed to Open Plugin Stdin");
+    let mut stdout = BufReader::new(child.stdout.take().expect("Failed to Read Plugin Stdout"));
+    let (ids, first) = (plugin.request_ids, request(""));
+    let (send_answer, answer) = mpsc::channel();
+    if !live {
+        let updates = updates.clone();
+        std::thread::spawn(move || {
+            let _ = send_answer.send(respond(&mut stdin, &mut stdout, &first, ids, &updates));
+        });
+        // stopping the plugin also ends a read that is still waiting
+        let answer = wait_answer(name, answer, updates);
+        stop(name, child);
+        return answer.unwrap_or_else(|| Ok(vec![]));
+    }
+    // searches typed before the first answer wait for the plugin
+    let queries = Arc::new(Queries::default());
+    STREAMS
+        .lock()
+        .expect("Failed to Access Global Mutex")
+        .insert(
+            name.to_owned(),
+            Stream {
+                queries: Arc::clone(&queries),
+                latest: vec![],
+            },
+        );
+    {
+        let (name, updates) = (name.to_owned(), updates.clone());
+        std::thread::spawn(move || {
+            let messages = match respond(&mut stdin, &mut stdout, &first, ids, &updates) {
+                Ok(messages) => messages,
+                Err(err) => {
+                    log::error!("streaming plugin {name:?} failed to answer: {err:?}");
+                    let _ = send_answer.send(Err(err));
+                    stop(&name, child);
+                    return;
+                }
+            };
+            if let Some(stream) = STREAMS
+                .lock()
+                .expect("Failed to Access Global Mutex")
+                .get_mut(&name)
+            {
+                stream.latest = messages.clone();
+            }
+            // the menu already opened w/o the first answer when nobody waits
+            if let Err(late) = send_answer.send(Ok(messages)) {
+                if !queries.superseded() {
+                    let messages = late.0.unwrap_or_default();
+                    let (entries, normalized) = entries(&name, &messages);
+                    crate::instance::queue(Control::Streamed(entries, normalized));
+                }
+            }
+            serve(name, child, stdin, stdout, queries, updates, ids)
+        });
+    }
+    wait_answer(name, answer, updates).unwrap_or_else(|| Ok(vec![]))
+}
+
+/// Forward the Changed Search to every Running Streaming Plugin
+///
+/// Masked searches are never sent to plugins.
+pub fn search(config: &Config, search: &str) {
+    if config.search.password {
+        return;
+    }
+    let query = request(search);
+    let streams = STREAMS.lock().expect("Failed to Access Global Mutex");
+    for (name, stream) in streams.iter() {
+        if let Some(dropped) = stream.queries.push(query.clone()) {
+            log::debug!(
+                "stream {name:?} busy, dropped request {}",
+                dropped.request_id
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_batching() {
+        let updates = UpdateConfig {
+            interval: 60_000,
+            batch_size: 3,
+            ..Default::default()
+        };
+        let mut batcher = Batcher::new(&updates);
+        assert!(!batcher.ready(1));
+        assert!(!batcher.ready(2));
+        assert!(batcher.ready(3));
+        assert!(!batcher.ready(5));
+        assert!(batcher.ready(6));
+        assert!(!batcher.ready(6));
+        let updates = UpdateConfig {
+            interval: 0,
+            batch_size: 1000,
+            ..Default::default()
+        };
+        let mut eager = Batcher::new(&updates);
+        assert!(eager.ready(1));
+        assert!(!eager.ready(1));
+    }
+
+    #[test]
+    fn test_superseded_queries() {
+        let queries = Queries::default();
+        let (first, second, third) = (request("f"), request("fi"), request("fir"));
+        assert!(first.request_id < second.request_id && second.request_id < third.request_id);
+        assert!(queries.push(first.clone()).is_none());
+        assert_eq!(queries.push(second), Some(first));
+        assert!(queries.push(third.clone()).is_some());
+        assert!(queries.superseded());
+        assert_eq!(queries.next(), third);
+        assert!(!queries.superseded());
+    }
+
+    #[test]
+    fn test_request_ids() {
+        let flight = InFlight::new(2);
+        flight.sent(1);
+        flight.sent(2);
+        assert!(!flight.current(Some(1)));
+        assert!(flight.current(Some(2)));
+        // answers w/o a header belong to the oldest request
+        assert!(!flight.current(None));
+        assert!(!flight.answered(None));
+        assert!(flight.current(None));
+        assert!(flight.answered(Some(2)));
+        flight.wait_room();
+    }
+
+    #[test]
+    fn test_abandoned_requests() {
+        let flight = InFlight::new(2);
+        flight.sent(1);
+        flight.sent(2);
+        // the plugin abandoned request 1 and only answered the newer one
+        assert!(flight.answered(Some(2)));
+        flight.wait_room();
+        flight.sent(3);
+        flight.sent(4);
+        assert!(!flight.answered(Some(3)));
+        assert!(flight.current(None));
+        assert!(flight.answered(None));
+        flight.wait_room();
+    }
+
+    #[test]
+    fn test_startup_timeout() {
+        let plugin = crate::testing::fixture_plugin("silent.sh");
+        let updates = UpdateConfig {
+            startup: 100,
+            ..Default::default()
+        };
+        let started = Instant::now();
+        let messages = open("silent", &plugin, false, &updates).expect("open failed");
+        assert!(messages.is_empty());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
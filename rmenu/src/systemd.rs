@@ -0,0 +1,133 @@
+//! RMenu Systemd Socket Activation and Readiness Notification
+//!
+//! Sockets passed by systemd (`LISTEN_FDS`) are adopted instead of binding
+//! new ones, and readiness is reported on `NOTIFY_SOCKET` once serving.
+use std::env;
+use std::io;
+use std::os::fd::{FromRawFd, RawFd};
+use std::os::linux::net::SocketAddrExt;
+use std::os::unix::net::{SocketAddr, UnixDatagram, UnixListener};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+/// First File Descriptor Passed by Systemd (`SD_LISTEN_FDS_START`)
+static LISTEN_FDS_START: RawFd = 3;
+
+static LISTEN_FDS: Lazy<Mutex<Vec<(String, RawFd)>>> = Lazy::new(|| Mutex::new(inherit()));
+
+/// Parse Socket-Activation Variables into Named File Descriptors
+///
+/// Descriptors only belong to the process named by `LISTEN_PID`, and
+/// descriptors missing from `LISTEN_FDNAMES` are named `unknown`.
+fn parse_listen_fds(
+    pid: Option<&str>,
+    fds: Option<&str>,
+    names: Option<&str>,
+    own: u32,
+) -> Vec<(String, RawFd)> {
+    if pid.and_then(|p| p.trim().parse::<u32>().ok()) != Some(own) {
+        return vec![];
+    }
+    let Some(count) = fds.and_then(|n| n.trim().parse::<RawFd>().ok()) else {
+        return vec![];
+    };
+    let mut names = names.unwrap_or_default().split(':');
+    (0..count.max(0))
+        .map(|i| {
+            let name = names.next().filter(|n| !n.is_empty()).unwrap_or("unknown");
+            (name.to_owned(), LISTEN_FDS_START + i)
+        })
+        .collect()
+}
+
+/// Take the Descriptors Passed to this Process, Hiding them from Plugins
+fn inherit() -> Vec<(String, RawFd)> {
+    let var = |key: &str| env::var(key).ok();
+    let fds = parse_listen_fds(
+        var("LISTEN_PID").as_deref(),
+        var("LISTEN_FDS").as_deref(),
+        var("LISTEN_FDNAMES").as_deref(),
+        std::process::id(),
+    );
+    for key in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+        env::remove_var(key);
+    }
+    for (_, fd) in fds.iter() {
+        unsafe { libc::fcntl(*fd, libc::F_SETFD, libc::FD_CLOEXEC) };
+    }
+    fds
+}
+
+/// Adopt the Listening Socket Passed by Systemd under the Given Name
+///
+/// Socket units name their descriptor after the unit unless
+/// `FileDescriptorName=` is set, so a single descriptor is always adopted.
+pub fn take_listener(name: &str) -> Option<UnixListener> {
+    let mut fds = LISTEN_FDS.lock().expect("Failed to Access Global Mutex");
+    let index = match fds.as_slice() {
+        [_] => 0,
+        passed => passed.iter().position(|(n, _)| n == name)?,
+    };
+    let (_, fd) = fds.remove(index);
+    log::info!("adopted {name:?} socket from systemd (fd {fd})");
+    Some(unsafe { UnixListener::from_raw_fd(fd) })
+}
+
+/// Send a State Update to the Notification Socket
+fn notify(path: &str, state: &str) -> io::Result<()> {
+    let socket = UnixDatagram::unbound()?;
+    match path.strip_prefix('@') {
+        Some(name) => {
+            let addr = SocketAddr::from_abstract_name(name)?;
+            socket.send_to_addr(state.as_bytes(), &addr)
+        }
+        None => socket.send_to(state.as_bytes(), path),
+    }?;
+    Ok(())
+}
+
+/// Report Readiness to the Service Manager (if Started by One)
+///
+/// The variable is removed afterwards, so plugins and later sockets of
+/// the same process never report again.
+pub fn notify_ready() {
+    let Ok(path) = env::var("NOTIFY_SOCKET") else {
+        return;
+    };
+    env::remove_var("NOTIFY_SOCKET");
+    match notify(&path, "READY=1") {
+        Ok(()) => log::debug!("notified systemd of readiness"),
+        Err(err) => log::warn!("failed to notify systemd: {err:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listen_fds() {
+        let fds = parse_listen_fds(Some("42"), Some("2"), Some("daemon:instance"), 42);
+        let named = vec![("daemon".to_owned(), 3), ("instance".to_owned(), 4)];
+        assert_eq!(fds, named);
+        let fds = parse_listen_fds(Some("42"), Some("1"), None, 42);
+        assert_eq!(fds, vec![("unknown".to_owned(), 3)]);
+        assert_eq!(parse_listen_fds(Some("41"), Some("1"), None, 42), vec![]);
+        assert_eq!(parse_listen_fds(None, Some("1"), None, 42), vec![]);
+        assert_eq!(parse_listen_fds(Some("42"), Some("x"), None, 42), vec![]);
+        assert_eq!(parse_listen_fds(Some("42"), None, None, 42), vec![]);
+    }
+
+    #[test]
+    fn test_notify() {
+        let path = env::temp_dir().join(format!("rmenu-notify-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let server = UnixDatagram::bind(&path).unwrap();
+        notify(&path.to_string_lossy(), "READY=1").unwrap();
+        let mut buf = [0; 16];
+        let len = server.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"READY=1");
+        let _ = std::fs::remove_file(&path);
+    }
+}
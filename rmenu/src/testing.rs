@@ -0,0 +1,613 @@
+//! RMenu Test-Support Harness using Fake Plugin Fixtures
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use clap::Parser;
+use rmenu_plugin::{Action, Entry};
+
+use crate::cli::{Args, Result};
+use crate::config::{CacheSetting, Config, Keybind, PluginConfig};
+use crate::exec::command_args;
+use crate::search::Normalized;
+use crate::state::{key_event, InnerState, KeyEvent};
+
+/// Retrieve Filepath of the Specified Plugin Fixture
+pub fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+/// Generate Uncached Plugin Configuration for the Specified Fixture
+pub fn fixture_plugin(name: &str) -> PluginConfig {
+    PluginConfig {
+        exec: vec![fixture(name).to_string_lossy().to_string()],
+        cache: CacheSetting::NoCache,
+        ..Default::default()
+    }
+}
+
+/// Headless Driver for Search, Paging and Keybind Logic
+pub struct Harness {
+    pub config: Config,
+    pub entries: Vec<Entry>,
+    normalized: Vec<Normalized>,
+    state: InnerState,
+    executed: Option<Action>,
+    batch: Vec<Action>,
+    exited: bool,
+}
+
+impl Harness {
+    /// Spawn new Harness from Pre-Collected Entries
+    pub fn new(config: Config, entries: Vec<Entry>) -> Self {
+        Self {
+            state: InnerState::new(&config),
+            normalized: entries.iter().map(Normalized::new).collect(),
+            config,
+            entries,
+            executed: None,
+            batch: vec![],
+            exited: false,
+        }
+    }
+
+    /// Spawn new Harness by Running the Specified Plugin Fixtures
+    pub fn plugins(mut config: Config, fixtures: &[&str]) -> Result<Self> {
+        let mut argv = vec!["rmenu".to_owned()];
+        for name in fixtures {
+            config
+                .plugins
+                .insert(name.to_string(), fixture_plugin(name));
+            argv.extend(["--run".to_owned(), name.to_string()]);
+        }
+        let mut cli = Args::parse_from(argv);
+        let (entries, _) = cli.get_entries(&mut config)?;
+        let config = cli.update_config(config);
+        Ok(Self::new(config, entries))
+    }
+
+    /// Retrieve Current Position State
+    #[inline]
+    pub fn position(&self) -> (usize, usize) {
+        self.state.position()
+    }
+
+    /// Update Search String and Return if it was Accepted
+    pub fn search(&mut self, search: &str) -> bool {
+        self.state.set_search(&self.config, search.to_owned())
+    }
+
+    /// Retrieve Currently Rendered Results
+    pub fn results(&mut self) -> Vec<&Entry> {
+        self.state
+            .results(&self.config, &self.entries, &self.normalized)
+    }
+
+    /// Retrieve Names of Currently Rendered Results
+    pub fn names(&mut self) -> Vec<String> {
+        self.results().into_iter().map(|e| e.name.clone()).collect()
+    }
+
+    /// Retrieve Number of Results Hidden by the Entry Limit
+    #[inline]
+    pub fn hidden(&self) -> usize {
+        self.state.hidden()
+    }
+
+    /// Simulate the Specified Keypress (e.g. `Shift+Tab`)
+    pub fn press(&mut self, key: &str) -> Option<KeyEvent> {
+        let bind = Keybind::from_str(key).expect("invalid keybind");
+        let event = match self.state.letter_event(&self.config, &bind.mods, &bind.key) {
+            Some(event) => event,
+            None => key_event(&self.config.keybinds, &bind.mods, &bind.key)?,
+        };
+        let results = self
+            .state
+            .results(&self.config, &self.entries, &self.normalized);
+        match event {
+            KeyEvent::Exit => self.exited = true,
+            KeyEvent::Exec if !self.state.is_submittable() => {}
+            KeyEvent::Exec => {
+                self.batch = self
+                    .state
+                    .actions(&results)
+                    .into_iter()
+                    .map(|(_, a)| a)
+                    .collect();
+                self.executed = self.batch.first().cloned();
+            }
+            KeyEvent::ExecAlt(index) => {
+                self.executed = self
+                    .state
+                    .alt_action(&results, index)
+                    .map(|(_, a)| a.clone())
+            }
+            KeyEvent::QuickExec(index) => {
+                self.executed = self
+                    .state
+                    .quick_action(&results, index)
+                    .map(|(_, a)| a.clone())
+            }
+            _ => self.state.navigate(&event, &self.config, &results),
+        }
+        Some(event)
+    }
+
+    /// Retrieve Action Executed by the Last Exec Event
+    #[inline]
+    pub fn executed(&self) -> Option<&Action> {
+        self.executed.as_ref()
+    }
+
+    /// Retrieve all Actions Executed by the Last Exec Event
+    #[inline]
+    pub fn executed_all(&self) -> &[Action] {
+        &self.batch
+    }
+
+    /// Retrieve Command that would have been Spawned by the Last Exec Event
+    pub fn command(&self) -> Option<Vec<String>> {
+        let action = self.executed.as_ref()?;
+        command_args(&action.exec, &self.config)
+    }
+
+    /// Check if an Exit Event was Triggered
+    #[inline]
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Scope, StaticEntry};
+    use rmenu_plugin::{ActionRef, Method, Options};
+
+    fn basic() -> Harness {
+        Harness::plugins(Config::default(), &["basic.sh"]).expect("fixture failed")
+    }
+
+    /// Configuration w/ the Arrow Keybinds of the Shipped `config.yaml`
+    fn arrows() -> Config {
+        let mut config = Config::default();
+        config.keybinds.move_next = vec![Keybind::from_str("Arrow-Down").unwrap()];
+        config.keybinds.move_prev = vec![Keybind::from_str("Arrow-Up").unwrap()];
+        config
+    }
+
+    #[test]
+    fn test_filter_and_exec() {
+        let mut h = basic();
+        assert_eq!(h.names().len(), 5);
+        assert!(h.search("fire"));
+        assert_eq!(h.names(), vec!["Firefox"]);
+        h.press("Enter");
+        assert_eq!(h.command(), Some(vec!["firefox".to_owned()]));
+    }
+
+    #[test]
+    fn test_filter_comment() {
+        let mut h = basic();
+        h.search("editor");
+        assert_eq!(h.names(), vec!["Vim", "Emacs"]);
+    }
+
+    #[test]
+    fn test_navigation() {
+        let mut h = Harness::plugins(arrows(), &["basic.sh"]).unwrap();
+        h.press("Arrow-Down");
+        h.press("Arrow-Down");
+        assert_eq!(h.position(), (2, 0));
+        h.press("Arrow-Up");
+        assert_eq!(h.position(), (1, 0));
+        h.press("Page-Up");
+        assert_eq!(h.position(), (0, 0));
+        h.press("Page-Down");
+        assert_eq!(h.position(), (4, 0));
+        h.press("Escape");
+        assert!(h.exited());
+    }
+
+    #[test]
+    fn test_submenu_exec() {
+        let mut config = Config::default();
+        config.keybinds.open_menu = vec![Keybind::from_str("Arrow-Right").unwrap()];
+        let mut h = Harness::plugins(config, &["basic.sh"]).unwrap();
+        h.search("vim");
+        h.press("Arrow-Right");
+        assert_eq!(h.position(), (0, 1));
+        h.press("Enter");
+        let action = h.executed().expect("no action executed");
+        assert_eq!(action.exec, Method::Terminal("vim".to_owned()));
+    }
+
+    #[test]
+    fn test_privileged_exec() {
+        let mut config = Config::default();
+        config.privileged = Some("sudo -A {cmd}".to_owned());
+        let mut entry = Entry::new("Reboot", "", None);
+        entry.actions[0].exec = Method::RunPrivileged("systemctl reboot".to_owned());
+        let mut h = Harness::new(config, vec![entry]);
+        h.press("Enter");
+        let command = h.command().expect("no command spawned");
+        assert_eq!(command, vec!["sudo", "-A", "systemctl", "reboot"]);
+    }
+
+    #[test]
+    fn test_plugin_prefix() {
+        let mut config = Config::default();
+        let scope = Scope {
+            name: "editors".to_owned(),
+            prefix: Some("e:".to_owned()),
+            entries: 1..3,
+        };
+        config.search.scopes.push(scope);
+        let mut h = Harness::plugins(config, &["basic.sh"]).unwrap();
+        h.search("e:");
+        assert_eq!(h.names(), vec!["Vim", "Emacs"]);
+        h.search("e: ema");
+        assert_eq!(h.names(), vec!["Emacs"]);
+        let status = crate::frontend::status(&h.state, &h.config);
+        assert_eq!(status.plugin.as_deref(), Some("editors"));
+        assert_eq!((status.query_length, status.matches), (6, 1));
+        h.search("fire");
+        assert_eq!(h.names(), vec!["Firefox"]);
+        let status = crate::frontend::status(&h.state, &h.config);
+        assert_eq!(status.plugin, None);
+    }
+
+    #[test]
+    fn test_paging() {
+        let mut config = arrows();
+        config.page_size = Some(2);
+        let mut h = Harness::plugins(config, &["basic.sh"]).unwrap();
+        assert_eq!(h.names().len(), 2);
+        h.press("Arrow-Down");
+        assert_eq!(h.names().len(), 4);
+    }
+
+    #[test]
+    fn test_plugin_options() {
+        let mut h = Harness::plugins(Config::default(), &["options.sh"]).unwrap();
+        assert_eq!(h.config.search.placeholder, Some("pick one".to_owned()));
+        h.press("Tab");
+        assert_eq!(h.position(), (1, 0));
+    }
+
+    #[test]
+    fn test_plugin_options_merge() {
+        let load = |run: &[&str]| {
+            let mut config = Config::default();
+            let mut basic = fixture_plugin("basic.sh");
+            basic.options = Some(Options {
+                placeholder: Some("basic".to_owned()),
+                style: Some(".a {}".to_owned()),
+                ..Default::default()
+            });
+            config.plugins.insert("basic.sh".to_owned(), basic);
+            config
+                .plugins
+                .insert("options.sh".to_owned(), fixture_plugin("options.sh"));
+            let mut argv = vec!["rmenu"];
+            run.iter().for_each(|name| argv.extend(["--run", *name]));
+            let mut cli = Args::parse_from(argv);
+            cli.get_entries(&mut config).unwrap();
+            config
+        };
+        let config = load(&["basic.sh", "options.sh"]);
+        assert_eq!(config.search.placeholder.as_deref(), Some("basic"));
+        assert_eq!(config.style.as_deref(), Some(".a {}"));
+        assert_eq!(
+            config.keybinds.move_next,
+            vec![Keybind::from_str("Tab").unwrap()]
+        );
+        let config = load(&["options.sh", "basic.sh"]);
+        assert_eq!(config.search.placeholder.as_deref(), Some("pick one"));
+    }
+
+    #[test]
+    fn test_search_restrict() {
+        let mut config = Config::default();
+        config.search.restrict = Some("[a-z]*".to_owned());
+        let mut h = Harness::plugins(config, &["basic.sh"]).unwrap();
+        assert!(!h.search("Fire1"));
+        assert!(h.search("fire"));
+    }
+
+    #[test]
+    fn test_search_length() {
+        let mut config = Config::default();
+        config.search.min_length = Some(2);
+        config.search.max_length = Some(4);
+        let mut h = Harness::plugins(config, &["basic.sh"]).unwrap();
+        assert!(h.names().is_empty());
+        assert!(h.search("v"));
+        assert!(h.names().is_empty());
+        assert!(h.search("vi"));
+        assert_eq!(h.names(), vec!["Vim"]);
+        assert!(!h.search("vimvi"));
+    }
+
+    #[test]
+    fn test_acronym() {
+        let mut config = Config::default();
+        config.search.use_regex = false;
+        config.search.use_acronym = true;
+        config.search.acronym_weight = 2.0;
+        let entries = vec![
+            Entry::new("Gimmick", "gimmick", None),
+            Entry::new("GNU Image Manipulation Program", "gimp", None),
+            Entry::new("Firefox File Transfer", "fft", None),
+        ];
+        let mut h = Harness::new(config, entries);
+        assert!(h.search("gim"));
+        assert_eq!(h.names()[0], "GNU Image Manipulation Program");
+        assert_eq!(h.names().len(), 2);
+        assert!(h.search("fft"));
+        assert_eq!(h.names(), vec!["Firefox File Transfer"]);
+    }
+
+    #[test]
+    fn test_matchers() {
+        let mut config = Config::default();
+        config.search.matcher = Some("fuzzy".to_owned());
+        let mut h = Harness::plugins(config, &["basic.sh"]).unwrap();
+        assert!(h.search("ffx"));
+        assert_eq!(h.names(), vec!["Firefox"]);
+        h.config.search.matcher = Some("fzf".to_owned());
+        assert!(h.search("^fi !fox"));
+        assert_eq!(h.names(), vec!["Files"]);
+        assert!(h.search("editor !exten"));
+        assert_eq!(h.names(), vec!["Vim"]);
+        h.config.search.matcher = Some("extended".to_owned());
+        assert!(h.search("^vim$ | ^hello$"));
+        assert_eq!(h.names(), vec!["Hello", "Vim"]);
+        assert!(h.search("'e !^e !r$"));
+        assert_eq!(h.names(), vec!["Files", "Firefox", "Hello"]);
+    }
+
+    #[test]
+    fn test_tie_break() {
+        let mut config = Config::default();
+        config.search.matcher = Some("fuzzy".to_owned());
+        let mut weighted = Entry::new("Bx Editor", "bx", None);
+        weighted.meta.insert("weight".to_owned(), "1".to_owned());
+        let entries = vec![
+            Entry::new("Cx Editor", "cx", None),
+            Entry::new("Ax Editor", "ax", None),
+            weighted,
+        ];
+        let mut h = Harness::new(config, entries);
+        assert!(h.search("editor"));
+        assert_eq!(h.names(), vec!["Bx Editor", "Ax Editor", "Cx Editor"]);
+        h.config.search.tie_break = vec![];
+        assert!(h.search("editor"));
+        assert_eq!(h.names(), vec!["Cx Editor", "Ax Editor", "Bx Editor"]);
+    }
+
+    #[test]
+    fn test_incremental_filter() {
+        let mut config = Config::default();
+        config.search.use_regex = false;
+        let mut h = Harness::plugins(config, &["basic.sh"]).unwrap();
+        assert!(h.search("e"));
+        assert_eq!(h.names().len(), 5);
+        assert!(h.search("ed"));
+        assert_eq!(h.names(), vec!["Vim", "Emacs"]);
+        assert!(h.search("edi"));
+        assert_eq!(h.names(), vec!["Vim", "Emacs"]);
+        assert!(h.search("f"));
+        assert_eq!(h.names(), vec!["Firefox", "Files"]);
+    }
+
+    #[test]
+    fn test_normalized_search() {
+        let mut config = Config::default();
+        config.search.use_regex = false;
+        let mut h = Harness::plugins(config, &["basic.sh"]).unwrap();
+        assert!(h.search("ＦＩＲＥ"));
+        assert_eq!(h.names(), vec!["Firefox"]);
+        h.config.search.ignore_case = false;
+        assert!(h.search("FIRE"));
+        assert!(h.names().is_empty());
+    }
+
+    #[test]
+    fn test_max_entries() {
+        let mut config = Config::default();
+        config.max_entries = Some(2);
+        config.keybinds.show_more = vec![Keybind::from_str("Ctrl+KeyM").unwrap()];
+        let mut h = Harness::plugins(config, &["basic.sh"]).unwrap();
+        assert_eq!(h.names(), vec!["Firefox", "Vim"]);
+        assert_eq!(h.hidden(), 3);
+        h.press("Ctrl+KeyM");
+        assert_eq!(h.names().len(), 4);
+        assert_eq!(h.hidden(), 1);
+        h.search("editor");
+        assert_eq!(h.names().len(), 2);
+        assert_eq!(h.hidden(), 0);
+    }
+
+    #[test]
+    fn test_max_entries_per_plugin() {
+        let mut config = Config::default();
+        config.max_entries = Some(2);
+        let editors = PluginConfig {
+            max_entries: Some(1),
+            ..Default::default()
+        };
+        config.plugins.insert("editors".to_owned(), editors);
+        let scope = |name: &str, entries| Scope {
+            name: name.to_owned(),
+            prefix: None,
+            entries,
+        };
+        config.search.scopes = vec![scope("browsers", 0..3), scope("editors", 3..6)];
+        config.keybinds.show_more = vec![Keybind::from_str("Ctrl+KeyM").unwrap()];
+        let entries = ["Firefox", "Chromium", "Epiphany", "Vim", "Emacs", "Helix"]
+            .into_iter()
+            .map(|name| Entry::new(name, "", None))
+            .collect();
+        let mut h = Harness::new(config, entries);
+        assert_eq!(h.names(), vec!["Firefox", "Chromium", "Vim"]);
+        assert_eq!(h.hidden(), 3);
+        assert_eq!(h.names().len(), 3);
+        assert_eq!(h.hidden(), 3);
+        h.press("Ctrl+KeyM");
+        let names = vec!["Firefox", "Chromium", "Epiphany", "Vim", "Emacs"];
+        assert_eq!(h.names(), names);
+        assert_eq!(h.hidden(), 1);
+    }
+
+    #[test]
+    fn test_exec_alt_and_default() {
+        let mut h = basic();
+        h.search("vim");
+        h.press("Shift+Enter");
+        let action = h.executed().expect("no action executed");
+        assert_eq!(action.exec, Method::Terminal("vim".to_owned()));
+        h.entries[1].default_action = Some(ActionRef::Name("Terminal".to_owned()));
+        h.press("Enter");
+        let action = h.executed().expect("no action executed");
+        assert_eq!(action.exec, Method::Terminal("vim".to_owned()));
+    }
+
+    #[test]
+    fn test_source_actions() {
+        let mut h = Harness::plugins(Config::default(), &["basic.sh", "options.sh"]).unwrap();
+        let entry = &h.entries[0];
+        assert_eq!(entry.source.as_deref(), Some("basic.sh"));
+        let last = entry.actions.last().expect("missing action");
+        assert_eq!(last.exec, Method::DisableSource("basic.sh".to_owned()));
+        h.state.disable_source("basic.sh");
+        assert_eq!(h.names(), vec!["foo", "bar"]);
+        let single = Harness::plugins(Config::default(), &["basic.sh"]).unwrap();
+        assert_eq!(single.entries[0].actions.len(), 1);
+    }
+
+    #[test]
+    fn test_deferred_plugins() {
+        let mut config = Config::default();
+        config.prioritize_plugins = true;
+        let mut argv = vec!["rmenu".to_owned()];
+        for name in ["basic.sh", "options.sh"] {
+            config.plugins.insert(name.to_owned(), fixture_plugin(name));
+            argv.extend(["--run".to_owned(), name.to_owned()]);
+        }
+        let mut cli = Args::parse_from(argv);
+        cli.prioritize(&config);
+        let (mut entries, mut normalized) = cli.get_entries(&mut config).unwrap();
+        assert_eq!(entries.len(), 5);
+        assert_eq!(config.search.plugin(""), None);
+        let deferred = cli.take_deferred();
+        assert_eq!(deferred, vec!["options.sh"]);
+        cli.load_deferred(&mut config, &deferred[0], &mut entries, &mut normalized)
+            .unwrap();
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names[5..], ["foo", "bar"]);
+        assert_eq!(normalized.len(), 7);
+        let last = entries[5].actions.last().expect("missing action");
+        assert_eq!(last.exec, Method::DisableSource("options.sh".to_owned()));
+        assert_eq!(config.search.scopes[1].entries, 5..7);
+    }
+
+    #[test]
+    fn test_static_entries() {
+        let mut config = Config::default();
+        let htop = StaticEntry {
+            name: "htop".to_owned(),
+            exec: "htop".to_owned(),
+            icon: Some("utilities-terminal".to_owned()),
+            terminal: true,
+            ..Default::default()
+        };
+        let plugin = PluginConfig {
+            static_entries: vec![htop],
+            ..Default::default()
+        };
+        config.plugins.insert("apps".to_owned(), plugin);
+        let mut cli = Args::parse_from(["rmenu", "--run", "apps"]);
+        let (entries, _) = cli.get_entries(&mut config).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].source.as_deref(), Some("apps"));
+        assert_eq!(entries[0].icon_alt.as_deref(), Some("utilities-terminal"));
+        assert_eq!(
+            entries[0].actions[0].exec,
+            Method::Terminal("htop".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_fifo_source() {
+        let path = std::env::temp_dir().join(format!("rmenu-test-{}.fifo", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let cpath = std::ffi::CString::new(path.to_string_lossy().as_bytes()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(cpath.as_ptr(), 0o600) }, 0);
+        let entry = rmenu_plugin::Message::Entry(Entry::echo("Update", None));
+        let line = serde_json::to_string(&entry).unwrap();
+        let writer = {
+            let path = path.clone();
+            std::thread::spawn(move || std::fs::write(path, format!("{line}\n")).unwrap())
+        };
+        let mut config = Config::default();
+        let plugin = PluginConfig {
+            exec: vec![format!("fifo://{}", path.to_string_lossy())],
+            cache: CacheSetting::NoCache,
+            ..Default::default()
+        };
+        config.plugins.insert("updates".to_owned(), plugin);
+        let mut cli = Args::parse_from(["rmenu", "--run", "updates"]);
+        let (entries, _) = cli.get_entries(&mut config).unwrap();
+        writer.join().unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "Update");
+        assert_eq!(entries[0].source.as_deref(), Some("updates"));
+    }
+
+    #[test]
+    fn test_fifo_without_writer() {
+        let path = std::env::temp_dir().join(format!("rmenu-idle-{}.fifo", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let cpath = std::ffi::CString::new(path.to_string_lossy().as_bytes()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(cpath.as_ptr(), 0o600) }, 0);
+        let mut config = Config::default();
+        config.updates.startup = 100;
+        let plugin = PluginConfig {
+            exec: vec![format!("fifo://{}", path.to_string_lossy())],
+            cache: CacheSetting::NoCache,
+            ..Default::default()
+        };
+        config.plugins.insert("idle".to_owned(), plugin);
+        let mut cli = Args::parse_from(["rmenu", "--run", "idle"]);
+        let started = std::time::Instant::now();
+        let (entries, _) = cli.get_entries(&mut config).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(entries.is_empty());
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_multi_select() {
+        let mut config = arrows();
+        config.search.use_regex = false;
+        config.multi_select = true;
+        let mut h = Harness::plugins(config, &["basic.sh"]).unwrap();
+        h.press("Ctrl+Space");
+        h.press("Ctrl+Space");
+        h.press("Ctrl+Space");
+        h.press("Arrow-Up");
+        h.press("Ctrl+Space");
+        assert_eq!(h.position(), (3, 0));
+        h.press("Enter");
+        let execs: Vec<&Method> = h.executed_all().iter().map(|a| &a.exec).collect();
+        let expected = vec![
+            Method::Run("firefox".to_owned()),
+            Method::Run("gvim".to_owned()),
+        ];
+        assert_eq!(execs, expected.iter().collect::<Vec<_>>());
+    }
+}
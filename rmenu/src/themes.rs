@@ -0,0 +1,40 @@
+//! RMenu Built-In Stylesheet Themes
+
+/// Themes Embedded in the Binary by Name
+static BUILTIN: &[(&str, &str)] = &[
+    ("compact", include_str!("../../themes/compact.css")),
+    (
+        "dmenu-classic",
+        include_str!("../../themes/dmenu-classic.css"),
+    ),
+    ("gruvbox", include_str!("../../themes/gruvbox.css")),
+    ("large-touch", include_str!("../../themes/large-touch.css")),
+    ("nord", include_str!("../../themes/nord.css")),
+];
+
+/// Retrieve Built-In Theme Stylesheet by Name
+pub fn builtin(name: &str) -> Option<&'static str> {
+    BUILTIN
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, content)| *content)
+}
+
+/// Retrieve Names of all Built-In Themes
+pub fn names() -> impl Iterator<Item = &'static str> {
+    BUILTIN.iter().map(|(name, _)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_themes() {
+        for name in names() {
+            let css = builtin(name).expect("missing theme");
+            assert!(crate::css::check(css).is_ok(), "{name} failed css check");
+        }
+        assert!(builtin("missing").is_none());
+    }
+}
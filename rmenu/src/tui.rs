@@ -0,0 +1,233 @@
+//! RMenu Terminal Frontend using Ratatui
+use std::io::{self, Stderr};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+};
+use dioxus_html::input_data::keyboard_types::{Code, Modifiers};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{Frame, Terminal};
+
+use crate::cli::Result;
+use crate::frontend::{Frontend, Outcome, Session};
+use crate::state::key_event;
+use crate::App;
+
+type Term = Terminal<CrosstermBackend<Stderr>>;
+
+/// Terminal Frontend for SSH Sessions and Compositor-Less Kiosks
+///
+/// The menu is drawn on stderr so echoed results can still be
+/// captured from stdout.
+pub struct Tui;
+
+impl Frontend for Tui {
+    fn run(&self, app: App) -> Result<()> {
+        let mut session = Session::new(app);
+        enable_raw_mode()?;
+        let mut stderr = io::stderr();
+        crossterm::execute!(stderr, EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stderr))?;
+        let outcome = event_loop(&mut terminal, &mut session);
+        // always restore the terminal before executing or exiting
+        disable_raw_mode()?;
+        crossterm::execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        terminal.show_cursor()?;
+        session.finish(outcome?);
+        Ok(())
+    }
+}
+
+/// Convert Terminal Modifiers into Keybind Modifiers
+fn convert_mods(mods: &KeyModifiers) -> Modifiers {
+    let mut out = Modifiers::empty();
+    if mods.contains(KeyModifiers::ALT) {
+        out |= Modifiers::ALT;
+    }
+    if mods.contains(KeyModifiers::CONTROL) {
+        out |= Modifiers::CONTROL;
+    }
+    if mods.contains(KeyModifiers::SHIFT) {
+        out |= Modifiers::SHIFT;
+    }
+    if mods.contains(KeyModifiers::SUPER) {
+        out |= Modifiers::META;
+    }
+    out
+}
+
+/// Convert Terminal KeyCode into Keybind Code (e.g. `a` -> `KeyA`)
+fn convert_key(code: &KeyCode) -> Option<Code> {
+    let name = match code {
+        KeyCode::Char(' ') => "Space".to_owned(),
+        KeyCode::Char(c) if c.is_ascii_alphabetic() => format!("Key{}", c.to_ascii_uppercase()),
+        KeyCode::Char(c) if c.is_ascii_digit() => format!("Digit{c}"),
+        KeyCode::Up => "ArrowUp".to_owned(),
+        KeyCode::Down => "ArrowDown".to_owned(),
+        KeyCode::Left => "ArrowLeft".to_owned(),
+        KeyCode::Right => "ArrowRight".to_owned(),
+        KeyCode::Tab | KeyCode::BackTab => "Tab".to_owned(),
+        KeyCode::Esc => "Escape".to_owned(),
+        KeyCode::F(n) => format!("F{n}"),
+        other => format!("{other:?}"),
+    };
+    Code::from_str(&name).ok()
+}
+
+/// Read and Apply Keypresses until the Session Completes
+fn event_loop(terminal: &mut Term, session: &mut Session) -> io::Result<Outcome> {
+    loop {
+        terminal.draw(|f| draw(f, session))?;
+        // only retitle the terminal for templated titles
+        let templated = session.config().window.title.contains('{');
+        if let Some(title) = session.changed_title().filter(|_| templated) {
+            crossterm::execute!(terminal.backend_mut(), SetTitle(title))?;
+        }
+        if let Some(outcome) = session.poll_controls() {
+            return Ok(outcome);
+        }
+        // wake periodically to apply remote-control commands
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        // apply configured keybinds before editing the search
+        let mods = convert_mods(&key.modifiers);
+        if let Some(code) = convert_key(&key.code) {
+            let bound = key_event(&session.config().keybinds, &mods, &code).is_some();
+            if bound || session.is_back(&code) || session.letter_event(&mods, &code).is_some() {
+                match session.press(&mods, &code) {
+                    Some(outcome) => return Ok(outcome),
+                    None => continue,
+                }
+            }
+        }
+        if session.config().search.disable {
+            continue;
+        }
+        let mut search = session.edit_buffer();
+        match key.code {
+            KeyCode::Char(c) if !mods.intersects(Modifiers::CONTROL | Modifiers::ALT) => {
+                search.push(c)
+            }
+            KeyCode::Backspace => {
+                search.pop();
+            }
+            _ => continue,
+        }
+        session.set_search(std::mem::take(&mut *search));
+    }
+}
+
+/// Render Search Prompt, Results and Status Line
+fn draw(f: &mut Frame, session: &mut Session) {
+    let disable_search = session.config().search.disable;
+    let prompt_height = match disable_search {
+        true => 0,
+        false => 3,
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(prompt_height),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .split(f.size());
+    // render search prompt
+    let config = session.config();
+    let search = session.search();
+    let shown = match config.search.password {
+        true => "*".repeat(search.chars().count()),
+        false => search.to_owned(),
+    };
+    let label = match crate::frontend::prompt(config) {
+        label if label.is_empty() => label,
+        label => format!("{label} "),
+    };
+    let prompt = match search.is_empty() {
+        true => Paragraph::new(Line::from(vec![
+            Span::raw(label.clone()),
+            Span::styled(
+                config.search.placeholder.clone().unwrap_or_default(),
+                Style::default().add_modifier(Modifier::DIM),
+            ),
+        ])),
+        false => Paragraph::new(format!("{label}{shown}")),
+    };
+    let crumbs = session.breadcrumbs().join(" › ");
+    if !disable_search {
+        f.render_widget(
+            prompt.block(Block::default().borders(Borders::ALL).title(crumbs)),
+            chunks[0],
+        );
+        f.set_cursor(
+            chunks[0].x + 1 + (label.chars().count() + search.chars().count()) as u16,
+            chunks[0].y + 1,
+        );
+    }
+    // render results and sub-actions of the selected entry
+    let use_comments = config.use_comments;
+    let scroll_margin = config.scroll_margin;
+    let display = config.display.clone();
+    let min = config.search.min_length.unwrap_or_default();
+    let (pos, subpos) = session.position();
+    session.set_viewport(chunks[1].height as usize);
+    let mut items = vec![];
+    let mut selected = None;
+    for (i, (entry, marked)) in session.marked_results().into_iter().enumerate() {
+        if i == pos {
+            selected = Some(items.len() + subpos);
+        }
+        let mark = match marked {
+            true => "+ ",
+            false => "",
+        };
+        let name = display.name(&entry.name);
+        let text = match (use_comments, entry.comment.as_ref()) {
+            (true, Some(comment)) if display.two_line => {
+                format!("{mark}{name}\n  {}", display.comment(comment))
+            }
+            (true, Some(comment)) => format!("{mark}{name}  {}", display.comment(comment)),
+            _ => format!("{mark}{name}"),
+        };
+        items.push(ListItem::new(text));
+        if i == pos && subpos > 0 {
+            for action in entry.actions.iter().skip(1) {
+                items.push(ListItem::new(format!("    {}", action.name)));
+            }
+        }
+    }
+    let list = List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default();
+    state.select(selected);
+    // keep the configured number of rows visible below the highlight
+    if let Some(selected) = selected {
+        let height = chunks[1].height as usize;
+        *state.offset_mut() = (selected + scroll_margin + 1).saturating_sub(height);
+    }
+    f.render_stateful_widget(list, chunks[1], &mut state);
+    // rows before the selection map 1:1 to results, sub-actions only follow it
+    session.set_top(state.offset().min(pos));
+    crate::instance::publish(session.status());
+    // render status line
+    let status = match (session.missing(), session.hidden()) {
+        (0, 0) => String::new(),
+        (0, hidden) => format!("{hidden} more results hidden — refine your search"),
+        _ => format!("type at least {min} characters"),
+    };
+    let status = Paragraph::new(status).style(Style::default().add_modifier(Modifier::DIM));
+    f.render_widget(status, chunks[2]);
+}
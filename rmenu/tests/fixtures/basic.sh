@@ -0,0 +1,8 @@
+#!/bin/sh
+# fake plugin emitting a small fixed set of entries
+
+echo '{"type":"entry","name":"Firefox","actions":[{"name":"main","exec":{"run":"firefox"},"comment":null}],"comment":"Web Browser","icon":null,"icon_alt":null}'
+echo '{"type":"entry","name":"Vim","actions":[{"name":"main","exec":{"run":"gvim"},"comment":null},{"name":"Terminal","exec":{"terminal":"vim"},"comment":null}],"comment":"Text Editor","icon":null,"icon_alt":null}'
+echo '{"type":"entry","name":"Emacs","actions":[{"name":"main","exec":{"run":"emacs"},"comment":null}],"comment":"Extensible Editor","icon":null,"icon_alt":null}'
+echo '{"type":"entry","name":"Files","actions":[{"name":"main","exec":{"run":"nautilus"},"comment":null}],"comment":"Browse the Filesystem","icon":null,"icon_alt":null}'
+echo '{"type":"entry","name":"Hello","actions":[{"name":"main","exec":{"echo":"hello world"},"comment":null}],"comment":null,"icon":null,"icon_alt":null}'
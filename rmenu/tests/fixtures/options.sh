@@ -0,0 +1,6 @@
+#!/bin/sh
+# fake plugin overriding options before emitting entries
+
+echo '{"type":"options","placeholder":"pick one","key_move_next":["Tab"]}'
+echo '{"type":"entry","name":"foo","actions":[{"name":"main","exec":{"echo":"foo"},"comment":null}],"comment":null,"icon":null,"icon_alt":null}'
+echo '{"type":"entry","name":"bar","actions":[{"name":"main","exec":{"echo":"bar"},"comment":null}],"comment":null,"icon":null,"icon_alt":null}'
@@ -0,0 +1,8 @@
+#!/bin/sh
+# fake service plugin publishing a single batch until asked to shut down
+
+echo '{"type":"entry","name":"Service","actions":[{"name":"main","exec":{"echo":"service"},"comment":null}],"comment":null,"icon":null,"icon_alt":null}'
+echo ''
+read line
+[ "$line" = "shutdown" ] && exit 0
+exit 1
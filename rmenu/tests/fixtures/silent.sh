@@ -0,0 +1,4 @@
+#!/bin/sh
+# fake streaming plugin reading searches w/o ever answering them
+
+cat > /dev/null